@@ -1,3 +1,5 @@
+use clap::{CommandFactory, FromArgMatches, parser::ValueSource};
+
 pub use clap::Parser;
 pub use gastimator::prelude::*;
 
@@ -9,41 +11,460 @@ pub use gastimator::prelude::*;
     long_about = "Estimates the Gas cost of a transaction on the Ethereum network."
 )]
 pub(crate) struct Cli {
+    /// A JSON config file, see `ConfigFile`, its values are used for flags
+    /// which are not passed, flags passed take precedence.
+    #[arg(long = "config")]
+    pub(crate) config: Option<std::path::PathBuf>,
+
     /// The address of the server
-    #[arg(short = 'a', long = "address", default_value = "0.0.0.0")]
+    #[arg(short = 'a', long = "address", default_value = DEFAULT_ADDRESS)]
     pub(crate) address: String,
 
     /// The port our program is running on.
     /// Valid values are 0-65535 (TCP standard range).
-    #[arg(short = 'p', long = "port", default_value_t = 3000)]
+    #[arg(short = 'p', long = "port", default_value_t = DEFAULT_PORT)]
     pub(crate) port: u16,
 
+    /// The API key for the Alchemy API, read from the environment variable
+    /// `ALCHEMY_API_KEY` if not passed, not needed if `--rpc-url` is passed.
     #[arg(short = 'k', long = "key", default_value = None)]
     pub(crate) alchemy_api_key: Option<String>,
+
+    /// A file to read the API key for the Alchemy API from, trimming
+    /// whitespace, e.g. a mounted Docker or Kubernetes secret, so that the
+    /// key does not leak into process listings. Read from the file at the
+    /// path of the environment variable `ALCHEMY_API_KEY_FILE` if not passed.
+    #[arg(long = "key-file", conflicts_with = "alchemy_api_key")]
+    pub(crate) alchemy_api_key_file: Option<std::path::PathBuf>,
+
+    /// The full URL of a JSON-RPC endpoint used for remote estimates instead
+    /// of Alchemy, e.g. `http://my-node:8545`.
+    #[arg(long = "rpc-url", default_value = None)]
+    pub(crate) rpc_url: Option<String>,
+
+    /// A token sent as `Authorization: Bearer <token>` header in requests
+    /// to `--rpc-url`.
+    #[arg(long = "rpc-bearer-token", default_value = None, requires = "rpc_url")]
+    pub(crate) rpc_bearer_token: Option<String>,
+
+    /// The strategy for ids of JSON-RPC requests to the remote, `sequential`
+    /// or `random`, the latter avoids ids colliding across restarts.
+    #[arg(long = "rpc-id-strategy", default_value = "sequential", value_parser = parse_id_strategy)]
+    pub(crate) rpc_id_strategy: IdStrategy,
+
+    /// The block the remote estimates transactions at, `latest`, `pending`,
+    /// `safe`, `finalized` or a block number, e.g. `22000000` or `0x14fb180`.
+    #[arg(long = "remote-block", default_value = "latest", value_parser = parse_block)]
+    pub(crate) remote_block: BlockNumberOrTag,
+
+    /// The id of the chain to estimate transactions for, e.g. `1` for
+    /// Ethereum mainnet, `10` for Optimism, `8453` for Base.
+    #[arg(short = 'c', long = "chain-id", default_value_t = MAINNET_CHAIN_ID)]
+    pub(crate) chain_id: u64,
+
+    /// The network to estimate transactions for by name instead of chain id,
+    /// also selecting the Alchemy API of the network, e.g. `sepolia`,
+    /// `polygon` or `base_sepolia`.
+    #[arg(short = 'n', long = "network", conflicts_with = "chain_id", value_parser = parse_network)]
+    pub(crate) network: Option<AlchemyNetwork>,
+
+    /// The maximum duration in milliseconds of a request to the remote gas
+    /// estimator, after which the local estimate is used.
+    #[arg(long = "remote-timeout-ms", default_value_t = DEFAULT_REMOTE_TIMEOUT.as_millis() as u64)]
+    pub(crate) remote_timeout_in_millis: u64,
+
+    /// The maximum number of concurrent requests to the remote gas estimator,
+    /// excess requests wait until one completes, e.g. to stay within the
+    /// compute unit budget of Alchemy. Unlimited if not passed.
+    #[arg(long = "max-concurrent-remote-requests")]
+    pub(crate) max_concurrent_remote_requests: Option<usize>,
+
+    /// The number of estimates allowed to be computed concurrently, excess
+    /// estimates are queued, single requests before batch items.
+    #[arg(short = 'w', long = "workers", default_value_t = DEFAULT_WORKER_POOL_SIZE)]
+    pub(crate) worker_pool_size: usize,
+
+    /// The number of local simulations allowed to run concurrently, bounding
+    /// the CPU used by them. Defaults to the number of CPUs.
+    #[arg(long = "local-simulation-threads", default_value_t = default_local_simulation_threads())]
+    pub(crate) local_simulation_threads: usize,
+
+    /// The excess blob gas of the simulated block, determines the blob base
+    /// fee used to price blobs of EIP-4844 transactions.
+    #[arg(long = "excess-blob-gas", default_value_t = 0)]
+    pub(crate) excess_blob_gas: u64,
+
+    /// The EVM hardfork used for local simulation, e.g. `Shanghai`, `Cancun`
+    /// or `Prague`, defaults to the hardfork of the chain.
+    #[arg(long = "spec", value_parser = parse_spec)]
+    pub(crate) spec: Option<SpecId>,
+
+    /// Locally simulate transactions against a fork of the chain state at
+    /// the latest block, read over RPC, instead of against empty state.
+    #[arg(long = "fork", default_value_t = false)]
+    pub(crate) fork: bool,
+
+    /// The number of the block to fork at with `--fork`, instead of the
+    /// latest block, e.g. to reproduce the gas used of a mined transaction.
+    #[arg(long = "fork-block", requires = "fork")]
+    pub(crate) fork_block: Option<u64>,
+
+    /// Return the gas usage of an empty contract creation for empty
+    /// transactions (without `to`, `input` and `value`), instead of
+    /// rejecting them.
+    #[arg(long = "estimate-empty-transactions", default_value_t = false)]
+    pub(crate) estimate_empty_transactions: bool,
+
+    /// Estimate by local simulation only, without any remote gas estimator,
+    /// in which case no Alchemy API key or RPC URL is needed (unless `--fork`).
+    #[arg(long = "local-only", default_value_t = false)]
+    pub(crate) local_only: bool,
+
+    /// Estimate by the remote gas estimator only, without local simulation,
+    /// e.g. where running the EVM is not wanted.
+    #[arg(long = "remote-only", default_value_t = false, conflicts_with_all = ["local_only", "fork"])]
+    pub(crate) remote_only: bool,
+
+    /// The fraction, between `0.0` and `1.0`, of cacheable transactions for
+    /// which the remote gas estimator is called, the rest are estimated by
+    /// local simulation only. Ignored with `--remote-only`.
+    #[arg(long = "remote-sample-rate", default_value_t = 1.0, value_parser = parse_fraction)]
+    pub(crate) remote_sample_rate: f64,
+
+    /// The time to live in seconds of cached estimates, after which they
+    /// are recomputed.
+    #[arg(long = "cache-ttl-secs", default_value_t = DEFAULT_CACHE_TTL.as_secs())]
+    pub(crate) cache_ttl_in_secs: u64,
+
+    /// How long in seconds responses are stored by the `Idempotency-Key`
+    /// header of their request, retries with the same key within it are
+    /// answered with the stored response, `0` to not store responses.
+    #[arg(long = "idempotency-ttl-secs", default_value_t = DEFAULT_IDEMPOTENCY_TTL.as_secs())]
+    pub(crate) idempotency_ttl_in_secs: u64,
+
+    /// Also cache local estimates by `to`, `value`, `input` and access list
+    /// only, so that transactions without `nonce` or `from` are cached too,
+    /// less precise than the cache keyed by the entire transaction.
+    #[arg(long = "kind-stable-cache", default_value_t = false)]
+    pub(crate) kind_stable_cache: bool,
+
+    /// The maximum number of entries (addresses plus storage keys) of the
+    /// precomputed access list of a transaction, larger ones are rejected.
+    #[arg(long = "max-access-list-entries", default_value_t = DEFAULT_MAX_ACCESS_LIST_ENTRIES)]
+    pub(crate) max_access_list_entries: usize,
+
+    /// The maximum gas limit of a transaction, e.g. the block gas limit,
+    /// transactions with a higher gas limit are rejected. Uncapped if not set.
+    #[arg(long = "max-allowed-gas-limit")]
+    pub(crate) max_allowed_gas_limit: Option<u64>,
+
+    /// The factor by which the gas limit of a transaction may exceed its
+    /// estimate before the response carries a warning.
+    #[arg(long = "gas-limit-warning-factor", default_value_t = DEFAULT_GAS_LIMIT_WARNING_FACTOR)]
+    pub(crate) gas_limit_warning_factor: f64,
+
+    /// The weight, between `0.0` and `1.0`, of the remote estimate in the
+    /// recommended gas of a range, the local estimate has the remaining weight.
+    #[arg(long = "remote-weight", default_value_t = DEFAULT_REMOTE_WEIGHT, value_parser = parse_fraction)]
+    pub(crate) remote_weight: f64,
+
+    /// How the recommended gas of a range is picked from the local and remote
+    /// estimates, one of `weighted` (by `--remote-weight`), `prefer_local`,
+    /// `prefer_remote`, `min`, `max` or `average`.
+    #[arg(long = "estimate-policy", default_value = "weighted", value_parser = parse_estimate_policy)]
+    pub(crate) estimate_policy: EstimatePolicy,
+
+    /// The factor estimates are padded with, e.g. `1.1` for +10%, capped by
+    /// the gas limit of the transaction.
+    #[arg(long = "safety-margin", default_value_t = DEFAULT_SAFETY_MARGIN)]
+    pub(crate) safety_margin: f64,
+
+    /// The end-to-end budget in milliseconds of an estimate, shared by the
+    /// local simulation and the remote estimate, when exhausted the local
+    /// estimate is returned. Unlimited if not set.
+    #[arg(long = "total-budget-ms")]
+    pub(crate) total_budget_in_millis: Option<u64>,
+
+    /// Optional route to not mount, can be repeated, one of `batch`, `health`,
+    /// `ready`, `cache_stats`, `simulate`, `cache_clear`, `replace`,
+    /// `metrics`, `classify`, `call`, `stream`, `ws`, `rlp_raw`, `by_hash`,
+    /// `accuracy`, `rlp_verify`, `bundle`, `stats` or `deploy`. The core routes `/tx` and `/rlp` are always mounted.
+    #[arg(long = "disable-route", value_parser = parse_route)]
+    pub(crate) disabled_routes: Vec<OptionalRoute>,
+
+    /// A token required as `Authorization: Bearer <token>` header by admin
+    /// endpoints, e.g. `POST /cache/clear`. If not passed admin endpoints
+    /// are not mounted.
+    #[arg(long = "admin-token", default_value = None)]
+    pub(crate) admin_token: Option<String>,
+
+    /// The maximum duration in milliseconds of handling a request, after
+    /// which `504 Gateway Timeout` is returned.
+    #[arg(long = "request-timeout-ms", default_value_t = DEFAULT_REQUEST_TIMEOUT.as_millis() as u64)]
+    pub(crate) request_timeout_in_millis: u64,
+
+    /// An origin browsers are allowed to call the server from, e.g.
+    /// `https://app.example.com`, can be repeated, `*` allows any origin.
+    /// If not passed any origin is allowed in debug builds, none in release.
+    #[arg(long = "allowed-origin")]
+    pub(crate) allowed_origins: Vec<String>,
+
+    /// The maximum size in bytes of a request body, larger requests are
+    /// rejected with `413 Payload Too Large`. For `/tx/stream` it also limits
+    /// the length of each line.
+    #[arg(long = "max-body-bytes", default_value_t = DEFAULT_MAX_BODY_BYTES)]
+    pub(crate) max_body_bytes: usize,
+
+    /// The maximum length in bytes of the RLP of a raw transaction, longer
+    /// ones are rejected with `InvalidTransaction` without being decoded.
+    #[arg(long = "max-rlp-bytes", default_value_t = DEFAULT_MAX_RLP_BYTES)]
+    pub(crate) max_rlp_bytes: usize,
+
+    /// The maximum number of requests per second of each client IP, more
+    /// frequent requests are rejected with `429 Too Many Requests`. If not
+    /// passed requests are not rate limited.
+    #[arg(long = "rate-limit")]
+    pub(crate) rate_limit: Option<f64>,
+
+    /// The number of requests a client IP may make at once before being
+    /// limited to `--rate-limit`, defaults to one second worth of requests.
+    #[arg(long = "rate-limit-burst", requires = "rate_limit")]
+    pub(crate) rate_limit_burst: Option<u32>,
+
+    /// The format of log lines, `text` or `json`, one JSON object per line
+    /// with fields as keys, e.g. for log aggregation.
+    #[arg(long = "log-format", env = "LOG_FORMAT", default_value = "text", value_parser = parse_log_format)]
+    pub(crate) log_format: LogFormat,
+
+    /// An OpenTelemetry collector, e.g. `http://localhost:4318`, metrics
+    /// are periodically pushed to over OTLP.
+    #[cfg(feature = "otel")]
+    #[arg(long = "otlp-endpoint")]
+    pub(crate) otlp_endpoint: Option<String>,
+}
+
+impl Cli {
+    /// Parses the CLI arguments, with the values of the `--config` file, if
+    /// passed, used for flags which were not passed.
+    ///
+    /// # Throws
+    /// Throws [`Error::FailedParseCliArgs`] if the arguments are invalid and
+    /// [`Error::ReadConfigFile`] or [`Error::InvalidConfigFile`] if the
+    /// config file is.
+    pub(crate) fn parse_with_config_file() -> Result<Self> {
+        let matches = Self::command().get_matches();
+        let mut args = Self::from_arg_matches(&matches).map_err(|e| Error::FailedParseCliArgs {
+            underlying: e.to_string(),
+        })?;
+        if let Some(path) = &args.config {
+            let file = ConfigFile::from_file(path)?;
+            args.merge(file, |id| {
+                matches.value_source(id) == Some(ValueSource::CommandLine)
+            });
+        }
+        Ok(args)
+    }
+
+    /// Replaces values with those of `file` for each flag not `passed`.
+    fn merge(&mut self, file: ConfigFile, passed: impl Fn(&str) -> bool) {
+        fn merge<T>(value: &mut T, from_file: Option<T>, passed: bool) {
+            if let Some(from_file) = from_file.filter(|_| !passed) {
+                *value = from_file;
+            }
+        }
+        let chain_passed = passed("chain_id") || passed("network");
+        merge(&mut self.address, file.address().clone(), passed("address"));
+        merge(&mut self.port, file.port(), passed("port"));
+        merge(
+            &mut self.alchemy_api_key,
+            file.alchemy_api_key().clone().map(Some),
+            passed("alchemy_api_key") || passed("alchemy_api_key_file"),
+        );
+        merge(
+            &mut self.rpc_url,
+            file.rpc_url().clone().map(Some),
+            passed("rpc_url"),
+        );
+        merge(
+            &mut self.rpc_bearer_token,
+            file.rpc_bearer_token().clone().map(Some),
+            passed("rpc_bearer_token"),
+        );
+        merge(&mut self.chain_id, file.chain_id(), chain_passed);
+        merge(&mut self.network, file.network().map(Some), chain_passed);
+        merge(
+            &mut self.remote_timeout_in_millis,
+            file.remote_timeout_ms(),
+            passed("remote_timeout_in_millis"),
+        );
+        merge(
+            &mut self.request_timeout_in_millis,
+            file.request_timeout_ms(),
+            passed("request_timeout_in_millis"),
+        );
+        merge(
+            &mut self.cache_ttl_in_secs,
+            file.cache_ttl_secs(),
+            passed("cache_ttl_in_secs"),
+        );
+        merge(
+            &mut self.admin_token,
+            file.admin_token().clone().map(Some),
+            passed("admin_token"),
+        );
+        merge(
+            &mut self.allowed_origins,
+            file.allowed_origins().clone(),
+            passed("allowed_origins"),
+        );
+    }
+}
+
+fn parse_spec(s: &str) -> Result<SpecId, String> {
+    s.parse::<SpecId>()
+        .map_err(|_| format!("Unknown hardfork: '{s}'"))
+}
+
+fn parse_route(s: &str) -> Result<OptionalRoute, String> {
+    s.parse::<OptionalRoute>().map_err(|e| e.to_string())
+}
+
+fn parse_id_strategy(s: &str) -> Result<IdStrategy, String> {
+    s.parse::<IdStrategy>().map_err(|e| e.to_string())
+}
+
+fn parse_estimate_policy(s: &str) -> Result<EstimatePolicy, String> {
+    s.parse::<EstimatePolicy>().map_err(|e| e.to_string())
+}
+
+fn parse_log_format(s: &str) -> Result<LogFormat, String> {
+    s.parse::<LogFormat>().map_err(|e| e.to_string())
+}
+
+fn parse_block(s: &str) -> Result<BlockNumberOrTag, String> {
+    s.parse::<u64>()
+        .map(BlockNumberOrTag::Number)
+        .or_else(|_| s.parse::<BlockNumberOrTag>())
+        .map_err(|e| format!("Invalid block '{s}': {e}"))
+}
+
+fn parse_network(s: &str) -> Result<AlchemyNetwork, String> {
+    s.parse::<AlchemyNetwork>().map_err(|e| e.to_string())
+}
+
+fn parse_fraction(s: &str) -> Result<f64, String> {
+    let fraction = s
+        .parse::<f64>()
+        .map_err(|_| format!("Not a number: '{s}'"))?;
+    if (0.0..=1.0).contains(&fraction) {
+        Ok(fraction)
+    } else {
+        Err(format!("Must be between 0.0 and 1.0, got: {fraction}"))
+    }
+}
+
+impl From<&Cli> for GastimatorConfig {
+    fn from(args: &Cli) -> Self {
+        GastimatorConfigBuilder::default()
+            .worker_pool_size(args.worker_pool_size)
+            .local_simulation_threads(args.local_simulation_threads)
+            .excess_blob_gas(args.excess_blob_gas)
+            .spec(args.spec)
+            .fork(args.fork)
+            .fork_block(args.fork_block)
+            .empty_transaction_policy(if args.estimate_empty_transactions {
+                EmptyTransactionPolicy::MinimalEstimate
+            } else {
+                EmptyTransactionPolicy::Reject
+            })
+            .mode(if args.local_only {
+                GastimatorMode::LocalOnly
+            } else if args.remote_only {
+                GastimatorMode::RemoteOnly
+            } else {
+                GastimatorMode::Both
+            })
+            .remote_sample_rate(args.remote_sample_rate)
+            .cache_ttl(Duration::from_secs(args.cache_ttl_in_secs))
+            .idempotency_ttl(Duration::from_secs(args.idempotency_ttl_in_secs))
+            .kind_stable_cache(args.kind_stable_cache)
+            .max_access_list_entries(args.max_access_list_entries)
+            .total_budget(args.total_budget_in_millis.map(Duration::from_millis))
+            .max_allowed_gas_limit(args.max_allowed_gas_limit.map(Gas::from))
+            .gas_limit_warning_factor(args.gas_limit_warning_factor)
+            .remote_weight(args.remote_weight)
+            .estimate_policy(args.estimate_policy)
+            .safety_margin(args.safety_margin)
+            .build()
+            .unwrap()
+    }
 }
 
 impl From<Cli> for ServerConfig {
     fn from(args: Cli) -> Self {
-        ServerConfigBuilder::default()
+        let enabled_routes = OptionalRoute::ALL
+            .into_iter()
+            .filter(|route| !args.disabled_routes.contains(route))
+            .collect::<std::collections::HashSet<_>>();
+        let mut builder = ServerConfigBuilder::default();
+        builder
             .address(args.address)
             .port(args.port)
-            .build()
-            .unwrap()
+            .enabled_routes(enabled_routes)
+            .admin_token(args.admin_token)
+            .request_timeout(Duration::from_millis(args.request_timeout_in_millis))
+            .max_body_bytes(args.max_body_bytes)
+            .max_rlp_bytes(args.max_rlp_bytes)
+            .log_format(args.log_format)
+            .rate_limit(args.rate_limit.map(|per_second| {
+                args.rate_limit_burst.map_or_else(
+                    || RateLimit::with_one_second_burst(per_second),
+                    |burst| RateLimit::new(per_second, burst),
+                )
+            }));
+        if !args.allowed_origins.is_empty() {
+            builder.allowed_origins(AllowedOrigins::new(args.allowed_origins));
+        }
+        #[cfg(feature = "otel")]
+        builder.otlp_endpoint(args.otlp_endpoint);
+        builder.build().unwrap()
     }
 }
 
 impl TryFrom<Cli> for Config {
     type Error = Error;
     fn try_from(args: Cli) -> Result<Self> {
-        let alchemy_api_key = args
-            .alchemy_api_key
-            .clone()
-            .or_else(|| read_alchemy_api_key().ok())
-            .ok_or(Error::NoAlchemyApiKey)?;
+        let alchemy_api_key = match (&args.alchemy_api_key, &args.alchemy_api_key_file) {
+            (Some(key), _) => Some(key.clone()),
+            (None, Some(path)) => Some(read_alchemy_api_key_file(path)?),
+            (None, None) => read_optional_alchemy_api_key()?,
+        };
+        if alchemy_api_key.is_none() && args.rpc_url.is_none() && !args.local_only {
+            return Err(Error::NoAlchemyApiKey);
+        }
+        let rpc_url = args.rpc_url.clone();
+        let rpc_bearer_token = args.rpc_bearer_token.clone();
+        let rpc_id_strategy = args.rpc_id_strategy;
+        let remote_block = args.remote_block;
+        let gastimator_config = GastimatorConfig::from(&args);
+        let chain_id = args
+            .network
+            .map(|network| network.chain_id())
+            .unwrap_or(args.chain_id);
+        let remote_timeout = Duration::from_millis(args.remote_timeout_in_millis);
+        let max_concurrent_remote_requests = args.max_concurrent_remote_requests;
         let server_config = ServerConfig::from(args);
         Ok(ConfigBuilder::default()
             .server(server_config)
             .alchemy_api_key(alchemy_api_key)
+            .rpc_url(rpc_url)
+            .rpc_bearer_token(rpc_bearer_token)
+            .rpc_id_strategy(rpc_id_strategy)
+            .remote_block(remote_block)
+            .chain_id(chain_id)
+            .remote_timeout(remote_timeout)
+            .max_concurrent_remote_requests(max_concurrent_remote_requests)
+            .gastimator(gastimator_config)
             .build()
             .unwrap())
     }
@@ -4,7 +4,7 @@ use gastimator_rest::run;
 
 #[tokio::main]
 async fn main() {
-    let args = Cli::parse();
+    let args = Cli::parse_with_config_file().unwrap_display();
     let config = &Config::try_from(args).unwrap_display();
     run(config).await;
 }
@@ -0,0 +1,33 @@
+//! Estimates the gas usage of a transaction in-process, without the REST
+//! server. Uses the Alchemy API as remote gas estimator if `ALCHEMY_API_KEY`
+//! (or `ALCHEMY_API_KEY_FILE`) is set, else estimates by local simulation only.
+//!
+//! ```sh
+//! cargo run -p gastimator --example embed
+//! ```
+use gastimator::prelude::*;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let gastimator = match read_optional_alchemy_api_key()? {
+        Some(api_key) => Gastimator::new(api_key),
+        None => Gastimator::builder()
+            .local_gas_estimator(
+                RevmTxSimulator::builder()
+                    .chain_id(MAINNET_CHAIN_ID)
+                    .build(),
+            )
+            .build(),
+    };
+
+    // A transfer of 1 wei.
+    let tx = TransactionBuilder::default()
+        .to(Address::repeat_byte(0xab))
+        .value(U256::from(1))
+        .build()
+        .unwrap();
+
+    let response = gastimator.estimate_gas(tx).await?;
+    println!("{}", response.gas_usage());
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use crate::prelude::*;
+
+/// A network supported by the Alchemy API, its subdomain is used in the URL
+/// of the API, e.g. `eth-sepolia` in `https://eth-sepolia.g.alchemy.com/v2`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlchemyNetwork {
+    /// Ethereum mainnet, chain id `1`.
+    Mainnet,
+    /// Ethereum Sepolia testnet, chain id `11155111`.
+    Sepolia,
+    /// Ethereum Holesky testnet, chain id `17000`.
+    Holesky,
+    /// Optimism mainnet, chain id `10`.
+    Optimism,
+    /// Optimism Sepolia testnet, chain id `11155420`.
+    OptimismSepolia,
+    /// Base mainnet, chain id `8453`.
+    Base,
+    /// Base Sepolia testnet, chain id `84532`.
+    BaseSepolia,
+    /// Arbitrum One, chain id `42161`.
+    Arbitrum,
+    /// Arbitrum Sepolia testnet, chain id `421614`.
+    ArbitrumSepolia,
+    /// Polygon PoS mainnet, chain id `137`.
+    Polygon,
+}
+
+// ========================================
+// Public Implementation
+// ========================================
+impl AlchemyNetwork {
+    /// All networks.
+    pub const ALL: [Self; 10] = [
+        Self::Mainnet,
+        Self::Sepolia,
+        Self::Holesky,
+        Self::Optimism,
+        Self::OptimismSepolia,
+        Self::Base,
+        Self::BaseSepolia,
+        Self::Arbitrum,
+        Self::ArbitrumSepolia,
+        Self::Polygon,
+    ];
+
+    /// The name of the network, as accepted by `FromStr`, e.g. `"sepolia"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Mainnet => "mainnet",
+            Self::Sepolia => "sepolia",
+            Self::Holesky => "holesky",
+            Self::Optimism => "optimism",
+            Self::OptimismSepolia => "optimism_sepolia",
+            Self::Base => "base",
+            Self::BaseSepolia => "base_sepolia",
+            Self::Arbitrum => "arbitrum",
+            Self::ArbitrumSepolia => "arbitrum_sepolia",
+            Self::Polygon => "polygon",
+        }
+    }
+
+    /// The subdomain of the Alchemy API of the network, e.g. `"eth-sepolia"`.
+    pub fn subdomain(&self) -> &'static str {
+        match self {
+            Self::Mainnet => "eth-mainnet",
+            Self::Sepolia => "eth-sepolia",
+            Self::Holesky => "eth-holesky",
+            Self::Optimism => "opt-mainnet",
+            Self::OptimismSepolia => "opt-sepolia",
+            Self::Base => "base-mainnet",
+            Self::BaseSepolia => "base-sepolia",
+            Self::Arbitrum => "arb-mainnet",
+            Self::ArbitrumSepolia => "arb-sepolia",
+            Self::Polygon => "polygon-mainnet",
+        }
+    }
+
+    /// The id of the chain of the network, e.g. `11155111` for Sepolia.
+    pub fn chain_id(&self) -> u64 {
+        match self {
+            Self::Mainnet => MAINNET_CHAIN_ID,
+            Self::Sepolia => 11155111,
+            Self::Holesky => 17000,
+            Self::Optimism => 10,
+            Self::OptimismSepolia => 11155420,
+            Self::Base => 8453,
+            Self::BaseSepolia => 84532,
+            Self::Arbitrum => 42161,
+            Self::ArbitrumSepolia => 421614,
+            Self::Polygon => 137,
+        }
+    }
+
+    /// The network of the chain with id `chain_id`, or `None` if Alchemy
+    /// does not support the chain.
+    pub fn from_chain_id(chain_id: u64) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|network| network.chain_id() == chain_id)
+    }
+
+    /// The URL of the Alchemy API of the network, with `api_key` in the
+    /// path, e.g. `"https://eth-sepolia.g.alchemy.com/v2/<API_KEY>"`.
+    pub fn url(&self, api_key: impl AsRef<str>) -> String {
+        format!(
+            "https://{}.g.alchemy.com/v2/{}",
+            self.subdomain(),
+            api_key.as_ref()
+        )
+    }
+}
+
+// ========================================
+// FromStr Implementation
+// ========================================
+impl std::str::FromStr for AlchemyNetwork {
+    type Err = Error;
+
+    /// Parses either the name, e.g. `"base_sepolia"`, or the subdomain, e.g.
+    /// `"base-sepolia"`, of a network.
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|network| network.name() == s || network.subdomain() == s)
+            .ok_or_else(|| Error::UnknownAlchemyNetwork {
+                bad_value: s.to_owned(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = AlchemyNetwork;
+
+    #[test]
+    fn chain_id_round_trip() {
+        for network in Sut::ALL {
+            assert_eq!(Sut::from_chain_id(network.chain_id()), Some(network));
+        }
+        assert_eq!(Sut::from_chain_id(1337), None);
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!("sepolia".parse::<Sut>(), Ok(Sut::Sepolia));
+        assert_eq!("polygon-mainnet".parse::<Sut>(), Ok(Sut::Polygon));
+        assert_eq!(
+            "ropsten".parse::<Sut>(),
+            Err(Error::UnknownAlchemyNetwork {
+                bad_value: "ropsten".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn name_matches_serde() {
+        for network in Sut::ALL {
+            assert_eq!(
+                serde_json::to_value(network).unwrap(),
+                serde_json::json!(network.name())
+            );
+        }
+    }
+}
@@ -1,30 +1,14 @@
 use crate::prelude::*;
 
-/// The url of the Alchemy Ethereum API
-const ALCHEMY_ETHEREUM_BASE_URL: &str = "https://eth-mainnet.g.alchemy.com/v2";
-
-/// The error message returned by Alchemy when the gas limit is exceeded
-/// when estimating gas for a transaction. Unfortunately, the Alchemy API
-/// does not return a proper error code, so we have to rely on the error message
-/// to determine if the gas limit was exceeded. A bit hacky, but it works.
-const ALCHEMY_GAS_USE_EXCEEDS_LIMIT_ERROR: &str = "gas required exceeds allowance";
-
 /// Alchemy RPC client for estimating gas costs
 ///
 /// It implements the `RemoteGasEstimator` trait, which allows it to be used
 /// as a gas estimator in the `GasEstimator` struct.
 pub struct AlchemyRpcClient {
-    /// The API key for the Alchemy Ethereum API, typically read
-    /// from the environment variable `ALCHEMY_API_KEY`.
-    api_key: String,
-
-    /// An underlying HTTP client for making requests to the Alchemy API,
-    /// using the reqwest library.
-    /// This client is used to send JSON-RPC requests to the Alchemy API.
-    client: reqwest::Client,
-
-    /// A helper which generates unique request IDs for each JSON-RPC request.
-    id_stepper: IdStepper,
+    /// The underlying JSON-RPC client, using the URL of the Alchemy API of
+    /// the network, see [`AlchemyNetwork::url`], with the API key in the path,
+    /// e.g. `"https://eth-mainnet.g.alchemy.com/v2/<API_KEY>"`.
+    rpc: GenericRpcClient,
 }
 
 // ========================================
@@ -36,204 +20,137 @@ impl AlchemyRpcClient {
     ///
     /// # Parameters
     /// - `api_key`: The API key for the Alchemy Ethereum API. You can
-    /// pass either a `String` or a string slice (`&str`).
+    ///   pass either a `String` or a string slice (`&str`).
     ///
     /// # Returns
     /// A new instance of `AlchemyRpcClient`.
     pub fn new(api_key: impl AsRef<str>) -> Self {
-        Self {
-            api_key: api_key.as_ref().to_owned(),
-            client: reqwest::Client::default(),
-            id_stepper: IdStepper::default(),
-        }
+        Self::for_network(api_key, AlchemyNetwork::Mainnet)
     }
-}
 
-// ========================================
-// Private Implementation
-// ========================================
+    /// Creates a new Alchemy RPC client with the given API key, for the chain
+    /// with id `chain_id`.
+    ///
+    /// # Throws
+    /// Throws [`Error::UnsupportedAlchemyChain`] if Alchemy does not support
+    /// the chain, see [`AlchemyNetwork::from_chain_id`].
+    pub fn for_chain(api_key: impl AsRef<str>, chain_id: u64) -> Result<Self> {
+        let network = AlchemyNetwork::from_chain_id(chain_id)
+            .ok_or(Error::UnsupportedAlchemyChain { chain_id })?;
+        Ok(Self::for_network(api_key, network))
+    }
 
-impl AlchemyRpcClient {
-    /// Formats the URL for the Alchemy Ethereum API using the provided API key.
-    fn url(&self) -> String {
-        format!("{}/{}", ALCHEMY_ETHEREUM_BASE_URL, self.api_key)
+    /// Creates a new Alchemy RPC client with the given API key, for `network`.
+    pub fn for_network(api_key: impl AsRef<str>, network: AlchemyNetwork) -> Self {
+        Self {
+            rpc: GenericRpcClient::new(network.url(api_key)),
+        }
     }
 
-    /// Calls the RPC method of the `Req::method()` using a single parameter
-    /// value. You can intercept the body of the response and pre-process it
-    /// before deserializing it into the `Res` type.
-    ///
-    /// # Parameters
-    /// - `param`: The parameter value to be passed to the RPC method.
-    /// - `utf8_body_interceptor`: A closure that takes the body of the response
-    ///   as a `Cow<str>` and returns an `Option<Result<Res>>`. If you return e.g.
-    /// `Some(Err(...))`, the function will return that error. If you don't want to
-    /// intercept the body, you can pass `|_| None` as the interceptor.
-    ///
-    /// # Returns
-    /// A `Result<Res>` containing the deserialized response or an error.
-    async fn call_single<Req, Res>(
-        &self,
-        param: Req,
-        utf8_body_interceptor: impl FnOnce(Cow<'_, str>) -> Option<Result<Res>>,
-    ) -> Result<Res>
-    where
-        Req: IsRpcRequest,
-        Req::Param: Clone,
-        Res: for<'de> Deserialize<'de>,
-        Req: TyEq<Req::Param>, // #20041
-    {
-        self.call::<Req, Res>([param.cast()], utf8_body_interceptor)
-            .await
+    /// Returns this client with requests to the Alchemy API timing out after
+    /// `timeout`, on timeout estimates fail with [`Error::Timeout`] with
+    /// `phase` [`EstimatePhase::Remote`].
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        Self {
+            rpc: self.rpc.with_timeout(timeout),
+        }
     }
 
-    /// Calls the RPC method of the `Req::method()` using with multiple parameters.
-    /// You can intercept the body of the response and pre-process it
-    /// before deserializing it into the `Res` type.
-    ///
-    /// # Parameters
-    /// - `params`: The parameter values to be passed to the RPC method.
-    /// - `utf8_body_interceptor`: A closure that takes the body of the response
-    ///   as a `Cow<str>` and returns an `Option<Result<Res>>`. If you return e.g.
-    /// `Some(Err(...))`, the function will return that error. If you don't want to
-    /// intercept the body, you can pass `|_| None` as the interceptor.
-    ///
-    /// # Returns
-    /// A `Result<Res>` containing the deserialized response or an error.
-    async fn call<Req, Res>(
-        &self,
-        params: impl IntoIterator<Item = Req::Param>,
-        utf8_body_interceptor: impl FnOnce(Cow<'_, str>) -> Option<Result<Res>>,
-    ) -> Result<Res>
-    where
-        Req: IsRpcRequest,
-        Req::Param: Clone,
-        Res: for<'de> Deserialize<'de>,
-    {
-        let id = self.id_stepper.next();
-        let method = Req::method();
-        let request = RpcRequestBuilder::default()
-            .params(params.into_iter().collect::<Vec<Req::Param>>())
-            .method(method.clone())
-            .id(id)
-            .build()
-            .unwrap();
+    /// Returns this client generating ids of JSON-RPC requests using
+    /// `strategy`, see [`IdStrategy`].
+    pub fn with_id_strategy(self, strategy: IdStrategy) -> Self {
+        Self {
+            rpc: self.rpc.with_id_strategy(strategy),
+        }
+    }
 
-        #[cfg(debug_assertions)]
-        {
-            let json = serde_json::to_string_pretty(&request).unwrap();
-            debug!("👻 Alchemy request JSON: {:?}", json);
+    /// Returns this client estimating transactions at `block`, e.g.
+    /// [`BlockNumberOrTag::Pending`], by default the latest block.
+    pub fn with_block(self, block: BlockNumberOrTag) -> Self {
+        Self {
+            rpc: self.rpc.with_block(block),
         }
+    }
 
-        let response = self
-            .client
-            .post(self.url())
-            .json(&request)
-            .send()
-            .await
-            .map_err(|_| Error::AlchemySendRequest { method })?;
+    /// Returns this client making at most `max` concurrent requests to the
+    /// Alchemy API, if set, smoothing bursts to stay within the compute unit
+    /// budget of the API key and avoid `429 Too Many Requests`.
+    pub fn with_max_concurrent_requests(self, max: impl Into<Option<usize>>) -> Self {
+        Self {
+            rpc: self.rpc.with_max_concurrent_requests(max),
+        }
+    }
 
-        let status = response.status();
-        info!("Alchemy response status: {:?}", status);
-        let body_bytes = response
-            .bytes()
-            .await
-            .map_err(Error::alchemy_read_bytes_of_response)?;
-        let body_string = String::from_utf8_lossy(&body_bytes);
+    /// Calls the `eth_gasPrice` method of the Alchemy API, returning the
+    /// current gas price (in wei).
+    pub async fn get_gas_price(&self) -> Result<U256> {
+        self.rpc.get_gas_price().await
+    }
 
-        // Print the response body as a debug string
-        #[cfg(debug_assertions)]
-        debug!(
-            "🔮 Alchemy RAW response: Status = {}, Body = {:?}",
-            status, body_string
-        );
+    /// Calls the `eth_maxPriorityFeePerGas` method of the Alchemy API,
+    /// returning the current max priority fee per gas (in wei).
+    pub async fn get_max_priority_fee_per_gas(&self) -> Result<U256> {
+        self.rpc.get_max_priority_fee_per_gas().await
+    }
+}
 
-        if let Some(intercepted) = utf8_body_interceptor(body_string) {
-            return intercepted;
-        }
+// ========================================
+// Private Implementation
+// ========================================
 
-        serde_json::from_slice(&body_bytes).map_err(|e| Error::AlchemyParseToResponseToType {
-            kind: std::any::type_name::<Res>().to_owned(),
-            underlying: format!("{:?}", e),
-        })
+impl AlchemyRpcClient {
+    /// The URL for the Alchemy API using the network and the provided
+    /// API key.
+    pub(crate) fn url(&self) -> String {
+        self.rpc.url().to_owned()
     }
+}
 
-    /// Calls the `eth_estimateGas` method of the Alchemy API to estimate the gas cost
-    /// for a given transaction.
-    ///
-    /// # Parameters
-    /// - `input`: The input parameters for the `eth_estimateGas` method. For more
-    /// info see [`AlchemyEstimateGasInput`].
-    ///
-    /// # Returns
-    /// A `Result<Gas>` containing the estimated gas cost or an error.
-    async fn get_gas_estimate(&self, input: AlchemyEstimateGasInput) -> Result<Gas> {
-        let gas_limit = *input.gas();
+// ========================================
+// RemoteGasEstimator Implementation
+// ========================================
 
-        let response: RpcResponse = self
-            .call_single(input, |body| {
-                if body.contains(ALCHEMY_GAS_USE_EXCEEDS_LIMIT_ERROR) {
-                    let gas_limit = gas_limit
-                        .expect("Should not have failed with gas required exceed limit if there is no limit")
-                        .try_into_u64()
-                        .expect("Gas limit should fit in a u64");
-                    let gas_limit = Gas::from(gas_limit);
-                    Some(Err(Error::GasExceedsLimit {
-                        estimated_cost: None,
-                        gas_limit,
-                    }))
-                } else {
-                    None
-                }
-            })
-            .await?;
+#[async_trait::async_trait]
+impl RemoteGasEstimator for AlchemyRpcClient {
+    async fn estimate_gas(&self, tx: &Transaction) -> Result<Gas> {
+        self.rpc.estimate_gas(tx).await
+    }
 
-        let response = &response.result_strip_0x();
-        let gas_used = u64::from_str_radix(response, 16).map_err(|_| Error::AlchemyParseAsU32)?;
-        info!(
-            "Successfully fetched gas estimate from Alchemy: {:?}",
-            gas_used
-        );
-        Ok(gas_used.into())
+    async fn estimate_gas_with_raw_response(
+        &self,
+        tx: &Transaction,
+    ) -> Result<(Gas, Option<String>)> {
+        self.rpc.estimate_gas_with_raw_response(tx).await
     }
 }
 
-/// A trait for converting a U256 value into a u64 value.
-///
-/// This is a custom trait because the `U256` type does not implement
-/// `TryInto<u64>` directly. And because we do not own the `U256` type,
-/// we cannot implement `TryInto<u64>` for it. So we create our own
-/// trait to provide this functionality.
-trait TryIntoU64 {
-    /// Attempts to convert a U256 value into a u64 value.
-    fn try_into_u64(self) -> Result<u64>;
-}
+// ========================================
+// TransactionSource Implementation
+// ========================================
 
-impl TryIntoU64 for U256 {
-    fn try_into_u64(self) -> Result<u64> {
-        if self > U256::from(u64::MAX) {
-            return Err(Error::UInt256LargerThanU64);
-        }
-        Ok(self.as_limbs()[0])
+#[async_trait::async_trait]
+impl TransactionSource for AlchemyRpcClient {
+    async fn transaction_by_hash(&self, hash: B256) -> Result<Transaction> {
+        self.rpc.get_transaction_by_hash(hash).await
+    }
+
+    async fn gas_used_by(&self, hash: B256) -> Result<Gas> {
+        self.rpc.get_gas_used_by(hash).await
     }
 }
 
 // ========================================
-// RemoteGasEstimator Implementation
+// FeeOracle Implementation
 // ========================================
 
 #[async_trait::async_trait]
-impl RemoteGasEstimator for AlchemyRpcClient {
-    async fn estimate_gas(&self, tx: &Transaction) -> Result<Gas> {
-        let tx = AlchemyEstimateGasInput::from(tx.clone());
-        self.get_gas_estimate(tx)
-            .await
-            .inspect_err(|e| {
-                error!("Error while fetching remote transaction: {e}");
-            })
-            .inspect(|gas| {
-                debug!("Remote estimate - gas used: {gas}");
-            })
+impl FeeOracle for AlchemyRpcClient {
+    async fn gas_price(&self) -> Result<U256> {
+        self.get_gas_price().await
+    }
+
+    async fn max_priority_fee_per_gas(&self) -> Result<U256> {
+        self.get_max_priority_fee_per_gas().await
     }
 }
 
@@ -243,16 +160,29 @@ mod tests {
     use super::*;
 
     #[test]
-    fn uint256_as_u64() {
-        let u256 = U256::from(237u64);
-        let u64 = u256.try_into_u64().unwrap();
-        assert_eq!(u64, 237u64)
+    fn url_mainnet() {
+        let sut = AlchemyRpcClient::new("key");
+        assert_eq!(sut.url(), "https://eth-mainnet.g.alchemy.com/v2/key");
+    }
+
+    #[test]
+    fn url_base() {
+        let sut = AlchemyRpcClient::for_chain("key", 8453).unwrap();
+        assert_eq!(sut.url(), "https://base-mainnet.g.alchemy.com/v2/key");
+    }
+
+    #[test]
+    fn url_sepolia() {
+        let sut = AlchemyRpcClient::for_network("key", AlchemyNetwork::Sepolia);
+        assert_eq!(sut.url(), "https://eth-sepolia.g.alchemy.com/v2/key");
     }
 
     #[test]
-    fn uint256_as_u64_too_large() {
-        let u256 = U256::from(u128::MAX);
-        let res = u256.try_into_u64();
-        assert!(res.is_err())
+    fn unsupported_chain() {
+        let res = AlchemyRpcClient::for_chain("key", 1337);
+        assert!(matches!(
+            res,
+            Err(Error::UnsupportedAlchemyChain { chain_id: 1337 })
+        ));
     }
 }
@@ -0,0 +1,37 @@
+use crate::prelude::*;
+
+/// Marker of the `eth_gasPrice` method, which takes no parameters and
+/// returns the current gas price (in wei), for legacy transactions.
+///
+/// For more info [see Alchemy's documentation][doc]
+///
+/// [doc]: https://docs.alchemy.com/reference/eth-gasprice
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct GasPriceRequest;
+
+/// Marker of the `eth_maxPriorityFeePerGas` method, which takes no
+/// parameters and returns the current priority fee per gas (in wei), for
+/// EIP-1559 transactions.
+///
+/// For more info [see Alchemy's documentation][doc]
+///
+/// [doc]: https://docs.alchemy.com/reference/eth-maxpriorityfeepergas
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct MaxPriorityFeePerGasRequest;
+
+// ========================================
+// IsRpcRequest impl
+// ========================================
+impl IsRpcRequest for GasPriceRequest {
+    type Param = GasPriceRequest;
+    fn method() -> String {
+        "eth_gasPrice".to_owned()
+    }
+}
+
+impl IsRpcRequest for MaxPriorityFeePerGasRequest {
+    type Param = MaxPriorityFeePerGasRequest;
+    fn method() -> String {
+        "eth_maxPriorityFeePerGas".to_owned()
+    }
+}
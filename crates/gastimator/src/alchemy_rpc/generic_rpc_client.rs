@@ -0,0 +1,728 @@
+use crate::prelude::*;
+
+/// JSON-RPC client for estimating gas costs using any Ethereum JSON-RPC
+/// endpoint, e.g. a self hosted node at `http://my-node:8545`.
+///
+/// It implements the `RemoteGasEstimator` trait, which allows it to be used
+/// as a gas estimator in the `GasEstimator` struct.
+pub struct GenericRpcClient {
+    /// The full URL of the JSON-RPC endpoint, e.g. `"http://my-node:8545"`.
+    url: String,
+
+    /// An optional token sent as `Authorization: Bearer <token>` header.
+    bearer_token: Option<String>,
+
+    /// The maximum duration of a request to the RPC node, including
+    /// reading the response, after which it is considered failed.
+    timeout: Duration,
+
+    /// An underlying HTTP client for making requests to the RPC node,
+    /// using the reqwest library.
+    client: reqwest::Client,
+
+    /// A helper which generates unique request IDs for each JSON-RPC request.
+    id_stepper: IdStepper,
+
+    /// The block transactions are estimated at, passed as second parameter
+    /// of `eth_estimateGas`, e.g. `pending` for mempool accurate estimates.
+    block: BlockNumberOrTag,
+
+    /// Limits the number of concurrent requests to the RPC node, if set,
+    /// excess requests wait for a permit, e.g. to stay within the compute
+    /// unit budget of a provider under batch load.
+    concurrency: Option<Arc<tokio::sync::Semaphore>>,
+}
+
+// ========================================
+// Public Implementation
+// ========================================
+
+impl GenericRpcClient {
+    /// Creates a new JSON-RPC client for the endpoint at `url`.
+    pub fn new(url: impl AsRef<str>) -> Self {
+        Self {
+            url: url.as_ref().to_owned(),
+            bearer_token: None,
+            timeout: DEFAULT_REMOTE_TIMEOUT,
+            client: reqwest::Client::default(),
+            id_stepper: IdStepper::default(),
+            block: BlockNumberOrTag::Latest,
+            concurrency: None,
+        }
+    }
+
+    /// Returns this client sending `bearer_token`, if any, as
+    /// `Authorization: Bearer <token>` header.
+    pub fn with_bearer_token(self, bearer_token: impl Into<Option<String>>) -> Self {
+        Self {
+            bearer_token: bearer_token.into(),
+            ..self
+        }
+    }
+
+    /// Returns this client with requests to the RPC node timing out after
+    /// `timeout`, on timeout estimates fail with [`Error::Timeout`] with
+    /// `phase` [`EstimatePhase::Remote`].
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        Self { timeout, ..self }
+    }
+
+    /// Returns this client generating ids of JSON-RPC requests using
+    /// `strategy`, see [`IdStrategy`].
+    pub fn with_id_strategy(self, strategy: IdStrategy) -> Self {
+        Self {
+            id_stepper: IdStepper::new(strategy),
+            ..self
+        }
+    }
+
+    /// Returns this client estimating transactions at `block`, e.g.
+    /// [`BlockNumberOrTag::Pending`], by default the latest block.
+    pub fn with_block(self, block: BlockNumberOrTag) -> Self {
+        Self { block, ..self }
+    }
+
+    /// Returns this client making at most `max` concurrent requests to the
+    /// RPC node, if set, excess requests wait until one completes.
+    pub fn with_max_concurrent_requests(self, max: impl Into<Option<usize>>) -> Self {
+        Self {
+            concurrency: max
+                .into()
+                .map(|max| Arc::new(tokio::sync::Semaphore::new(max))),
+            ..self
+        }
+    }
+
+    /// The full URL of the JSON-RPC endpoint.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Calls the `eth_gasPrice` method of the RPC node, returning the
+    /// current gas price (in wei).
+    pub async fn get_gas_price(&self) -> Result<U256> {
+        self.get_quantity::<GasPriceRequest>().await
+    }
+
+    /// Calls the `eth_maxPriorityFeePerGas` method of the RPC node,
+    /// returning the current max priority fee per gas (in wei).
+    pub async fn get_max_priority_fee_per_gas(&self) -> Result<U256> {
+        self.get_quantity::<MaxPriorityFeePerGasRequest>().await
+    }
+
+    /// Calls the `eth_getTransactionByHash` method of the RPC node,
+    /// returning the transaction with `hash`.
+    ///
+    /// # Throws
+    /// Throws [`Error::TransactionNotFound`] if the node does not know the
+    /// transaction.
+    pub async fn get_transaction_by_hash(&self, hash: B256) -> Result<Transaction> {
+        let tx: Option<RpcTransaction> = self
+            .call::<TransactionByHashRequest, _>([hash], |_| None)
+            .await?;
+        let tx = tx.ok_or(Error::TransactionNotFound { hash })?;
+        info!("Successfully fetched transaction {hash} from RPC node");
+        Ok(tx.into())
+    }
+
+    /// Calls the `eth_getTransactionReceipt` method of the RPC node,
+    /// returning the gas used by the mined transaction with `hash`.
+    ///
+    /// # Throws
+    /// Throws [`Error::TransactionNotFound`] if the node does not know the
+    /// transaction or it is not yet mined.
+    pub async fn get_gas_used_by(&self, hash: B256) -> Result<Gas> {
+        let receipt: Option<RpcTransactionReceipt> = self
+            .call::<TransactionReceiptRequest, _>([hash], |_| None)
+            .await?;
+        let receipt = receipt.ok_or(Error::TransactionNotFound { hash })?;
+        Ok(Gas::from(receipt.gas_used.to::<u64>()))
+    }
+}
+
+// ========================================
+// Private Implementation
+// ========================================
+
+impl GenericRpcClient {
+    /// Maps a failed request to the RPC node to an error, a timeout is
+    /// mapped to [`Error::Timeout`] of the remote phase.
+    fn request_failed(&self, error: reqwest::Error, otherwise: Error) -> Error {
+        if error.is_timeout() {
+            warn!("RPC request timed out after {:?}", self.timeout);
+            Error::Timeout {
+                phase: EstimatePhase::Remote,
+            }
+        } else {
+            otherwise
+        }
+    }
+
+    /// Calls the RPC method of the `Req::method()` with multiple parameters,
+    /// returning its result deserialized as `Res`.
+    ///
+    /// # Parameters
+    /// - `params`: The parameter values to be passed to the RPC method.
+    /// - `map_rpc_error`: Maps well-known JSON-RPC error objects of the method
+    ///   to a specific error, e.g. to [`Error::GasExceedsLimit`], others
+    ///   are returned as [`Error::RemoteRpcError`]. Pass `|_| None` to map
+    ///   none.
+    ///
+    /// # Returns
+    /// A `Result<Res>` containing the deserialized result or an error.
+    async fn call<Req, Res>(
+        &self,
+        params: impl IntoIterator<Item = Req::Param>,
+        map_rpc_error: impl FnOnce(&RpcError) -> Option<Error>,
+    ) -> Result<Res>
+    where
+        Req: IsRpcRequest,
+        Req::Param: Clone,
+        Res: for<'de> Deserialize<'de>,
+    {
+        self.call_with_raw_response::<Req, Res>(params, map_rpc_error)
+            .await
+            .map(|(result, _)| result)
+    }
+
+    /// Like [`Self::call`] but also returns the raw JSON-RPC response body.
+    async fn call_with_raw_response<Req, Res>(
+        &self,
+        params: impl IntoIterator<Item = Req::Param>,
+        map_rpc_error: impl FnOnce(&RpcError) -> Option<Error>,
+    ) -> Result<(Res, String)>
+    where
+        Req: IsRpcRequest,
+        Req::Param: Clone,
+        Res: for<'de> Deserialize<'de>,
+    {
+        // Held until the response is read, the semaphore is never closed
+        let _permit = match &self.concurrency {
+            Some(semaphore) => Some(semaphore.acquire().await.expect("never closed")),
+            None => None,
+        };
+        let id = self.id_stepper.next();
+        let method = Req::method();
+        let request = RpcRequestBuilder::default()
+            .params(params.into_iter().collect::<Vec<Req::Param>>())
+            .method(method.clone())
+            .id(id)
+            .build()
+            .unwrap();
+
+        #[cfg(debug_assertions)]
+        {
+            let json = serde_json::to_string_pretty(&request).unwrap();
+            debug!("👻 RPC request JSON: {:?}", json);
+        }
+
+        let request_builder = self.client.post(&self.url).timeout(self.timeout);
+        let request_builder = match &self.bearer_token {
+            Some(token) => request_builder.bearer_auth(token),
+            None => request_builder,
+        };
+        let response = request_builder
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| self.request_failed(e, Error::AlchemySendRequest { method }))?;
+
+        let status = response.status();
+        info!("RPC response status: {:?}", status);
+        let body_bytes = response.bytes().await.map_err(|e| {
+            let otherwise = Error::alchemy_read_bytes_of_response(&e);
+            self.request_failed(e, otherwise)
+        })?;
+        let body_string = String::from_utf8_lossy(&body_bytes);
+
+        // Print the response body as a debug string
+        #[cfg(debug_assertions)]
+        debug!(
+            "🔮 RPC RAW response: Status = {}, Body = {:?}",
+            status, body_string
+        );
+
+        // E.g. an HTML error page of the provider during an outage, which is
+        // not a JSON-RPC error, keep the status to make it diagnosable.
+        if serde_json::from_slice::<serde::de::IgnoredAny>(&body_bytes).is_err() {
+            warn!("RPC response with status {status} is not JSON");
+            return Err(Error::remote_non_json_response(
+                status.as_u16(),
+                &body_string,
+            ));
+        }
+
+        let envelope: RpcResponseEnvelope<Res> =
+            serde_json::from_slice(&body_bytes).map_err(|e| {
+                Error::AlchemyParseToResponseToType {
+                    kind: std::any::type_name::<Res>().to_owned(),
+                    underlying: format!("{:?}", e),
+                }
+            })?;
+        if let Some(error) = &envelope.error {
+            warn!("RPC responded with error {}: {}", error.code, error.message);
+        }
+        let result = envelope.into_result(map_rpc_error)?;
+        Ok((result, body_string.into_owned()))
+    }
+
+    /// Calls the parameterless RPC method of `Req::method()`, parsing the
+    /// hex encoded quantity it returns, e.g. `"0x3b9aca00"`.
+    async fn get_quantity<Req>(&self) -> Result<U256>
+    where
+        Req: IsRpcRequest,
+        Req::Param: Clone,
+    {
+        // Parameterless methods must be called with empty `params`, so we
+        // cannot use `call_single`.
+        let quantity: Quantity = self.call::<Req, _>([], |_| None).await?;
+        let quantity = *quantity;
+        info!(
+            "Successfully fetched {} from RPC node: {quantity}",
+            Req::method()
+        );
+        Ok(quantity)
+    }
+
+    /// Calls the `eth_estimateGas` method of the RPC node to estimate the gas cost
+    /// for a given transaction, at the block of this client.
+    ///
+    /// # Parameters
+    /// - `input`: The input parameters for the `eth_estimateGas` method. For more
+    ///   info see [`AlchemyEstimateGasInput`].
+    ///
+    /// # Returns
+    /// A `Result<(Gas, String)>` containing the estimated gas cost and the raw
+    /// JSON-RPC response body, or an error.
+    async fn get_gas_estimate(&self, input: AlchemyEstimateGasInput) -> Result<(Gas, String)> {
+        let gas_limit = *input.gas();
+
+        let map_rpc_error = |error: &RpcError| {
+            if !error.is_gas_required_exceeds_allowance() {
+                return None;
+            }
+            let allowance = error.allowance_in_message();
+            // Without a gas limit the node caps it, e.g. by the balance of
+            // the sender, and reports the cap as the allowance.
+            let gas_limit = gas_limit
+                .and_then(|gas_limit| Gas::try_from(gas_limit).ok())
+                .or(allowance)?;
+            Some(Error::GasExceedsLimit {
+                // Only a number larger than the limit can be the gas required.
+                estimated_cost: allowance.filter(|allowance| *allowance > gas_limit),
+                gas_limit,
+            })
+        };
+        let params = [
+            EstimateGasParam::Transaction(input),
+            EstimateGasParam::Block(self.block),
+        ];
+        let (gas_used, raw_response): (Quantity, _) = self
+            .call_with_raw_response::<AlchemyEstimateGasInput, _>(params, map_rpc_error)
+            .await?;
+
+        let gas_used = Gas::try_from(*gas_used)?;
+        info!(
+            "Successfully fetched gas estimate from RPC node: {:?}",
+            gas_used
+        );
+        Ok((gas_used, raw_response))
+    }
+}
+
+// ========================================
+// RemoteGasEstimator Implementation
+// ========================================
+
+#[async_trait::async_trait]
+impl RemoteGasEstimator for GenericRpcClient {
+    async fn estimate_gas(&self, tx: &Transaction) -> Result<Gas> {
+        self.estimate_gas_with_raw_response(tx)
+            .await
+            .map(|(gas, _)| gas)
+    }
+
+    async fn estimate_gas_with_raw_response(
+        &self,
+        tx: &Transaction,
+    ) -> Result<(Gas, Option<String>)> {
+        let tx: AlchemyEstimateGasInput = tx.clone().into();
+        self.get_gas_estimate(tx)
+            .await
+            .inspect_err(|e| {
+                error!("Error while fetching remote transaction: {e}");
+            })
+            .inspect(|(gas, _)| {
+                debug!("Remote estimate - gas used: {gas}");
+            })
+            .map(|(gas, raw)| (gas, Some(raw)))
+    }
+}
+
+// ========================================
+// TransactionSource Implementation
+// ========================================
+
+#[async_trait::async_trait]
+impl TransactionSource for GenericRpcClient {
+    async fn transaction_by_hash(&self, hash: B256) -> Result<Transaction> {
+        self.get_transaction_by_hash(hash).await
+    }
+
+    async fn gas_used_by(&self, hash: B256) -> Result<Gas> {
+        self.get_gas_used_by(hash).await
+    }
+}
+
+// ========================================
+// FeeOracle Implementation
+// ========================================
+
+#[async_trait::async_trait]
+impl FeeOracle for GenericRpcClient {
+    async fn gas_price(&self) -> Result<U256> {
+        self.get_gas_price().await
+    }
+
+    async fn max_priority_fee_per_gas(&self) -> Result<U256> {
+        self.get_max_priority_fee_per_gas().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+
+    type Sut = GenericRpcClient;
+
+    /// Spawns a server responding to a single request with `response_body`,
+    /// returning its URL and a receiver of the raw request.
+    async fn serve_once(
+        response_body: &'static str,
+    ) -> (String, tokio::sync::oneshot::Receiver<String>) {
+        serve_once_with_status("200 OK", "application/json", response_body).await
+    }
+
+    /// Spawns a server responding to a single request with `status`,
+    /// `content_type` and `response_body`, returning its URL and a receiver
+    /// of the raw request.
+    async fn serve_once_with_status(
+        status: &'static str,
+        content_type: &'static str,
+        response_body: &'static str,
+    ) -> (String, tokio::sync::oneshot::Receiver<String>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buffer = vec![0; 8192];
+            let read = stream.read(&mut buffer).await.unwrap();
+            let _ = sender.send(String::from_utf8_lossy(&buffer[..read]).to_string());
+            let response = format!(
+                "HTTP/1.1 {status}\r\ncontent-type: {content_type}\r\ncontent-length: {}\r\n\r\n{}",
+                response_body.len(),
+                response_body
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+        });
+        (format!("http://{address}"), receiver)
+    }
+
+    #[tokio::test]
+    async fn max_concurrent_requests() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let in_flight = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let max_in_flight = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        {
+            let (in_flight, max_in_flight) = (in_flight.clone(), max_in_flight.clone());
+            tokio::spawn(async move {
+                loop {
+                    let (mut stream, _) = listener.accept().await.unwrap();
+                    let (in_flight, max_in_flight) = (in_flight.clone(), max_in_flight.clone());
+                    tokio::spawn(async move {
+                        let mut buffer = vec![0; 8192];
+                        let _ = stream.read(&mut buffer).await.unwrap();
+                        let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                        max_in_flight.fetch_max(now, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                        in_flight.fetch_sub(1, Ordering::SeqCst);
+                        let body = r#"{"jsonrpc":"2.0","id":1,"result":"0x5208"}"#;
+                        let response = format!(
+                            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{body}",
+                            body.len()
+                        );
+                        stream.write_all(response.as_bytes()).await.unwrap();
+                    });
+                }
+            });
+        }
+        let sut = Sut::new(url).with_max_concurrent_requests(1);
+        let tx = Transaction::sample_contract_creation();
+
+        let (first, second) = tokio::join!(sut.estimate_gas(&tx), sut.estimate_gas(&tx));
+
+        assert_eq!(first.unwrap(), Gas::from(21_000));
+        assert_eq!(second.unwrap(), Gas::from(21_000));
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn transaction_by_hash() {
+        let (url, request) = serve_once(
+            r#"{"jsonrpc":"2.0","id":1,"result":{"from":"0x1111111111111111111111111111111111111111","to":null,"value":"0x0","input":"0x5b","gas":"0x10000","nonce":"0x7"}}"#,
+        )
+        .await;
+        let sut = Sut::new(url);
+
+        let tx = sut
+            .transaction_by_hash(B256::repeat_byte(0xab))
+            .await
+            .unwrap();
+
+        assert_eq!(*tx.to(), TxKind::Create);
+        assert_eq!(*tx.nonce(), Some(7));
+        let request = request.await.unwrap();
+        assert!(request.contains("eth_getTransactionByHash"));
+        assert!(request.contains(&B256::repeat_byte(0xab).to_string()));
+    }
+
+    #[tokio::test]
+    async fn gas_used_by() {
+        let (url, request) =
+            serve_once(r#"{"jsonrpc":"2.0","id":1,"result":{"status":"0x1","gasUsed":"0xf6dd"}}"#)
+                .await;
+        let sut = Sut::new(url);
+
+        let gas = sut.gas_used_by(B256::repeat_byte(0xab)).await.unwrap();
+
+        assert_eq!(gas, Gas::from(63_197));
+        assert!(request.await.unwrap().contains("eth_getTransactionReceipt"));
+    }
+
+    #[tokio::test]
+    async fn transaction_by_hash_not_found() {
+        let (url, _) = serve_once(r#"{"jsonrpc":"2.0","id":1,"result":null}"#).await;
+        let sut = Sut::new(url);
+        let hash = B256::repeat_byte(0xab);
+
+        let res = sut.transaction_by_hash(hash).await;
+
+        assert_eq!(res, Err(Error::TransactionNotFound { hash }));
+    }
+
+    #[tokio::test]
+    async fn estimate_gas_with_bearer_token() {
+        let (url, request) = serve_once(r#"{"jsonrpc":"2.0","id":1,"result":"0x5208"}"#).await;
+        let sut = Sut::new(url).with_bearer_token("secret".to_owned());
+
+        let gas = sut
+            .estimate_gas(&Transaction::sample_contract_creation())
+            .await
+            .unwrap();
+
+        assert_eq!(gas, Gas::from(21_000));
+        let request = request.await.unwrap().to_lowercase();
+        assert!(request.contains("authorization: bearer secret"));
+        assert!(request.contains("eth_estimategas"));
+    }
+
+    #[tokio::test]
+    async fn estimate_gas_at_latest_block_by_default() {
+        let (url, request) = serve_once(r#"{"jsonrpc":"2.0","id":1,"result":"0x5208"}"#).await;
+        let sut = Sut::new(url);
+
+        sut.estimate_gas(&Transaction::sample_contract_creation())
+            .await
+            .unwrap();
+
+        assert!(request.await.unwrap().contains(r#"},"latest"]"#));
+    }
+
+    #[tokio::test]
+    async fn estimate_gas_at_configured_block() {
+        let (url, request) = serve_once(r#"{"jsonrpc":"2.0","id":1,"result":"0x5208"}"#).await;
+        let sut = Sut::new(url).with_block(BlockNumberOrTag::Pending);
+
+        sut.estimate_gas(&Transaction::sample_contract_creation())
+            .await
+            .unwrap();
+
+        assert!(request.await.unwrap().contains(r#"},"pending"]"#));
+    }
+
+    #[tokio::test]
+    async fn times_out_with_remote_timeout() {
+        // A server which accepts connections but never responds
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut connections = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                connections.push(stream);
+            }
+        });
+        let sut = Sut::new(format!("http://{address}")).with_timeout(Duration::from_millis(50));
+
+        let res = sut
+            .estimate_gas(&Transaction::sample_contract_creation())
+            .await;
+
+        assert_eq!(
+            res,
+            Err(Error::Timeout {
+                phase: EstimatePhase::Remote
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn gas_price() {
+        let (url, request) = serve_once(r#"{"jsonrpc":"2.0","id":1,"result":"0x3b9aca00"}"#).await;
+        let sut = Sut::new(url);
+
+        let gas_price = sut.get_gas_price().await.unwrap();
+
+        assert_eq!(gas_price, U256::from(1_000_000_000u64));
+        let request = request.await.unwrap();
+        assert!(request.contains(r#""method":"eth_gasPrice""#));
+        assert!(request.contains(r#""params":[]"#));
+    }
+
+    #[tokio::test]
+    async fn max_priority_fee_per_gas() {
+        let (url, request) = serve_once(r#"{"jsonrpc":"2.0","id":1,"result":"0x5f5e100"}"#).await;
+        let sut = Sut::new(url);
+
+        let fee = sut.max_priority_fee_per_gas().await.unwrap();
+
+        assert_eq!(fee, U256::from(100_000_000u64));
+        let request = request.await.unwrap();
+        assert!(request.contains(r#""method":"eth_maxPriorityFeePerGas""#));
+    }
+
+    #[tokio::test]
+    async fn gas_price_not_hex_fails() {
+        let (url, _) = serve_once(r#"{"jsonrpc":"2.0","id":1,"result":"0xnothex"}"#).await;
+        let sut = Sut::new(url);
+
+        assert!(
+            sut.get_gas_price()
+                .await
+                .unwrap_err()
+                .is_alchemy_parse_to_response_to_type()
+        );
+    }
+
+    #[tokio::test]
+    async fn non_json_response_includes_status() {
+        let (url, _) = serve_once_with_status(
+            "503 Service Unavailable",
+            "text/html",
+            "<html><body>Service Unavailable</body></html>",
+        )
+        .await;
+        let sut = Sut::new(url);
+
+        let res = sut
+            .estimate_gas(&Transaction::sample_contract_creation())
+            .await;
+
+        assert_eq!(
+            res,
+            Err(Error::RemoteNonJsonResponse {
+                status: 503,
+                body: "<html><body>Service Unavailable</body></html>".to_owned()
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn gas_required_exceeds_allowance_is_gas_exceeds_limit() {
+        let (url, _) = serve_once(
+            r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32000,"message":"gas required exceeds allowance (21000)"}}"#,
+        )
+        .await;
+        let sut = Sut::new(url);
+        let tx = Transaction::sample_contract_creation_gas_limit(Gas::from(21_000));
+
+        let res = sut.estimate_gas(&tx).await;
+
+        assert_eq!(
+            res,
+            Err(Error::GasExceedsLimit {
+                estimated_cost: None, // the allowance is the limit, not the gas required
+                gas_limit: Gas::from(21_000)
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn gas_required_exceeds_allowance_without_gas_limit() {
+        let (url, _) = serve_once(
+            r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32000,"message":"gas required exceeds allowance (1000000)"}}"#,
+        )
+        .await;
+        let sut = Sut::new(url);
+
+        let res = sut
+            .estimate_gas(&Transaction::sample_contract_creation())
+            .await;
+
+        assert_eq!(
+            res,
+            Err(Error::GasExceedsLimit {
+                estimated_cost: None,
+                gas_limit: Gas::from(1_000_000)
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn gas_required_exceeds_allowance_without_gas_in_message() {
+        let (url, _) = serve_once(
+            r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32000,"message":"gas required exceeds allowance"}}"#,
+        )
+        .await;
+        let sut = Sut::new(url);
+        let tx = Transaction::sample_contract_creation_gas_limit(Gas::from(21_000));
+
+        let res = sut.estimate_gas(&tx).await;
+
+        assert_eq!(
+            res,
+            Err(Error::GasExceedsLimit {
+                estimated_cost: None,
+                gas_limit: Gas::from(21_000)
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn json_rpc_error_is_not_a_non_json_response() {
+        let (url, _) = serve_once_with_status(
+            "500 Internal Server Error",
+            "application/json",
+            r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32000,"message":"boom"}}"#,
+        )
+        .await;
+        let sut = Sut::new(url);
+
+        let res = sut
+            .estimate_gas(&Transaction::sample_contract_creation())
+            .await;
+
+        assert_eq!(
+            res,
+            Err(Error::RemoteRpcError {
+                code: -32000,
+                message: "boom".to_owned()
+            })
+        );
+    }
+}
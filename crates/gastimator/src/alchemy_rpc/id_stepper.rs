@@ -1,13 +1,103 @@
 use crate::prelude::*;
 
+/// The strategy used by an [`IdStepper`] to generate ids of JSON-RPC requests.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdStrategy {
+    /// Ids count up from `1`, restarting with each process.
+    #[default]
+    Sequential,
+
+    /// Ids are random `u64`s, so that ids do not collide across process
+    /// restarts, e.g. with a provider deduplicating requests by id.
+    Random,
+}
+
 /// A helper which generates unique request IDs for each JSON-RPC request.
 #[derive(Default)]
-pub struct IdStepper(RwLock<u64>);
+pub struct IdStepper {
+    strategy: IdStrategy,
+    last: RwLock<u64>,
+}
+
 impl IdStepper {
+    /// Creates a new stepper generating ids using `strategy`.
+    pub fn new(strategy: IdStrategy) -> Self {
+        Self {
+            strategy,
+            ..Default::default()
+        }
+    }
+
     /// Returns the next request ID, e.g. for a JSON-RPC request.
     pub fn next(&self) -> u64 {
-        let mut id = self.0.write().unwrap();
-        *id += 1;
-        *id
+        match self.strategy {
+            IdStrategy::Sequential => {
+                let mut id = self.last.write().unwrap();
+                *id += 1;
+                *id
+            }
+            IdStrategy::Random => rand::random(),
+        }
+    }
+}
+
+// ========================================
+// FromStr Implementation
+// ========================================
+impl std::str::FromStr for IdStrategy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "sequential" => Ok(Self::Sequential),
+            "random" => Ok(Self::Random),
+            _ => Err(Error::UnknownIdStrategy {
+                bad_value: s.to_owned(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    type Sut = IdStepper;
+
+    fn assert_unique(sut: Sut) {
+        let n = 10_000;
+        let ids = (0..n).map(|_| sut.next()).collect::<HashSet<_>>();
+        assert_eq!(ids.len(), n);
+    }
+
+    #[test]
+    fn sequential_ids_are_unique() {
+        assert_unique(Sut::new(IdStrategy::Sequential));
+    }
+
+    #[test]
+    fn random_ids_are_unique() {
+        assert_unique(Sut::new(IdStrategy::Random));
+    }
+
+    #[test]
+    fn default_is_sequential_from_one() {
+        let sut = Sut::default();
+        assert_eq!(sut.next(), 1);
+        assert_eq!(sut.next(), 2);
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!("random".parse::<IdStrategy>(), Ok(IdStrategy::Random));
+        assert_eq!(
+            "uuid".parse::<IdStrategy>(),
+            Err(Error::UnknownIdStrategy {
+                bad_value: "uuid".to_owned()
+            })
+        );
     }
 }
@@ -1,14 +1,24 @@
+mod alchemy_network;
 #[allow(clippy::module_inception)]
 mod alchemy_rpc;
+mod fee_requests;
+mod generic_rpc_client;
 mod id_stepper;
 mod is_rpc_request;
 mod request_estimate_gas_input;
 mod rpc_request;
 mod rpc_response;
+mod transaction_by_hash;
+mod transaction_receipt;
 
+pub use alchemy_network::*;
 pub use alchemy_rpc::*;
+pub use fee_requests::*;
+pub use generic_rpc_client::*;
 pub use id_stepper::*;
 pub use is_rpc_request::*;
 pub use request_estimate_gas_input::*;
 pub use rpc_request::*;
 pub use rpc_response::*;
+pub use transaction_by_hash::*;
+pub use transaction_receipt::*;
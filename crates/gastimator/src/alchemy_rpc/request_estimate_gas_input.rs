@@ -9,6 +9,12 @@ use crate::prelude::*;
 #[derive(Clone, Debug, Serialize, Builder, Getters, Default)]
 #[builder(setter(into), default)]
 pub struct AlchemyEstimateGasInput {
+    /// The address of the sender of the transaction, so that calls depending
+    /// on `msg.sender`, e.g. balance checks or access control, are estimated
+    /// realistically. No getter, which would shadow `From::from`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from: Option<Address>, // e.g. "0xb60e8dd61c5d32be8058bb8eb970870f07233155",
+
     /// The address of the recipient of the transaction, either a contract or an EOA.
     #[serde(skip_serializing_if = "Option::is_none")]
     #[getset(get = "pub")]
@@ -26,7 +32,7 @@ pub struct AlchemyEstimateGasInput {
     gas_price: Option<U256>, // e.g. "0x9184e72a000",
 
     /// An optional amount of ETH to send with the transaction
-    /// (in wei).
+    /// (in wei), omitted if zero, like wallets do.
     #[serde(skip_serializing_if = "Option::is_none")]
     #[getset(get = "pub")]
     value: Option<U256>, // e.g. "0x0",
@@ -36,6 +42,29 @@ pub struct AlchemyEstimateGasInput {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[getset(get = "pub")]
     data: Option<Bytes>, // e.g. "0x"
+
+    /// Versioned hashes of blobs, for EIP-4844 transactions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "blobVersionedHashes")]
+    #[getset(get = "pub")]
+    blob_versioned_hashes: Option<Vec<B256>>,
+
+    /// Max fee per blob gas, for EIP-4844 transactions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "maxFeePerBlobGas")]
+    #[getset(get = "pub")]
+    max_fee_per_blob_gas: Option<U256>,
+}
+
+/// A parameter of the `eth_estimateGas` method, the transaction or the block
+/// to estimate it at, e.g. `"latest"` or `"pending"`.
+#[derive(Clone, Debug, Serialize)]
+#[serde(untagged)]
+pub enum EstimateGasParam {
+    /// The first parameter, the transaction to estimate.
+    Transaction(AlchemyEstimateGasInput),
+    /// The second parameter, the block to estimate the transaction at.
+    Block(BlockNumberOrTag),
 }
 
 impl From<Transaction> for AlchemyEstimateGasInput {
@@ -47,12 +76,20 @@ impl From<Transaction> for AlchemyEstimateGasInput {
             Some(value.input().clone())
         };
         let gas_limit = value.gas_limit().map(|gas| U256::from(*gas));
+        let amount = Some(*value.value()).filter(|amount| !amount.is_zero());
+        let blob_versioned_hashes = value
+            .is_blob_carrying()
+            .then(|| value.blob_versioned_hashes().clone());
+        let max_fee_per_blob_gas = value.max_fee_per_blob_gas().map(U256::from);
 
         AlchemyEstimateGasInputBuilder::default()
+            .from(*value.from())
             .to(*value.to())
             .gas(gas_limit)
-            .value(*value.value())
+            .value(amount)
             .data(data)
+            .blob_versioned_hashes(blob_versioned_hashes)
+            .max_fee_per_blob_gas(max_fee_per_blob_gas)
             .build()
             .unwrap()
     }
@@ -62,8 +99,74 @@ impl From<Transaction> for AlchemyEstimateGasInput {
 // IsRpcRequest impl
 // ========================================
 impl IsRpcRequest for AlchemyEstimateGasInput {
-    type Param = AlchemyEstimateGasInput;
+    type Param = EstimateGasParam;
     fn method() -> String {
         "eth_estimateGas".to_owned()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = AlchemyEstimateGasInput;
+
+    #[test]
+    fn from_is_serialized() {
+        let from = Address::with_last_byte(0xaa);
+        let tx = TransactionBuilder::default()
+            .from(from)
+            .to(Address::with_last_byte(0xbb))
+            .build()
+            .unwrap();
+
+        let sut: Sut = tx.into();
+
+        let json = serde_json::to_value(sut).unwrap();
+
+        assert_eq!(json["from"], serde_json::json!(from));
+    }
+
+    #[test]
+    fn from_is_omitted_if_unknown() {
+        let tx = TransactionBuilder::default()
+            .to(Address::with_last_byte(0xbb))
+            .build()
+            .unwrap();
+
+        let sut: Sut = tx.into();
+
+        let json = serde_json::to_value(sut).unwrap();
+
+        assert!(json.get("from").is_none());
+    }
+
+    #[test]
+    fn zero_value_is_omitted() {
+        let tx = TransactionBuilder::default()
+            .to(Address::with_last_byte(0xbb))
+            .build()
+            .unwrap();
+
+        let sut: Sut = tx.into();
+
+        assert_eq!(*sut.value(), None);
+        assert!(serde_json::to_value(sut).unwrap().get("value").is_none());
+    }
+
+    #[test]
+    fn non_zero_value_is_forwarded() {
+        let tx = TransactionBuilder::default()
+            .to(Address::with_last_byte(0xbb))
+            .value(U256::from(1_000))
+            .build()
+            .unwrap();
+
+        let sut: Sut = tx.into();
+
+        assert_eq!(
+            serde_json::to_value(sut).unwrap()["value"],
+            serde_json::json!("0x3e8")
+        );
+    }
+}
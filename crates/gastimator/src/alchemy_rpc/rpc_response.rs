@@ -1,16 +1,250 @@
 use crate::prelude::*;
 
+/// The response of a JSON-RPC call, with its `result` typed, by default a
+/// [`Quantity`], e.g. of `eth_estimateGas`.
 #[derive(Deserialize, Debug)]
-pub struct RpcResponse {
-    pub result: String,
+pub struct RpcResponse<T = Quantity> {
+    pub result: T,
 }
 
-impl RpcResponse {
-    pub fn result_strip_0x(&self) -> String {
-        if self.result.starts_with("0x") {
-            self.result[2..].to_string()
-        } else {
-            self.result.clone()
+/// The envelope of any JSON-RPC response, either with a `result` or an
+/// `error`, e.g. `{"jsonrpc":"2.0","id":1,"error":{"code":-32000,"message":"..."}}`.
+#[derive(Deserialize, Debug)]
+pub struct RpcResponseEnvelope<T> {
+    /// The result of a successful call, `None` if it failed or, for some
+    /// methods, if the result is `null`.
+    pub result: Option<T>,
+
+    /// The error of a failed call.
+    pub error: Option<RpcError>,
+}
+
+/// The error object of a failed JSON-RPC call.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RpcError {
+    /// The code of the error, e.g. `-32000` for server errors of Geth
+    /// compatible nodes.
+    pub code: i64,
+
+    /// The human readable message of the error, e.g.
+    /// `"gas required exceeds allowance (21000)"`.
+    pub message: String,
+}
+
+impl RpcError {
+    /// The code Geth compatible nodes use for execution errors, e.g. when the
+    /// gas limit of a transaction is exceeded.
+    pub const SERVER_ERROR: i64 = -32000;
+
+    /// Returns `true` if this is the error of `eth_estimateGas` when the gas
+    /// required by a transaction exceeds its gas limit.
+    pub fn is_gas_required_exceeds_allowance(&self) -> bool {
+        self.code == Self::SERVER_ERROR && self.message.contains("gas required exceeds allowance")
+    }
+
+    /// The gas in the parenthetical of the message, e.g. `21000` of
+    /// `"gas required exceeds allowance (21000)"`, or `None` if there is none
+    /// or it is not a number. For Geth this is the allowance, i.e. the gas
+    /// limit or the cap of it, not the gas required.
+    pub fn allowance_in_message(&self) -> Option<Gas> {
+        let (_, rest) = self.message.split_once('(')?;
+        let (gas, _) = rest.split_once(')')?;
+        gas.trim().parse::<u64>().ok().map(Gas::from)
+    }
+}
+
+impl<T: serde::de::DeserializeOwned> RpcResponseEnvelope<T> {
+    /// The result of the call, or, if it failed, the error returned by
+    /// `map_error`, falling back to [`Error::RemoteRpcError`].
+    ///
+    /// A missing or `null` result is deserialized as `T`, so `Option<T>`
+    /// results are `None` and others fail.
+    pub fn into_result(self, map_error: impl FnOnce(&RpcError) -> Option<Error>) -> Result<T> {
+        if let Some(error) = self.error {
+            return Err(map_error(&error).unwrap_or(Error::RemoteRpcError {
+                code: error.code,
+                message: error.message,
+            }));
+        }
+        match self.result {
+            Some(result) => Ok(result),
+            None => serde_json::from_value(serde_json::Value::Null).map_err(|e| {
+                Error::AlchemyParseToResponseToType {
+                    kind: std::any::type_name::<T>().to_owned(),
+                    underlying: format!("{:?}", e),
+                }
+            }),
         }
     }
 }
+
+/// A hex encoded quantity of the JSON-RPC API, e.g. `"0x5208"`, the `0x`
+/// prefix is optional. Deserializing fails if it is not hex or larger than
+/// 256 bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deref)]
+pub struct Quantity(U256);
+
+impl Quantity {
+    /// The quantity as `u64`.
+    ///
+    /// # Throws
+    /// Throws [`Error::UInt256LargerThanU64`] if it does not fit.
+    pub fn to_u64(self) -> Result<u64> {
+        u64::try_from(self.0).map_err(|_| Error::UInt256LargerThanU64)
+    }
+}
+
+impl From<Quantity> for U256 {
+    fn from(value: Quantity) -> Self {
+        value.0
+    }
+}
+
+impl<'de> Deserialize<'de> for Quantity {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        U256::from_str_radix(strip_0x(&s), 16)
+            .map(Self)
+            .map_err(|_| serde::de::Error::custom(format!("not a hex quantity: `{s}`")))
+    }
+}
+
+/// Hex encoded bytes of the JSON-RPC API, e.g. `"0xdeadbeef"`, the `0x`
+/// prefix is optional. Deserializing fails if it is not hex.
+#[derive(Debug, Clone, PartialEq, Eq, Deref)]
+pub struct HexBytes(Bytes);
+
+impl From<HexBytes> for Bytes {
+    fn from(value: HexBytes) -> Self {
+        value.0
+    }
+}
+
+impl<'de> Deserialize<'de> for HexBytes {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        hex::decode(strip_0x(&s))
+            .map(|bytes| Self(Bytes::from(bytes)))
+            .map_err(|_| serde::de::Error::custom(format!("not hex bytes: `{s}`")))
+    }
+}
+
+/// `s` without its `0x` prefix, if any.
+fn strip_0x(s: &str) -> &str {
+    s.strip_prefix("0x").unwrap_or(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn quantity_with_and_without_prefix() {
+        for s in ["0x5208", "5208"] {
+            let quantity: Quantity = serde_json::from_value(json!(s)).unwrap();
+            assert_eq!(quantity.to_u64(), Ok(21_000));
+        }
+    }
+
+    #[test]
+    fn quantity_larger_than_u64() {
+        let quantity: Quantity = serde_json::from_value(json!("0x10000000000000000")).unwrap();
+        assert_eq!(*quantity, U256::from(u64::MAX) + U256::from(1));
+        assert_eq!(quantity.to_u64(), Err(Error::UInt256LargerThanU64));
+    }
+
+    #[test]
+    fn quantity_not_hex() {
+        assert!(serde_json::from_value::<Quantity>(json!("0xnothex")).is_err());
+        assert!(serde_json::from_value::<Quantity>(json!(21000)).is_err());
+    }
+
+    #[test]
+    fn hex_bytes() {
+        let bytes: HexBytes = serde_json::from_value(json!("0xdeadbeef")).unwrap();
+        assert_eq!(
+            Bytes::from(bytes),
+            Bytes::from_static(&[0xde, 0xad, 0xbe, 0xef])
+        );
+        assert!(serde_json::from_value::<HexBytes>(json!("0xzz")).is_err());
+    }
+
+    #[test]
+    fn envelope_result() {
+        let envelope: RpcResponseEnvelope<Quantity> =
+            serde_json::from_value(json!({"jsonrpc": "2.0", "id": 1, "result": "0x5208"})).unwrap();
+        assert_eq!(envelope.into_result(|_| None).unwrap().to_u64(), Ok(21_000));
+    }
+
+    #[test]
+    fn envelope_null_result() {
+        let json = json!({"jsonrpc": "2.0", "id": 1, "result": null});
+        let envelope: RpcResponseEnvelope<Option<Quantity>> =
+            serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(envelope.into_result(|_| None), Ok(None));
+        let envelope: RpcResponseEnvelope<Quantity> = serde_json::from_value(json).unwrap();
+        assert!(
+            envelope
+                .into_result(|_| None)
+                .unwrap_err()
+                .is_alchemy_parse_to_response_to_type()
+        );
+    }
+
+    #[test]
+    fn envelope_error() {
+        let envelope: RpcResponseEnvelope<Quantity> = serde_json::from_value(json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "error": {"code": -32000, "message": "gas required exceeds allowance (21000)"}
+        }))
+        .unwrap();
+        let error = envelope.error.clone().unwrap();
+        assert!(error.is_gas_required_exceeds_allowance());
+        assert_eq!(
+            envelope.into_result(|_| None),
+            Err(Error::RemoteRpcError {
+                code: -32000,
+                message: "gas required exceeds allowance (21000)".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn allowance_in_message() {
+        let error = |message: &str| RpcError {
+            code: RpcError::SERVER_ERROR,
+            message: message.to_owned(),
+        };
+        assert_eq!(
+            error("gas required exceeds allowance (21000)").allowance_in_message(),
+            Some(Gas::from(21_000))
+        );
+        assert_eq!(
+            error("gas required exceeds allowance").allowance_in_message(),
+            None
+        );
+        assert_eq!(
+            error("gas required exceeds allowance (lots)").allowance_in_message(),
+            None
+        );
+    }
+
+    #[test]
+    fn allowance_message_with_other_code_is_not_gas_required_exceeds_allowance() {
+        let error = RpcError {
+            code: -32603,
+            message: "gas required exceeds allowance".to_owned(),
+        };
+        assert!(!error.is_gas_required_exceeds_allowance());
+    }
+
+    #[test]
+    fn response_result() {
+        let response: RpcResponse =
+            serde_json::from_value(json!({"jsonrpc": "2.0", "id": 1, "result": "0x5208"})).unwrap();
+        assert_eq!(response.result.to_u64(), Ok(21_000));
+    }
+}
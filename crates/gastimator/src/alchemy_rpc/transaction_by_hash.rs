@@ -0,0 +1,123 @@
+use alloy_primitives::{U64, U128};
+
+use crate::prelude::*;
+
+/// Marker of the `eth_getTransactionByHash` method, which takes the hash of
+/// a transaction and returns it, or `null` if unknown.
+///
+/// For more info [see Alchemy's documentation][doc]
+///
+/// [doc]: https://docs.alchemy.com/reference/eth-gettransactionbyhash
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct TransactionByHashRequest;
+
+impl IsRpcRequest for TransactionByHashRequest {
+    type Param = B256;
+    fn method() -> String {
+        "eth_getTransactionByHash".to_owned()
+    }
+}
+
+/// A transaction as returned by the JSON-RPC API, e.g. by
+/// `eth_getTransactionByHash`, with only the fields relevant for estimating
+/// it, quantities are hex encoded, e.g. `"0x5208"`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransaction {
+    /// The sender of the transaction.
+    pub from: Address,
+
+    /// The receiver of the transaction, `None` for contract creations.
+    #[serde(default)]
+    pub to: Option<Address>,
+
+    /// The amount of wei sent with the transaction.
+    #[serde(default)]
+    pub value: U256,
+
+    /// The calldata, or init code of contract creations.
+    #[serde(default)]
+    pub input: Bytes,
+
+    /// The gas limit of the transaction.
+    pub gas: U64,
+
+    /// The nonce of the sender.
+    pub nonce: U64,
+
+    /// Versioned hashes of the blobs of an EIP-4844 transaction.
+    #[serde(default)]
+    pub blob_versioned_hashes: Vec<B256>,
+
+    /// Max fee per blob gas (in wei) of an EIP-4844 transaction.
+    #[serde(default)]
+    pub max_fee_per_blob_gas: Option<U128>,
+}
+
+impl From<RpcTransaction> for Transaction {
+    /// The transaction to estimate, with the sender, nonce and gas limit of
+    /// the mined transaction. Its access list, if any, is not used.
+    fn from(value: RpcTransaction) -> Self {
+        TransactionBuilder::default()
+            .nonce(value.nonce.to::<u64>())
+            .from(value.from)
+            .to(value.to.map_or(TxKind::Create, TxKind::Call))
+            .value(value.value)
+            .gas_limit(Gas::from(value.gas.to::<u64>()))
+            .input(value.input)
+            .blob_versioned_hashes(value.blob_versioned_hashes)
+            .max_fee_per_blob_gas(value.max_fee_per_blob_gas.map(|fee| fee.to::<u128>()))
+            .build()
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    type Sut = RpcTransaction;
+
+    #[test]
+    fn contract_call_into_transaction() {
+        let sut: Sut = serde_json::from_value(json!({
+            "blockHash": "0x0000000000000000000000000000000000000000000000000000000000000001",
+            "from": "0x1111111111111111111111111111111111111111",
+            "to": "0x2222222222222222222222222222222222222222",
+            "value": "0x1",
+            "input": "0xdeadbeef",
+            "gas": "0x5208",
+            "nonce": "0x2a",
+            "type": "0x2",
+            "accessList": []
+        }))
+        .unwrap();
+
+        let tx: Transaction = sut.into();
+
+        assert_eq!(*tx.nonce(), Some(42));
+        assert_eq!(*tx.from(), Some(Address::from([0x11; 20])));
+        assert_eq!(*tx.to(), TxKind::Call(Address::from([0x22; 20])));
+        assert_eq!(*tx.value(), U256::from(1));
+        assert_eq!(*tx.gas_limit(), Some(Gas::from(21_000)));
+        assert_eq!(tx.input(), &Bytes::from_static(&[0xde, 0xad, 0xbe, 0xef]));
+    }
+
+    #[test]
+    fn contract_creation_without_to() {
+        let sut: Sut = serde_json::from_value(json!({
+            "from": "0x1111111111111111111111111111111111111111",
+            "to": null,
+            "value": "0x0",
+            "input": "0x5b",
+            "gas": "0x10000",
+            "nonce": "0x0"
+        }))
+        .unwrap();
+
+        let tx: Transaction = sut.into();
+        assert_eq!(*tx.to(), TxKind::Create);
+    }
+}
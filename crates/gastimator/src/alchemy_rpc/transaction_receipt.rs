@@ -0,0 +1,28 @@
+use alloy_primitives::U64;
+
+use crate::prelude::*;
+
+/// Marker of the `eth_getTransactionReceipt` method, which takes the hash of
+/// a transaction and returns its receipt, or `null` if unknown or pending.
+///
+/// For more info [see Alchemy's documentation][doc]
+///
+/// [doc]: https://docs.alchemy.com/reference/eth-gettransactionreceipt
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct TransactionReceiptRequest;
+
+impl IsRpcRequest for TransactionReceiptRequest {
+    type Param = B256;
+    fn method() -> String {
+        "eth_getTransactionReceipt".to_owned()
+    }
+}
+
+/// A receipt as returned by the JSON-RPC API, with only the gas used by the
+/// transaction, hex encoded, e.g. `"0x5208"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTransactionReceipt {
+    /// The gas actually used by the transaction.
+    pub gas_used: U64,
+}
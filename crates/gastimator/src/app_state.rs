@@ -1,20 +1,786 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
 use crate::prelude::*;
 
 #[derive(Debug, Default)]
 pub struct AppState {
     pub cache: Cache,
+
+    /// Local estimates keyed by the fields relevant for local simulation
+    /// only, used if [`GastimatorConfig::kind_stable_cache`] is enabled.
+    pub kind_stable_cache: Cache<KindStableKey, (Gas, Option<GasBreakdown>)>,
+
+    /// Responses keyed by the `Idempotency-Key` header and route of their
+    /// request, with the hash of the request, so that retried requests are
+    /// answered without estimating again.
+    pub idempotent_responses: Cache<IdempotencyKey, (B256, IdempotentResponse)>,
+
+    /// Whether the remote gas estimator has responded successfully at least
+    /// once, used to determine readiness.
+    pub remote_reachable: std::sync::atomic::AtomicBool,
+
+    /// Whether the warm-up has completed, used to determine readiness.
+    pub warmed_up: std::sync::atomic::AtomicBool,
+
+    /// Number of cacheable transactions considered for remote sampling, see
+    /// [`GastimatorConfig::remote_sample_rate`].
+    pub remote_sample_counter: AtomicU64,
+
+    /// Corrects local estimates of transactions not sampled for the remote
+    /// by the divergence of the sampled ones, see [`CorrectionFactor`].
+    pub correction_factor: CorrectionFactor,
+
+    /// Request counts and latencies, see [`Metrics`].
+    pub metrics: Arc<Metrics>,
+
+    /// Estimates being computed, keyed by transaction and mode, so that
+    /// concurrent identical requests share one computation (single-flight)
+    /// instead of each calling the remote. Entries are removed once computed.
+    pub(crate) in_flight: dashmap::DashMap<
+        (Transaction, EstimateMode),
+        Arc<tokio::sync::OnceCell<ComputedEstimates>>,
+    >,
+}
+
+// ========================================
+// Public Implementation
+// ========================================
+impl AppState {
+    /// Creates a new state with an empty cache, whose entries expire after
+    /// `cache_ttl`.
+    pub fn with_cache_ttl(cache_ttl: Duration) -> Self {
+        Self {
+            cache: Cache::new(cache_ttl),
+            kind_stable_cache: Cache::new(cache_ttl),
+            ..Default::default()
+        }
+    }
+}
+
+/// Default time to live of entries in the [`Cache`].
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Default duration responses are stored by their idempotency key, see
+/// [`AppState::idempotent_responses`].
+pub const DEFAULT_IDEMPOTENCY_TTL: Duration = Duration::from_secs(60);
+
+/// Weight of the latest divergence in the [`CorrectionFactor`], a higher
+/// weight follows drift of the local estimates faster, but noisier.
+const CORRECTION_FACTOR_WEIGHT: f64 = 0.1;
+
+/// Factor by which local estimates served without the remote, see
+/// [`GastimatorConfig::remote_sample_rate`], are corrected, the exponentially
+/// weighted moving average of the ratio of the remote to the local estimate
+/// of transactions estimated by both, initially `1.0`.
+#[derive(Debug)]
+pub struct CorrectionFactor(AtomicU64);
+
+impl Default for CorrectionFactor {
+    fn default() -> Self {
+        Self(AtomicU64::new(1.0_f64.to_bits()))
+    }
+}
+
+impl CorrectionFactor {
+    /// The current factor, e.g. `1.05` if the remote estimates have been
+    /// about 5% higher than the local ones.
+    pub fn get(&self) -> f64 {
+        f64::from_bits(self.0.load(Ordering::Relaxed))
+    }
+
+    /// Moves the factor towards the divergence of the `remote` from the
+    /// `local` estimate of a transaction.
+    pub fn update(&self, local: Gas, remote: Gas) {
+        if *local == 0 {
+            return;
+        }
+        let ratio = *remote as f64 / *local as f64;
+        let _ = self
+            .0
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |bits| {
+                let factor = f64::from_bits(bits);
+                Some((factor + CORRECTION_FACTOR_WEIGHT * (ratio - factor)).to_bits())
+            });
+    }
+
+    /// The `local` estimate corrected by the factor, rounded up, but never
+    /// lower than `local`, since an underestimate fails the transaction.
+    pub fn corrected(&self, local: Gas) -> Gas {
+        let corrected = (*local as f64 * self.get()).ceil() as u64;
+        Gas::from(corrected.max(*local))
+    }
+}
+
+/// Minimum interval between sweeps of expired entries of a [`Cache`], so
+/// that a burst of inserts does not scan all entries on every insert.
+const MIN_SWEEP_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Cache of gas usage of transactions, entries expire after `ttl`, after
+/// which they are treated as misses, so that estimates refresh periodically.
+/// Expired entries are removed when looked up, and swept on insert, so that
+/// entries which are never looked up again do not accumulate.
+///
+/// By default keyed by the transaction without its gas limit, see
+/// [`CacheKey`], and [`KindStableKey`] for a key of the fields relevant for a
+/// local estimate only.
+#[derive(Debug, Getters)]
+pub struct Cache<K: Eq + std::hash::Hash = CacheKey, V = CachedEstimate> {
+    entries: dashmap::DashMap<K, (V, Instant)>,
+
+    /// The time to live of entries, e.g. `Duration::from_secs(60)`.
+    #[getset(get = "pub")]
+    ttl: Duration,
+
+    /// Number of lookups of cacheable transactions with a fresh entry.
+    hits: AtomicU64,
+
+    /// Number of lookups of cacheable transactions without a fresh entry.
+    misses: AtomicU64,
+
+    /// Number of transactions which were not cacheable, i.e. lacking `nonce`
+    /// or `from`, and thus never looked up.
+    uncacheable: AtomicU64,
+
+    /// When expired entries were last swept, see [`Cache::insert`].
+    swept_at: std::sync::Mutex<Instant>,
+}
+
+/// An estimate in the [`Cache`], the gas usage and how long it took to
+/// originally compute it.
+#[derive(Debug, Clone, PartialEq, Eq, Builder, Getters, CopyGetters)]
+#[builder(setter(into))]
+pub struct CachedEstimate {
+    /// The estimated gas usage.
+    #[getset(get = "pub")]
+    gas_usage: GasUsage,
+
+    /// The time in milliseconds it took to compute the estimate.
+    #[getset(get_copy = "pub")]
+    compute_millis: u128,
 }
 
-#[derive(Clone, Debug, Default, Deref, DerefMut)]
-pub struct Cache(dashmap::DashMap<Transaction, GasUsage>);
+/// Statistics of the effectiveness of the [`Cache`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Builder, Getters, CopyGetters)]
+#[builder(setter(into))]
+pub struct CacheStats {
+    /// Number of lookups with a fresh entry.
+    #[getset(get_copy = "pub")]
+    hits: u64,
+
+    /// Number of lookups without a fresh entry.
+    #[getset(get_copy = "pub")]
+    misses: u64,
+
+    /// Number of transactions which were not cacheable, i.e. never looked up.
+    #[getset(get_copy = "pub")]
+    uncacheable: u64,
+
+    /// Number of entries, including expired ones not yet removed.
+    #[getset(get_copy = "pub")]
+    size: usize,
+
+    /// `hits / (hits + misses)`, `0.0` if there were no lookups.
+    #[getset(get_copy = "pub")]
+    hit_rate: f64,
+}
+
+/// The response of clearing the [`Cache`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, CopyGetters)]
+pub struct CacheClearResponse {
+    /// Number of entries removed, including expired ones.
+    #[getset(get_copy = "pub")]
+    removed: usize,
+}
+
+impl From<usize> for CacheClearResponse {
+    fn from(removed: usize) -> Self {
+        Self { removed }
+    }
+}
+
+impl<K: Eq + std::hash::Hash, V: Clone + std::fmt::Debug> Default for Cache<K, V> {
+    fn default() -> Self {
+        Self::new(DEFAULT_CACHE_TTL)
+    }
+}
+
+impl<K: Eq + std::hash::Hash, V: Clone + std::fmt::Debug> Cache<K, V> {
+    /// Creates a new empty cache whose entries expire after `ttl`.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entries: dashmap::DashMap::new(),
+            ttl,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            uncacheable: AtomicU64::new(0),
+            swept_at: std::sync::Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Returns the cached estimate of `key`, e.g. a transaction, if any and
+    /// not expired, expired entries are removed.
+    pub fn get(&self, key: &K) -> Option<V> {
+        let fresh = self.get_fresh(key);
+        let counter = if fresh.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        fresh
+    }
+
+    /// Records that a transaction was not cacheable, and thus not looked up.
+    pub fn record_uncacheable(&self) {
+        self.uncacheable.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Statistics of the effectiveness of the cache.
+    pub fn stats(&self) -> CacheStats {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let lookups = hits + misses;
+        CacheStats {
+            hits,
+            misses,
+            uncacheable: self.uncacheable.load(Ordering::Relaxed),
+            size: self.len(),
+            hit_rate: if lookups == 0 {
+                0.0
+            } else {
+                hits as f64 / lookups as f64
+            },
+        }
+    }
+
+    /// Caches the estimate of `key`, e.g. a transaction, replacing any
+    /// existing entry. Expired entries are swept first, at most once per
+    /// `ttl`, or [`MIN_SWEEP_INTERVAL`] if shorter.
+    pub fn insert(&self, key: K, estimate: V) {
+        self.sweep_if_due();
+        self.entries.insert(key, (estimate, Instant::now()));
+    }
+
+    /// Removes all entries, returning the number of entries removed.
+    /// Statistics are kept.
+    pub fn clear(&self) -> usize {
+        let removed = self.len();
+        self.entries.clear();
+        removed
+    }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Builder, Getters)] // deserialize for tests
+    /// Removes all expired entries, returning the number of entries removed.
+    pub fn remove_expired(&self) -> usize {
+        let before = self.len();
+        self.entries
+            .retain(|_, (_, inserted_at)| inserted_at.elapsed() < self.ttl);
+        before.saturating_sub(self.len())
+    }
+
+    /// The number of entries, including expired ones not yet removed.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the cache has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+// ========================================
+// Private Implementation
+// ========================================
+impl<K: Eq + std::hash::Hash, V: Clone + std::fmt::Debug> Cache<K, V> {
+    /// Removes expired entries if the last sweep is older than the sweep
+    /// interval, by one caller at a time.
+    fn sweep_if_due(&self) {
+        let Ok(mut swept_at) = self.swept_at.try_lock() else {
+            return;
+        };
+        if swept_at.elapsed() < max(self.ttl, MIN_SWEEP_INTERVAL) {
+            return;
+        }
+        *swept_at = Instant::now();
+        drop(swept_at);
+        let removed = self.remove_expired();
+        if removed > 0 {
+            debug!("Swept {removed} expired cache entries");
+        }
+    }
+
+    fn get_fresh(&self, key: &K) -> Option<V> {
+        let (estimate, inserted_at) = self.entries.get(key)?.value().clone();
+        if inserted_at.elapsed() < self.ttl {
+            return Some(estimate);
+        }
+        debug!("Cached estimate expired: {:?}", estimate);
+        self.entries
+            .remove_if(key, |_, (_, at)| at.elapsed() >= self.ttl);
+        None
+    }
+}
+
+#[derive(
+    Debug, Clone, Serialize, Deserialize, PartialEq, Builder, Getters, CopyGetters, Setters,
+)] // deserialize for tests
 #[builder(setter(into))]
 pub struct GasEstimateResponse {
-    /// The gas used by the transaction.
+    /// Kind of transaction, same as the `kind` nested in `gas_usage`, put
+    /// at top level for convenience.
     #[getset(get = "pub")]
+    kind: TransactionKind,
+
+    /// The gas used by the transaction.
+    #[getset(get = "pub", set = "pub(crate)")]
     gas_usage: GasUsage,
 
+    /// How the estimate was obtained, e.g. `both` if local and remote
+    /// succeeded or `remote_only` if the local simulation failed. Defaults to
+    /// `both` if missing.
+    #[serde(default)]
+    #[getset(get_copy = "pub")]
+    source: EstimateSource,
+
+    /// Whether the estimate was served from the [`Cache`], in which case
+    /// `source` is `cached`. Defaults to `false` if missing.
+    #[builder(default)]
+    #[serde(default)]
+    #[getset(get_copy = "pub", set = "pub(crate)")]
+    from_cache: bool,
+
     #[getset(get = "pub")]
     time_elapsed_in_millis: u128,
+
+    /// The gas used by the transaction before being padded with the safety
+    /// margin, only present if `gas_usage` was padded.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub(crate)")]
+    unpadded_gas_usage: Option<GasUsage>,
+
+    /// The time in milliseconds it took to originally compute the estimate,
+    /// only present on cache hits, for which `time_elapsed_in_millis` is
+    /// just the time of the cache lookup.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub(crate)")]
+    original_compute_millis: Option<u128>,
+
+    /// Breakdown of the gas used by the local simulation into intrinsic,
+    /// calldata and execution gas. `None` if the local simulation failed
+    /// or was not performed (e.g. cache hits and native token transfers).
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub(crate)")]
+    gas_breakdown: Option<GasBreakdown>,
+
+    /// The blob gas used by an EIP-4844 transaction, a separate dimension
+    /// from the execution gas in `gas_usage`. `None` if the transaction
+    /// does not carry any blobs.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub(crate)")]
+    blob_gas: Option<Gas>,
+
+    /// The blob base fee (in wei per blob gas) used to price the blobs of
+    /// an EIP-4844 transaction, `None` if the transaction does not carry
+    /// any blobs.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub(crate)")]
+    blob_base_fee: Option<u128>,
+
+    /// The cost of the blobs (in wei), `blob_gas * blob_base_fee`.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub(crate)")]
+    blob_fee: Option<U256>,
+
+    /// The estimated fee (in wei) of the execution gas, `gas * fee_per_gas`
+    /// where gas is the high bound of a range and fee per gas the
+    /// `max_fee_per_gas`, or else `max_priority_fee_per_gas`, of the request
+    /// options, i.e. an upper bound. `None` if no fee per gas was passed.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub(crate)")]
+    estimated_fee_wei: Option<U256>,
+
+    /// The raw JSON-RPC response of the remote `eth_estimateGas` call,
+    /// unmodified. Only present if requested with `raw_remote=true` and if
+    /// the remote estimator was called (e.g. not for cache hits).
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub(crate)")]
+    raw_remote_response: Option<String>,
+
+    /// Warnings about the transaction, e.g. a gas limit far above the
+    /// estimate, empty if there are none.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[getset(get = "pub", set = "pub(crate)")]
+    warnings: Vec<EstimateWarning>,
+
+    /// The transaction decoded from the RLP of the request, only present if
+    /// requested with `decoded=true` on `/rlp`.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub")]
+    decoded_transaction: Option<Transaction>,
+
+    /// The id of the request, passed in its `X-Request-Id` header or
+    /// generated by the server, to correlate the response with log lines.
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub")]
+    request_id: Option<RequestId>,
+}
+
+impl GasEstimateResponse {
+    /// This response with `tx`, the transaction decoded from RLP, included.
+    pub fn with_decoded_transaction(self, tx: Transaction) -> Self {
+        Self {
+            decoded_transaction: Some(tx),
+            ..self
+        }
+    }
+
+    /// This response with the id of its request, see [`RequestId`].
+    pub fn with_request_id(self, request_id: RequestId) -> Self {
+        Self {
+            request_id: Some(request_id),
+            ..self
+        }
+    }
+}
+
+/// An item of the response of a batch gas estimate, either the estimate of
+/// the transaction at the same index in the batch, or the error estimating
+/// it, so that partial failures do not fail the whole batch.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)] // deserialize for tests
+#[serde(untagged)]
+pub enum BatchGasEstimateItem {
+    /// The transaction was successfully estimated.
+    Estimate(Box<GasEstimateResponse>),
+
+    /// Estimating the transaction failed.
+    Error {
+        /// Description of the error, e.g. `"Gas exceeds limit"`.
+        error: String,
+    },
+}
+
+impl From<Result<GasEstimateResponse>> for BatchGasEstimateItem {
+    fn from(result: Result<GasEstimateResponse>) -> Self {
+        match result {
+            Ok(response) => Self::Estimate(Box::new(response)),
+            Err(e) => Self::Error {
+                error: e.to_string(),
+            },
+        }
+    }
+}
+
+/// A response stored under an [`IdempotencyKey`], of a single or a batch
+/// estimate, see [`AppState::idempotent_responses`].
+#[derive(Debug, Clone, PartialEq, derive_more::From, derive_more::TryInto)]
+pub enum IdempotentResponse {
+    /// The response of a single estimate, e.g. of `/tx`.
+    Estimate(GasEstimateResponse),
+
+    /// The response of a batch estimate, of `/tx/batch`.
+    Batch(Vec<BatchGasEstimateItem>),
+}
+
+/// The response of a simulation, the gas estimate of the transaction and
+/// optionally the changes to the state made by it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Getters)] // deserialize for tests
+pub struct SimulationResponse {
+    /// The gas estimate of the transaction, flattened into the response.
+    #[serde(flatten)]
+    #[getset(get = "pub")]
+    estimate: GasEstimateResponse,
+
+    /// The changes to the state of all accounts changed by the transaction,
+    /// only present if requested with `state_diff=true`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub")]
+    state_diff: Option<StateDiff>,
+}
+
+impl SimulationResponse {
+    /// A response of `estimate` and optionally `state_diff`.
+    pub fn new(estimate: GasEstimateResponse, state_diff: impl Into<Option<StateDiff>>) -> Self {
+        Self {
+            estimate,
+            state_diff: state_diff.into(),
+        }
+    }
+}
+
+/// The response of simulating a bundle of transactions in order against
+/// shared state, the gas used by each transaction and in total.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Getters, CopyGetters)] // deserialize for tests
+pub struct BundleSimulationResponse {
+    /// The gas used by each transaction, in the order of the bundle.
+    #[getset(get = "pub")]
+    gas_used: Vec<Gas>,
+
+    /// The sum of the gas used by all transactions.
+    #[getset(get_copy = "pub")]
+    total_gas: Gas,
+}
+
+impl BundleSimulationResponse {
+    /// A response of the gas used by each transaction of a bundle, summing
+    /// up the total.
+    pub fn new(gas_used: Vec<Gas>) -> Self {
+        let total_gas = Gas::from(gas_used.iter().map(|gas| **gas).sum::<u64>());
+        Self {
+            gas_used,
+            total_gas,
+        }
+    }
+}
+
+/// The response of estimating a replacement of a pending transaction, the
+/// estimates of both transactions and whether their gas usage is equivalent,
+/// which it should be if only the fees differ.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Getters, CopyGetters)] // deserialize for tests
+pub struct ReplacementResponse {
+    /// Whether the gas usage of `original` and `replacement` is equal.
+    #[getset(get_copy = "pub")]
+    equivalent: bool,
+
+    /// The gas estimate of the original transaction.
+    #[getset(get = "pub")]
+    original: GasEstimateResponse,
+
+    /// The gas estimate of the replacement transaction.
+    #[getset(get = "pub")]
+    replacement: GasEstimateResponse,
+}
+
+impl ReplacementResponse {
+    /// A response of the estimates of `original` and `replacement`.
+    pub fn new(original: GasEstimateResponse, replacement: GasEstimateResponse) -> Self {
+        Self {
+            equivalent: original.gas_usage() == replacement.gas_usage(),
+            original,
+            replacement,
+        }
+    }
+}
+
+/// How a transaction is classified, without estimating it, e.g. to debug why
+/// it is of kind [`TransactionKind::Unknown`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Getters, CopyGetters)] // deserialize for tests
+pub struct ClassificationResponse {
+    /// The kind of the transaction.
+    #[getset(get = "pub")]
+    kind: TransactionKind,
+
+    /// Whether estimates of the transaction are cached, i.e. it has a
+    /// `nonce` and `from`.
+    #[getset(get_copy = "pub")]
+    cacheable: bool,
+
+    /// The gas charged before execution, which any estimate is at least.
+    #[getset(get_copy = "pub")]
+    intrinsic_gas: Gas,
+}
+
+impl From<&Transaction> for ClassificationResponse {
+    fn from(tx: &Transaction) -> Self {
+        Self {
+            kind: tx.kind(),
+            cacheable: tx.is_cacheable(),
+            intrinsic_gas: tx.intrinsic_gas(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    type Sut = GasEstimateResponse;
+
+    #[test]
+    fn serialize_kind_at_top_level() {
+        let kind = TransactionKind::ContractCall {
+            with_native_token_transfer: false,
+        };
+        let sut = GasEstimateResponseBuilder::default()
+            .kind(kind.clone())
+            .gas_usage(GasUsage::Estimate {
+                kind,
+                gas: Gas::from(54321),
+            })
+            .source(EstimateSource::RemoteOnly)
+            .time_elapsed_in_millis(7u128)
+            .build()
+            .unwrap();
+
+        let json = serde_json::to_value(&sut).unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "kind": {"contract_call": {"with_native_token_transfer": false}},
+                "gas_usage": {
+                    "estimate": {
+                        "kind": {"contract_call": {"with_native_token_transfer": false}},
+                        "gas": 54321
+                    }
+                },
+                "source": "remote_only",
+                "from_cache": false,
+                "time_elapsed_in_millis": 7
+            })
+        );
+        let deserialized: Sut = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(deserialized, sut);
+
+        let mut without_defaults = json;
+        without_defaults
+            .as_object_mut()
+            .unwrap()
+            .remove("from_cache");
+        without_defaults.as_object_mut().unwrap().remove("source");
+        let deserialized: Sut = serde_json::from_value(without_defaults).unwrap();
+        assert!(!deserialized.from_cache());
+        assert_eq!(deserialized.source(), EstimateSource::Both);
+    }
+
+    #[test]
+    fn serialize_batch_item_error() {
+        let item = BatchGasEstimateItem::from(Err(Error::EmptyTransaction));
+        assert_eq!(
+            serde_json::to_value(&item).unwrap(),
+            json!({
+                "error": "Empty transaction, without `to`, `input` and `value`, cannot be estimated"
+            })
+        );
+    }
+
+    fn cached_usage() -> CachedEstimate {
+        CachedEstimateBuilder::default()
+            .gas_usage(GasUsage::Exact {
+                kind: TransactionKind::NativeTokenTransfer,
+                gas: Gas::from(21_000),
+            })
+            .compute_millis(42u128)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn cache_hit_before_ttl() {
+        let sut = Cache::new(Duration::from_secs(60));
+        let tx = Transaction::sample_native_token_transfer_cachable();
+        sut.insert(tx.clone(), cached_usage());
+        assert_eq!(sut.get(&tx), Some(cached_usage()));
+    }
+
+    #[test]
+    fn cache_remove_expired() {
+        let sut = Cache::new(Duration::ZERO);
+        sut.insert(
+            Transaction::sample_native_token_transfer_cachable(),
+            cached_usage(),
+        );
+        sut.insert(
+            Transaction::sample_contract_creation_cachable(),
+            cached_usage(),
+        );
+        assert_eq!(sut.remove_expired(), 2);
+        assert!(sut.is_empty());
+    }
+
+    #[test]
+    fn cache_sweeps_expired_entries_on_insert() {
+        let sut = Cache::new(Duration::ZERO);
+        sut.insert(
+            Transaction::sample_native_token_transfer_cachable(),
+            cached_usage(),
+        );
+        assert_eq!(sut.len(), 1);
+        // Never looked up again, but swept once the sweep interval passed
+        let mut swept_at = sut.swept_at.lock().unwrap();
+        *swept_at = swept_at.checked_sub(MIN_SWEEP_INTERVAL).unwrap();
+        drop(swept_at);
+
+        let tx = Transaction::sample_contract_creation_cachable();
+        sut.insert(tx.clone(), cached_usage());
+
+        assert_eq!(sut.len(), 1);
+        assert!(sut.entries.contains_key(&tx));
+    }
+
+    #[test]
+    fn cache_expired_entry_is_miss_and_removed() {
+        let sut = Cache::new(Duration::ZERO);
+        let tx = Transaction::sample_native_token_transfer_cachable();
+        sut.insert(tx.clone(), cached_usage());
+        assert_eq!(sut.get(&tx), None);
+        assert!(sut.is_empty());
+    }
+
+    #[test]
+    fn cache_stats() {
+        let sut = Cache::new(Duration::from_secs(60));
+        let tx = Transaction::sample_native_token_transfer_cachable();
+        assert_eq!(sut.get(&tx), None);
+        sut.insert(tx.clone(), cached_usage());
+        assert_eq!(sut.get(&tx), Some(cached_usage()));
+        assert_eq!(sut.get(&tx), Some(cached_usage()));
+        sut.record_uncacheable();
+
+        let stats = sut.stats();
+        assert_eq!(stats.hits(), 2);
+        assert_eq!(stats.misses(), 1);
+        assert_eq!(stats.uncacheable(), 1);
+        assert_eq!(stats.size(), 1);
+        assert!((stats.hit_rate() - 2.0 / 3.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn cache_stats_hit_rate_without_lookups() {
+        assert_eq!(<Cache>::default().stats().hit_rate(), 0.0);
+    }
+
+    #[test]
+    fn cache_clear_returns_number_removed() {
+        let sut = Cache::new(Duration::from_secs(60));
+        let tx = Transaction::sample_native_token_transfer_cachable();
+        sut.insert(tx.clone(), cached_usage());
+
+        assert_eq!(sut.clear(), 1);
+        assert!(sut.is_empty());
+        assert_eq!(sut.get(&tx), None);
+        assert_eq!(sut.clear(), 0);
+    }
+
+    #[test]
+    fn correction_factor_moves_towards_divergence() {
+        let sut = CorrectionFactor::default();
+        assert_eq!(sut.get(), 1.0);
+
+        sut.update(Gas::from(40_000), Gas::from(50_000));
+
+        assert!((sut.get() - 1.025).abs() < 1e-9);
+        assert_eq!(sut.corrected(Gas::from(40_000)), Gas::from(41_000));
+    }
+
+    #[test]
+    fn correction_factor_never_corrects_below_local() {
+        let sut = CorrectionFactor::default();
+
+        sut.update(Gas::from(50_000), Gas::from(40_000));
+
+        assert!(sut.get() < 1.0);
+        assert_eq!(sut.corrected(Gas::from(50_000)), Gas::from(50_000));
+    }
 }
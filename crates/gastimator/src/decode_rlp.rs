@@ -1,7 +1,95 @@
 use crate::prelude::*;
 
 use alloy_consensus::transaction::RlpEcdsaDecodableTx;
-use alloy_consensus::{Signed, TxEip1559};
+use alloy_consensus::{Signed, TxEip1559, TxEip4844, TxEip4844Variant};
+
+/// The EIP-2718 type of legacy transactions, which are not prefixed with a
+/// type byte.
+pub const LEGACY_TX_TYPE: u8 = 0x00;
+
+/// The EIP-2718 type byte of EIP-1559 (dynamic fee) transactions.
+pub const EIP1559_TX_TYPE: u8 = 0x02;
+
+/// The EIP-2718 type byte of EIP-4844 (blob carrying) transactions.
+pub const EIP4844_TX_TYPE: u8 = 0x03;
+
+/// The smallest first byte of an RLP encoded list, an EIP-2718 type byte is
+/// always smaller than this, so transactions starting with a byte at least
+/// this large are either legacy or unsigned transactions without type byte.
+const RLP_LIST_PREFIX: u8 = 0xc0;
+
+/// Decodes a supported transaction, inspecting its leading EIP-2718 type byte,
+/// into a [`Transaction`], with `from` set to the signer recovered from the
+/// signature if the transaction is signed.
+///
+/// A signature from which no signer can be recovered is ignored, leaving
+/// `from` unset, since it has no impact on gas usage.
+///
+/// # Throws
+/// Throws [`Error::UnsupportedTransactionType`] if the transaction is neither
+/// EIP-1559 nor EIP-4844, e.g. a legacy or EIP-2930 (type `0x01`) transaction.
+pub fn decode_transaction(raw_tx: impl AsRef<[u8]>) -> Result<Transaction, Error> {
+    let raw_tx = raw_tx.as_ref();
+    let mut tx = _decode_transaction_without_signer(raw_tx)?;
+    match _recover_signer_if_signed(raw_tx) {
+        Some(Ok(signer)) => {
+            tx.set_from(Some(signer));
+        }
+        Some(Err(error)) => warn!("Decoded transaction without signer: {error}"),
+        None => {}
+    }
+    Ok(tx)
+}
+
+fn _decode_transaction_without_signer(raw_tx: &[u8]) -> Result<Transaction, Error> {
+    match raw_tx.first() {
+        Some(&EIP4844_TX_TYPE) => decode_eip4844_transaction(raw_tx).map(Transaction::from_eip4844),
+        Some(&type_byte) if type_byte < RLP_LIST_PREFIX && type_byte != EIP1559_TX_TYPE => {
+            Err(Error::UnsupportedTransactionType { type_byte })
+        }
+        Some(&type_byte) if type_byte >= RLP_LIST_PREFIX => {
+            // Either an unsigned EIP-1559 transaction or a legacy transaction,
+            // which lacks a type byte.
+            decode_eip1559_transaction(raw_tx)
+                .map(Transaction::from_eip1559)
+                .map_err(|_| Error::UnsupportedTransactionType {
+                    type_byte: LEGACY_TX_TYPE,
+                })
+        }
+        _ => decode_eip1559_transaction(raw_tx).map(Transaction::from_eip1559),
+    }
+}
+
+/// Recovers the signer of a signed EIP-1559 or EIP-4844 transaction from its
+/// signature, `None` if the transaction is unsigned.
+///
+/// # Throws
+/// Throws [`Error::InvalidSignature`] if the transaction is signed but its
+/// signer cannot be recovered, else fails as [`decode_transaction`] if the
+/// transaction cannot be decoded.
+pub fn recover_signer(raw_tx: impl AsRef<[u8]>) -> Result<Option<Address>, Error> {
+    let raw_tx = raw_tx.as_ref();
+    match _recover_signer_if_signed(raw_tx) {
+        Some(signer) => signer.map(Some),
+        None => _decode_transaction_without_signer(raw_tx).map(|_| None),
+    }
+}
+
+/// The signer recovered from the signature of `raw_tx`, `None` if it is not
+/// a signed EIP-1559 or EIP-4844 transaction.
+fn _recover_signer_if_signed(raw_tx: &[u8]) -> Option<Result<Address, Error>> {
+    let recovered = match raw_tx.first() {
+        Some(&EIP4844_TX_TYPE) => _decode_eip4844_transaction_signed(raw_tx)
+            .ok()?
+            .recover_signer(),
+        _ => _decode_eip1559_transaction_signed(raw_tx)
+            .ok()?
+            .recover_signer(),
+    };
+    Some(recovered.map_err(|e| Error::InvalidSignature {
+        underlying: e.to_string(),
+    }))
+}
 
 pub fn decode_eip1559_transaction(raw_tx: impl AsRef<[u8]>) -> Result<TxEip1559, Error> {
     if let Ok(signed_tx) = _decode_eip1559_transaction_signed(raw_tx.as_ref()) {
@@ -27,6 +115,36 @@ fn _decode_eip1559_transaction_signed(
         .map_err(Error::decode_rlp_decode_bytes_into_signed_eip1559)
 }
 
+/// Decodes a signed EIP-4844 transaction, either with or without its blob
+/// sidecar (network encoding), prefixed with the `0x03` type byte, or an
+/// unsigned EIP-4844 transaction. The sidecar is discarded, it has no
+/// impact on gas usage.
+pub fn decode_eip4844_transaction(raw_tx: impl AsRef<[u8]>) -> Result<TxEip4844, Error> {
+    if let Ok(signed_tx) = _decode_eip4844_transaction_signed(raw_tx.as_ref()) {
+        Ok(TxEip4844::from(signed_tx.tx().clone()))
+    } else {
+        _decode_eip4844_transaction_not_signed(raw_tx)
+    }
+}
+
+fn _decode_eip4844_transaction_not_signed(raw_tx: impl AsRef<[u8]>) -> Result<TxEip4844, Error> {
+    let mut buf = raw_tx.as_ref();
+    TxEip4844Variant::rlp_decode(&mut buf)
+        .map(TxEip4844::from)
+        .map_err(Error::decode_rlp_decode_bytes_into_eip4844)
+}
+
+fn _decode_eip4844_transaction_signed(
+    raw_tx: impl AsRef<[u8]>,
+) -> Result<Signed<TxEip4844Variant>, Error> {
+    let mut buf = raw_tx.as_ref();
+    if buf.starts_with(&[EIP4844_TX_TYPE]) {
+        buf = &buf[1..];
+    }
+    TxEip4844Variant::rlp_decode_signed(&mut buf)
+        .map_err(Error::decode_rlp_decode_bytes_into_signed_eip4844)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -47,4 +165,151 @@ mod tests {
 
         assert_eq!(tx, tx2)
     }
+
+    #[test]
+    fn decode_rlp_eip4844() {
+        use alloy_consensus::transaction::RlpEcdsaEncodableTx;
+        use alloy_primitives::{PrimitiveSignature, b256};
+
+        let tx = TxEip4844 {
+            chain_id: 1,
+            nonce: 7,
+            gas_limit: 50_000,
+            max_fee_per_gas: 20_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+            to: Address::from([0x12; 20]),
+            value: U256::ZERO,
+            access_list: Default::default(),
+            blob_versioned_hashes: vec![b256!(
+                "0x01b0a4cdd5f55589f5c5b4d46c76704bb6ce95c0a8c09f77f197a57808dded28"
+            )],
+            max_fee_per_blob_gas: 3_000_000_000,
+            input: Bytes::from_static(&[0xde, 0xad]),
+        };
+        let signature = PrimitiveSignature::new(U256::from(1), U256::from(2), false);
+        let mut raw_tx_signed = vec![EIP4844_TX_TYPE];
+        tx.rlp_encode_signed(&signature, &mut raw_tx_signed);
+
+        let decoded = decode_eip4844_transaction(&raw_tx_signed).unwrap();
+        assert_eq!(decoded, tx);
+
+        let mut raw_tx_not_signed = Vec::new();
+        tx.rlp_encode(&mut raw_tx_not_signed);
+        let decoded = decode_eip4844_transaction(&raw_tx_not_signed).unwrap();
+        assert_eq!(decoded, tx);
+    }
+
+    #[test]
+    fn decode_legacy_is_unsupported() {
+        use alloy_consensus::TxLegacy;
+        use alloy_consensus::transaction::RlpEcdsaEncodableTx;
+        use alloy_primitives::PrimitiveSignature;
+
+        let tx = TxLegacy {
+            chain_id: Some(1),
+            nonce: 7,
+            gas_price: 20_000_000_000,
+            gas_limit: 21_000,
+            to: TxKind::Call(Address::from([0x12; 20])),
+            value: U256::from(1),
+            input: Bytes::new(),
+        };
+        let signature = PrimitiveSignature::new(U256::from(1), U256::from(2), false);
+        let mut raw_tx = Vec::new();
+        tx.rlp_encode_signed(&signature, &mut raw_tx);
+
+        assert_eq!(
+            decode_transaction(&raw_tx),
+            Err(Error::UnsupportedTransactionType {
+                type_byte: LEGACY_TX_TYPE
+            })
+        );
+    }
+
+    #[test]
+    fn decode_eip2930_is_unsupported() {
+        use alloy_consensus::TxEip2930;
+        use alloy_consensus::transaction::RlpEcdsaEncodableTx;
+        use alloy_primitives::PrimitiveSignature;
+
+        let tx = TxEip2930 {
+            chain_id: 1,
+            nonce: 7,
+            gas_price: 20_000_000_000,
+            gas_limit: 21_000,
+            to: TxKind::Call(Address::from([0x12; 20])),
+            value: U256::from(1),
+            access_list: Default::default(),
+            input: Bytes::new(),
+        };
+        let signature = PrimitiveSignature::new(U256::from(1), U256::from(2), false);
+        let mut raw_tx = vec![0x01];
+        tx.rlp_encode_signed(&signature, &mut raw_tx);
+
+        assert_eq!(
+            decode_transaction(&raw_tx),
+            Err(Error::UnsupportedTransactionType { type_byte: 0x01 })
+        );
+    }
+
+    #[test]
+    fn recover_signer_of_signed() {
+        // https://etherscan.io/tx/0xb1869db00d08d706059ae6a167b9d89b01884606ee4dec42c19c9c6466471542
+        let raw_tx_signed = hex!(
+            "02f87201824f4c83142ebf842d441366825208942e575fe17124f7ef2d22bbfb33cf3dbfc3f002d68711c37937e0800080c001a0152c51f0aa71d7698b486a34f8ffc9b61cc7a000c34d48e1cf9361d8973ba518a024216a87cb193b7e502ad9ddbcfc9674c40fe98bd4a7bda575ba03185621cd13"
+        );
+        assert_eq!(
+            recover_signer(raw_tx_signed),
+            Ok(Some(alloy_primitives::address!(
+                "0xd5b1d4e6626748968daee303133d2be5e2353f44"
+            )))
+        );
+    }
+
+    #[test]
+    fn decode_signed_sets_from() {
+        // https://etherscan.io/tx/0xb1869db00d08d706059ae6a167b9d89b01884606ee4dec42c19c9c6466471542
+        let raw_tx_signed = hex!(
+            "02f87201824f4c83142ebf842d441366825208942e575fe17124f7ef2d22bbfb33cf3dbfc3f002d68711c37937e0800080c001a0152c51f0aa71d7698b486a34f8ffc9b61cc7a000c34d48e1cf9361d8973ba518a024216a87cb193b7e502ad9ddbcfc9674c40fe98bd4a7bda575ba03185621cd13"
+        );
+        let raw_tx_not_signed = hex!(
+            "ef01824f4c83142ebf842d441366825208942e575fe17124f7ef2d22bbfb33cf3dbfc3f002d68711c37937e0800080c0"
+        );
+
+        let signed = decode_transaction(raw_tx_signed).unwrap();
+        let not_signed = decode_transaction(raw_tx_not_signed).unwrap();
+
+        assert_eq!(
+            *signed.from(),
+            Some(alloy_primitives::address!(
+                "0xd5b1d4e6626748968daee303133d2be5e2353f44"
+            ))
+        );
+        assert!(signed.is_cacheable());
+        assert_eq!(*not_signed.from(), None);
+        assert!(!not_signed.is_cacheable());
+    }
+
+    #[test]
+    fn recover_signer_of_unsigned_is_none() {
+        let raw_tx_not_signed = hex!(
+            "ef01824f4c83142ebf842d441366825208942e575fe17124f7ef2d22bbfb33cf3dbfc3f002d68711c37937e0800080c0"
+        );
+        assert_eq!(recover_signer(raw_tx_not_signed), Ok(None));
+        assert!(recover_signer(hex!("deadbeef")).is_err());
+    }
+
+    #[test]
+    fn decode_eip1559_with_and_without_type_byte() {
+        let raw_tx_signed = hex!(
+            "02f87201824f4c83142ebf842d441366825208942e575fe17124f7ef2d22bbfb33cf3dbfc3f002d68711c37937e0800080c001a0152c51f0aa71d7698b486a34f8ffc9b61cc7a000c34d48e1cf9361d8973ba518a024216a87cb193b7e502ad9ddbcfc9674c40fe98bd4a7bda575ba03185621cd13"
+        );
+        let raw_tx_not_signed = hex!(
+            "ef01824f4c83142ebf842d441366825208942e575fe17124f7ef2d22bbfb33cf3dbfc3f002d68711c37937e0800080c0"
+        );
+        let signed = decode_transaction(raw_tx_signed).unwrap();
+        let mut not_signed = decode_transaction(raw_tx_not_signed).unwrap();
+        not_signed.set_from(*signed.from());
+        assert_eq!(signed, not_signed);
+    }
 }
@@ -0,0 +1,451 @@
+use crate::prelude::*;
+
+use alloy::{
+    consensus::BlockHeader,
+    eips::{BlockId, BlockNumberOrTag},
+    network::{BlockResponse, primitives::HeaderResponse},
+    providers::{DynProvider, Provider, ProviderBuilder},
+};
+use revm::{
+    Context, MainBuilder, MainContext,
+    context::{BlockEnv, TxEnv},
+    database::{AlloyDB, CacheDB, DatabaseRef, WrapDatabaseAsync},
+};
+use tokio::runtime::Handle;
+
+/// A source of chain state which a [`ForkingTxSimulator`] forks from, e.g.
+/// a trusted RPC node.
+pub trait ForkStateSource {
+    /// The database reading state at a specific block.
+    type Db: DatabaseRef<Error: std::fmt::Display>;
+
+    /// The block `block`, e.g. the latest block or the block with a specific
+    /// number.
+    fn block(&self, block: BlockNumberOrTag) -> Result<ForkBlock>;
+
+    /// A database reading all state at the block with hash `block_hash`.
+    fn state_at(&self, block_hash: B256) -> Result<Self::Db>;
+}
+
+/// A block which a [`ForkingTxSimulator`] forks at, transactions are
+/// simulated against the state at it, in its environment.
+#[derive(Debug, Clone, Builder, Getters, CopyGetters)]
+#[builder(setter(into))]
+pub struct ForkBlock {
+    /// The hash of the block, pinning the state.
+    #[getset(get_copy = "pub")]
+    hash: B256,
+
+    /// The environment of the block, e.g. number, timestamp, base fee and
+    /// gas limit, without blob gas price.
+    #[getset(get = "pub")]
+    env: BlockEnv,
+
+    /// The excess blob gas of the block, from which the blob base fee is
+    /// derived, `None` before Cancun.
+    #[builder(default)]
+    #[getset(get_copy = "pub")]
+    excess_blob_gas: Option<u64>,
+}
+
+/// An EVM transaction simulator which forks the state of a remote chain,
+/// giving higher fidelity than simulating against empty state.
+///
+/// Each simulation pins the latest block hash at its start (fetched once)
+/// and reads all state at that block, so that all storage reads within one
+/// simulation are consistent, even if a new block arrives mid-simulation.
+/// Alternatively all simulations fork at a fixed block, see
+/// [`Self::at_block`], e.g. to reproduce the gas used of a mined transaction,
+/// or a transaction specifies the block to fork at, see
+/// [`Transaction::block_number`]. Transactions are simulated in the
+/// environment of the pinned block, e.g. its number, timestamp and base fee.
+pub struct ForkingTxSimulator<S> {
+    source: S,
+    chain_id: u64,
+    spec: SpecId,
+
+    /// The number of the block to fork at, if `None` the latest block.
+    block_number: Option<u64>,
+
+    /// The block with `block_number`, fetched at the first simulation.
+    fixed_block: std::sync::OnceLock<ForkBlock>,
+}
+
+/// A [`ForkStateSource`] reading state over JSON-RPC using `alloy`.
+#[derive(Clone)]
+pub struct AlloyForkStateSource {
+    provider: DynProvider,
+}
+
+// ========================================
+// Public Implementation
+// ========================================
+impl<S: ForkStateSource> ForkingTxSimulator<S> {
+    /// Creates a new simulator forking state from `source`, simulating
+    /// transactions on the chain with id `chain_id` using `spec`.
+    pub fn new(source: S, chain_id: u64, spec: SpecId) -> Self {
+        Self {
+            source,
+            chain_id,
+            spec,
+            block_number: None,
+            fixed_block: Default::default(),
+        }
+    }
+
+    /// Forks all simulations at the block with number `block_number`
+    /// instead of at the latest block, unless a transaction specifies the
+    /// block to fork at.
+    pub fn at_block(self, block_number: impl Into<Option<u64>>) -> Self {
+        Self {
+            block_number: block_number.into(),
+            fixed_block: Default::default(),
+            ..self
+        }
+    }
+}
+
+impl AlloyForkStateSource {
+    /// Creates a new source reading state from the JSON-RPC node at `url`.
+    pub fn new(url: impl AsRef<str>) -> Result<Self> {
+        let url = url
+            .as_ref()
+            .parse()
+            .map_err(Error::fork_state_unavailable)?;
+        let provider = ProviderBuilder::new().on_http(url).erased();
+        Ok(Self { provider })
+    }
+}
+
+// ========================================
+// Private Implementation
+// ========================================
+impl<S: ForkStateSource> ForkingTxSimulator<S> {
+    /// Pins the block to fork at and simulates `tx` against the state at it.
+    fn simulate_tx_at_pinned_block(&self, tx: &Transaction) -> Result<(Gas, GasBreakdown)> {
+        let (mut evm, tx) = self.fork_at_pinned_block(tx)?;
+        RevmTxSimulator::simulate_tx(&mut evm, tx)
+    }
+
+    /// The block to fork at for `tx`, the block of `tx` if any, else the
+    /// configured block if any, else the latest block.
+    fn pinned_block(&self, tx: &Transaction) -> Result<ForkBlock> {
+        if let Some(block) = tx.block_number() {
+            return self.source.block(*block);
+        }
+        let Some(block_number) = self.block_number else {
+            return self.source.block(BlockNumberOrTag::Latest);
+        };
+        if let Some(block) = self.fixed_block.get() {
+            return Ok(block.clone());
+        }
+        let block = self.source.block(block_number.into())?;
+        Ok(self.fixed_block.get_or_init(|| block).clone())
+    }
+
+    /// An EVM forking the state at the pinned block, in its environment,
+    /// and `tx` as simulated on it.
+    fn fork_at_pinned_block(&self, tx: &Transaction) -> Result<(EVM<CacheDB<S::Db>>, TxEnv)> {
+        let pinned = self.pinned_block(tx)?;
+        debug!("Forking state at pinned block: {}", pinned.hash());
+        let mut db = CacheDB::new(self.source.state_at(pinned.hash())?);
+        if let Some(state_override) = tx.state_override() {
+            RevmTxSimulator::apply_state_override(&mut db, state_override)?;
+        }
+        let is_prague = self.spec.is_enabled_in(SpecId::PRAGUE);
+        let evm = Context::mainnet()
+            .with_db(db)
+            .modify_cfg_chained(|cfg| configure_cfg(cfg, self.chain_id, self.spec))
+            .modify_block_chained(|block| {
+                *block = pinned.env().clone();
+                block.set_blob_excess_gas_and_price(
+                    pinned.excess_blob_gas().unwrap_or_default(),
+                    is_prague,
+                );
+                override_block(block, tx);
+            })
+            .build_mainnet();
+        let tx = TxEnv {
+            chain_id: Some(self.chain_id),
+            ..TxEnv::from(tx.clone())
+        };
+        Ok((evm, tx))
+    }
+}
+
+impl AlloyForkStateSource {
+    /// Blocks on `future`, requires a multi threaded tokio runtime.
+    fn block_on<F: IntoFuture>(future: F) -> Result<F::Output> {
+        let handle = Handle::try_current().map_err(Error::fork_state_unavailable)?;
+        Ok(tokio::task::block_in_place(|| {
+            handle.block_on(future.into_future())
+        }))
+    }
+}
+
+// ========================================
+// LocalTxSimulator Implementation
+// ========================================
+impl<S: ForkStateSource> LocalTxSimulator for ForkingTxSimulator<S> {
+    fn locally_simulate_tx(&self, tx: &Transaction) -> Result<Gas> {
+        self.locally_simulate_tx_with_breakdown(tx)
+            .map(|(gas, _)| gas)
+    }
+
+    fn locally_simulate_tx_with_breakdown(
+        &self,
+        tx: &Transaction,
+    ) -> Result<(Gas, Option<GasBreakdown>)> {
+        self.simulate_tx_at_pinned_block(tx)
+            .inspect_err(|e| {
+                error!("Error while simulating transaction on fork: {e}");
+            })
+            .inspect(|(gas, breakdown)| {
+                debug!("Forked simulation - gas used: {gas}, breakdown: {breakdown:?}");
+            })
+            .map(|(gas, breakdown)| (gas, Some(breakdown)))
+    }
+
+    fn spec(&self) -> Option<SpecId> {
+        Some(self.spec)
+    }
+
+    fn locally_simulate_tx_with_state_diff(&self, tx: &Transaction) -> Result<StateDiff> {
+        let (mut evm, tx) = self.fork_at_pinned_block(tx)?;
+        RevmTxSimulator::simulate_tx_with_state_diff(&mut evm, tx).inspect_err(|e| {
+            error!("Error while simulating transaction on fork for state diff: {e}");
+        })
+    }
+
+    fn simulate_call(&self, tx: &Transaction) -> Result<CallOutput> {
+        let (mut evm, tx) = self.fork_at_pinned_block(tx)?;
+        RevmTxSimulator::simulate_call_in(&mut evm, tx).inspect_err(|e| {
+            error!("Error while simulating call on fork: {e}");
+        })
+    }
+}
+
+// ========================================
+// ForkStateSource Implementation
+// ========================================
+impl ForkStateSource for AlloyForkStateSource {
+    type Db = WrapDatabaseAsync<AlloyDB<alloy::network::Ethereum, DynProvider>>;
+
+    fn block(&self, block: BlockNumberOrTag) -> Result<ForkBlock> {
+        let block = Self::block_on(self.provider.get_block_by_number(block))?
+            .map_err(Error::fork_state_unavailable)?
+            .ok_or_else(|| Error::fork_state_unavailable(format!("No block {block}")))?;
+        let header = block.header();
+        let env = BlockEnv {
+            number: header.number(),
+            beneficiary: header.beneficiary(),
+            timestamp: header.timestamp(),
+            gas_limit: header.gas_limit(),
+            basefee: header.base_fee_per_gas().unwrap_or_default(),
+            difficulty: header.difficulty(),
+            prevrandao: header.mix_hash(),
+            ..Default::default()
+        };
+        Ok(ForkBlockBuilder::default()
+            .hash(header.hash())
+            .env(env)
+            .excess_blob_gas(header.excess_blob_gas())
+            .build()
+            .unwrap())
+    }
+
+    fn state_at(&self, block_hash: B256) -> Result<Self::Db> {
+        let db = AlloyDB::new(self.provider.clone(), BlockId::hash(block_hash));
+        WrapDatabaseAsync::new(db)
+            .ok_or_else(|| Error::fork_state_unavailable("Requires multi threaded runtime"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+    use std::sync::Mutex;
+
+    use revm::{bytecode::Bytecode, state::AccountInfo};
+
+    use super::*;
+
+    type Sut = ForkingTxSimulator<MockForkStateSource>;
+
+    const CONTRACT: Address = Address::new([0xcc; 20]);
+    const NUMBER_READER: Address = Address::new([0xdd; 20]);
+
+    /// Chain head which advances every time it is read, and every time
+    /// storage is read, i.e. a new block arrives "mid simulation".
+    #[derive(Default)]
+    struct MockChain {
+        head: Mutex<u8>,
+        /// `(block hash, slot)` of every storage read.
+        storage_reads: Mutex<Vec<(B256, U256)>>,
+    }
+    impl MockChain {
+        fn advance_head(&self) -> B256 {
+            let mut head = self.head.lock().unwrap();
+            *head += 1;
+            B256::repeat_byte(*head)
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct MockForkStateSource {
+        chain: Arc<MockChain>,
+    }
+
+    struct MockDb {
+        chain: Arc<MockChain>,
+        pinned_block_hash: B256,
+    }
+
+    impl ForkStateSource for MockForkStateSource {
+        type Db = MockDb;
+        fn block(&self, block: BlockNumberOrTag) -> Result<ForkBlock> {
+            let (hash, number) = match block {
+                BlockNumberOrTag::Number(number) => (B256::with_last_byte(number as u8), number),
+                _ => (self.chain.advance_head(), 0),
+            };
+            Ok(ForkBlockBuilder::default()
+                .hash(hash)
+                .env(BlockEnv {
+                    number,
+                    ..Default::default()
+                })
+                .build()
+                .unwrap())
+        }
+        fn state_at(&self, block_hash: B256) -> Result<MockDb> {
+            Ok(MockDb {
+                chain: self.chain.clone(),
+                pinned_block_hash: block_hash,
+            })
+        }
+    }
+
+    impl DatabaseRef for MockDb {
+        type Error = Infallible;
+        fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Infallible> {
+            let code = match address {
+                // PUSH1 0x00 SLOAD PUSH1 0x01 SLOAD STOP
+                CONTRACT => Bytes::from_static(&[0x60, 0x00, 0x54, 0x60, 0x01, 0x54, 0x00]),
+                // NUMBER SLOAD STOP, i.e. reads the slot of the block number
+                NUMBER_READER => Bytes::from_static(&[0x43, 0x54, 0x00]),
+                _ => return Ok(None),
+            };
+            let code = Bytecode::new_raw(code);
+            Ok(Some(AccountInfo {
+                code_hash: code.hash_slow(),
+                code: Some(code),
+                ..Default::default()
+            }))
+        }
+        fn code_by_hash_ref(&self, _: B256) -> Result<Bytecode, Infallible> {
+            unreachable!("code is loaded with account")
+        }
+        fn storage_ref(&self, _: Address, index: U256) -> Result<U256, Infallible> {
+            self.chain
+                .storage_reads
+                .lock()
+                .unwrap()
+                .push((self.pinned_block_hash, index));
+            self.chain.advance_head();
+            Ok(U256::ZERO)
+        }
+        fn block_hash_ref(&self, _: u64) -> Result<B256, Infallible> {
+            Ok(B256::ZERO)
+        }
+    }
+
+    fn call_contract() -> Transaction {
+        TransactionBuilder::default().to(CONTRACT).build().unwrap()
+    }
+
+    #[test]
+    fn storage_reads_within_one_simulation_use_same_pinned_block() {
+        let source = MockForkStateSource::default();
+        let sut = Sut::new(source.clone(), MAINNET_CHAIN_ID, SpecId::default());
+
+        sut.locally_simulate_tx(&call_contract()).unwrap();
+
+        let reads = source.chain.storage_reads.lock().unwrap().clone();
+        let pinned = B256::repeat_byte(1);
+        assert_eq!(
+            reads,
+            vec![(pinned, U256::from(0)), (pinned, U256::from(1))]
+        );
+    }
+
+    #[test]
+    fn each_simulation_pins_latest_block_at_its_start() {
+        let source = MockForkStateSource::default();
+        let sut = Sut::new(source.clone(), MAINNET_CHAIN_ID, SpecId::default());
+
+        sut.locally_simulate_tx(&call_contract()).unwrap();
+        sut.locally_simulate_tx(&call_contract()).unwrap();
+
+        let pinned = source
+            .chain
+            .storage_reads
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(hash, _)| *hash)
+            .collect::<Vec<_>>();
+        // head advanced by 1 at pin and by 1 per storage read
+        let first = B256::repeat_byte(1);
+        let second = B256::repeat_byte(4);
+        assert_eq!(pinned, vec![first, first, second, second]);
+    }
+
+    #[test]
+    fn gas_used_includes_cold_storage_reads() {
+        let sut = Sut::new(
+            MockForkStateSource::default(),
+            MAINNET_CHAIN_ID,
+            SpecId::default(),
+        );
+        let gas = sut.locally_simulate_tx(&call_contract()).unwrap();
+        // 21_000 base + 2 * (PUSH1 3 gas + cold SLOAD 2_100 gas)
+        assert_eq!(gas, Gas::from(21_000 + 2 * (3 + 2_100)));
+    }
+
+    #[test]
+    fn at_block_pins_that_block_for_all_simulations() {
+        let source = MockForkStateSource::default();
+        let sut = Sut::new(source.clone(), MAINNET_CHAIN_ID, SpecId::default()).at_block(42);
+
+        sut.locally_simulate_tx(&call_contract()).unwrap();
+        sut.locally_simulate_tx(&call_contract()).unwrap();
+
+        let pinned = B256::with_last_byte(42);
+        assert!(
+            source
+                .chain
+                .storage_reads
+                .lock()
+                .unwrap()
+                .iter()
+                .all(|(hash, _)| *hash == pinned)
+        );
+        assert_eq!(source.chain.storage_reads.lock().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn block_number_of_transaction_pins_state_and_environment() {
+        let source = MockForkStateSource::default();
+        let sut = Sut::new(source.clone(), MAINNET_CHAIN_ID, SpecId::default()).at_block(42);
+        let tx = TransactionBuilder::default()
+            .to(NUMBER_READER)
+            .block_number(BlockNumberOrTag::Number(7))
+            .build()
+            .unwrap();
+
+        sut.locally_simulate_tx(&tx).unwrap();
+
+        let reads = source.chain.storage_reads.lock().unwrap().clone();
+        assert_eq!(reads, vec![(B256::with_last_byte(7), U256::from(7))]);
+    }
+}
@@ -11,6 +11,11 @@ pub struct Dependencies {
     /// Remote gas estimator
     /// (e.g. Alchemy API)
     remote_gas_estimator: Arc<dyn RemoteGasEstimator + Send + Sync>,
+
+    /// Source of mined transactions, to estimate transactions by hash
+    /// (e.g. Alchemy API)
+    #[builder(default)]
+    transaction_source: Option<Arc<dyn TransactionSource + Send + Sync>>,
 }
 
 impl GastimatorDependencies for Dependencies {
@@ -20,10 +25,14 @@ impl GastimatorDependencies for Dependencies {
     fn remote_gas_estimator(&self) -> Arc<dyn RemoteGasEstimator + Send + Sync> {
         self.remote_gas_estimator.clone()
     }
+    fn transaction_source(&self) -> Option<Arc<dyn TransactionSource + Send + Sync>> {
+        self.transaction_source.clone()
+    }
 }
 
 /// Trait for the gastimator dependencies, allows testing
 pub trait GastimatorDependencies {
     fn local_gas_estimator(&self) -> Arc<dyn LocalTxSimulator + Send + Sync>;
     fn remote_gas_estimator(&self) -> Arc<dyn RemoteGasEstimator + Send + Sync>;
+    fn transaction_source(&self) -> Option<Arc<dyn TransactionSource + Send + Sync>>;
 }
@@ -1,11 +1,44 @@
 use crate::prelude::*;
 
+use std::sync::atomic::Ordering;
+
+use futures_util::{Stream, StreamExt};
+
+use tracing::{Instrument, Span, field::Empty, info_span, instrument};
+
+/// The local estimate and a breakdown of it.
+pub(crate) type LocalEstimate = Result<(Gas, Option<GasBreakdown>)>;
+
+/// The remote estimate and the raw response of the remote, if available.
+pub(crate) type RemoteEstimate = Result<(Gas, Option<String>)>;
+
+/// The local and remote estimates of a transaction, the remote `None` if it
+/// was not called, shared by concurrent identical requests, see
+/// [`AppState::in_flight`].
+pub(crate) type ComputedEstimates = Result<(LocalEstimate, Option<RemoteEstimate>)>;
+
+/// Number of responses stored by idempotency key before expired ones are
+/// removed, and above which no more are stored, bounding the memory used by
+/// clients sending many distinct keys.
+const MAX_IDEMPOTENT_RESPONSES: usize = 10_000;
+
+/// The RPC client of the remote, as gas estimator and as source of mined
+/// transactions, and the URL of its RPC node.
+struct RemoteRpc {
+    estimator: Arc<dyn RemoteGasEstimator + Send + Sync>,
+    transactions: Arc<dyn TransactionSource + Send + Sync>,
+    url: String,
+}
+
 #[derive(derive_more::Debug, derive_more::Deref)]
 #[debug("Gastimator(stateless)")]
 pub struct Gastimator {
     #[deref]
     dependencies: Arc<dyn GastimatorDependencies + Send + Sync>,
     state: AppState,
+    work_queue: WorkQueue,
+    local_simulations: Arc<tokio::sync::Semaphore>,
+    config: GastimatorConfig,
 }
 
 // ========================================
@@ -14,46 +47,426 @@ pub struct Gastimator {
 impl Gastimator {
     /// Creates a new `Gastimator` with the given dependencies.
     pub fn with(dependencies: Arc<dyn GastimatorDependencies + Send + Sync>) -> Self {
+        Self::with_config(dependencies, GastimatorConfig::default())
+    }
+
+    /// Creates a new `Gastimator` with the given dependencies and config.
+    pub fn with_config(
+        dependencies: Arc<dyn GastimatorDependencies + Send + Sync>,
+        config: GastimatorConfig,
+    ) -> Self {
         Self {
             dependencies,
-            state: AppState::default(),
+            state: AppState {
+                idempotent_responses: Cache::new(config.idempotency_ttl()),
+                ..AppState::with_cache_ttl(config.cache_ttl())
+            },
+            work_queue: WorkQueue::new(config.worker_pool_size()),
+            local_simulations: Arc::new(tokio::sync::Semaphore::new(
+                config.local_simulation_threads(),
+            )),
+            config,
         }
     }
 
-    /// Creates a new `Gastimator` with the given local and remote gas estimators.
+    /// Creates a new `Gastimator` with the given local and remote gas
+    /// estimators, remote only, see [`GastimatorMode::RemoteOnly`], if
+    /// `local_gas_estimator` is `None`.
     pub fn with_dependencies(
-        local_gas_estimator: Arc<dyn LocalTxSimulator + Send + Sync>,
+        local_gas_estimator: Option<Arc<dyn LocalTxSimulator + Send + Sync>>,
         remote_gas_estimator: Arc<dyn RemoteGasEstimator + Send + Sync>,
     ) -> Self {
-        Self::with(Arc::new(
-            DependenciesBuilder::default()
-                .local_gas_estimator(local_gas_estimator)
-                .remote_gas_estimator(remote_gas_estimator)
-                .build()
-                .unwrap(),
-        ))
+        let (local_gas_estimator, mode) = match local_gas_estimator {
+            Some(local_gas_estimator) => (local_gas_estimator, GastimatorMode::Both),
+            None => {
+                let local_gas_estimator: Arc<dyn LocalTxSimulator + Send + Sync> =
+                    Arc::new(NoLocalTxSimulator);
+                (local_gas_estimator, GastimatorMode::RemoteOnly)
+            }
+        };
+        let dependencies = DependenciesBuilder::default()
+            .local_gas_estimator(local_gas_estimator)
+            .remote_gas_estimator(remote_gas_estimator)
+            .build()
+            .unwrap();
+        Self::with_config(
+            Arc::new(dependencies),
+            GastimatorConfig::default().with_mode(mode),
+        )
     }
 
     /// Creates a new `Gastimator` with the given Alchemy API key.
     pub fn new(alchemy_api_key: String) -> Self {
         let remote_gas_estimator = Arc::new(AlchemyRpcClient::new(alchemy_api_key));
         let local_gas_estimator = Arc::new(RevmTxSimulator::new());
-        Self::with_dependencies(local_gas_estimator, remote_gas_estimator)
+        Self::with_dependencies(Some(local_gas_estimator), remote_gas_estimator)
+    }
+
+    /// Creates a new `Gastimator` from the run configuration `config`.
+    ///
+    /// # Throws
+    /// Throws [`Error::NoAlchemyApiKey`] if the config has neither an RPC URL
+    /// nor an Alchemy API key, unless local only and not forking.
+    /// Throws [`Error::UnsupportedAlchemyChain`] if Alchemy is used and does
+    /// not support the chain of the config.
+    /// Throws [`Error::ForkStateUnavailable`] if configured to fork and the
+    /// RPC URL is invalid.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let chain_id = config.chain_id();
+        let remote_rpc = Self::remote_rpc(config)?;
+        let local_gas_estimator: Arc<dyn LocalTxSimulator + Send + Sync> =
+            if config.gastimator().mode().is_remote_only() {
+                Arc::new(NoLocalTxSimulator)
+            } else if config.gastimator().fork() {
+                let spec = config
+                    .gastimator()
+                    .spec()
+                    .unwrap_or_else(|| spec_for_chain(chain_id));
+                let rpc_url = &remote_rpc.as_ref().ok_or(Error::NoAlchemyApiKey)?.url;
+                Arc::new(
+                    ForkingTxSimulator::new(AlloyForkStateSource::new(rpc_url)?, chain_id, spec)
+                        .at_block(config.gastimator().fork_block()),
+                )
+            } else {
+                Arc::new(
+                    RevmTxSimulator::builder()
+                        .chain_id(chain_id)
+                        .spec(config.gastimator().spec())
+                        .excess_blob_gas(config.gastimator().excess_blob_gas())
+                        .build(),
+                )
+            };
+        let remote_gas_estimator: Arc<dyn RemoteGasEstimator + Send + Sync> =
+            if config.gastimator().mode().is_local_only() {
+                Arc::new(NoRemoteGasEstimator)
+            } else {
+                remote_rpc
+                    .as_ref()
+                    .ok_or(Error::NoAlchemyApiKey)?
+                    .estimator
+                    .clone()
+            };
+        let dependencies = DependenciesBuilder::default()
+            .local_gas_estimator(local_gas_estimator)
+            .remote_gas_estimator(remote_gas_estimator)
+            .transaction_source(remote_rpc.map(|rpc| rpc.transactions))
+            .build()
+            .unwrap();
+        Ok(Self::with_config(
+            Arc::new(dependencies),
+            config.gastimator().clone(),
+        ))
     }
 
     /// Estimates the gas usage of `tx` using the local and remote gas estimators.
     ///
     pub async fn estimate_gas(&self, tx: Transaction) -> Result<GasEstimateResponse> {
+        self.estimate_gas_with_options(tx, EstimateOptions::default())
+            .await
+    }
+
+    /// Estimates the gas usage of `tx` using the local and remote gas estimators,
+    /// respecting the per request `options`.
+    pub async fn estimate_gas_with_options(
+        &self,
+        tx: Transaction,
+        options: EstimateOptions,
+    ) -> Result<GasEstimateResponse> {
+        self.estimate_gas_with_priority(tx, options, WorkPriority::High)
+            .await
+    }
+
+    /// The mined transaction with `hash`, fetched from the transaction
+    /// source, e.g. to estimate it and compare against the gas it used.
+    ///
+    /// # Throws
+    /// Throws [`Error::NoTransactionSource`] if there is no transaction
+    /// source, i.e. no remote was configured, and
+    /// [`Error::TransactionNotFound`] if the chain has no such transaction.
+    pub async fn transaction_by_hash(&self, hash: B256) -> Result<Transaction> {
+        self.transaction_source()
+            .ok_or(Error::NoTransactionSource)?
+            .transaction_by_hash(hash)
+            .await
+    }
+
+    /// Estimates the mined transaction with `hash`, respecting `options`, and
+    /// compares the estimate against the gas it actually used, according to
+    /// its receipt, to quantify the error of the estimate.
+    ///
+    /// # Throws
+    /// Throws as [`Gastimator::transaction_by_hash`], or if the estimate
+    /// fails.
+    pub async fn accuracy(&self, hash: B256, options: EstimateOptions) -> Result<AccuracyResponse> {
+        let source = self
+            .transaction_source()
+            .ok_or(Error::NoTransactionSource)?;
+        let (tx, actual_gas) =
+            futures_util::try_join!(source.transaction_by_hash(hash), source.gas_used_by(hash))?;
+        let estimate = self.estimate_gas_with_options(tx, options).await?;
+        Ok(AccuracyResponse::new(estimate, actual_gas))
+    }
+
+    /// Estimates the gas usage of `tx` using the local and remote gas estimators,
+    /// respecting the per request `options`, if the estimate needs to be computed
+    /// it waits for a worker in the work queue with `priority`.
+    pub async fn estimate_gas_with_priority(
+        &self,
+        tx: Transaction,
+        options: EstimateOptions,
+        priority: WorkPriority,
+    ) -> Result<GasEstimateResponse> {
+        let blob_gas = tx.blob_gas();
+        let gas_limit = *tx.gas_limit();
+        let fee_per_gas = options.fee_per_gas();
         let start = Instant::now();
-        info!("Received transaction: {:?}", tx);
-        if let Some(response) = self.check_native_transfer(&tx, start)? {
-            return Ok(response);
+        let response = self.estimate_execution_gas(tx, options, priority).await;
+        self.state
+            .metrics
+            .record_estimate(response.is_ok(), start.elapsed());
+        let mut response = response?;
+        if let Some(warning) = EstimateWarning::gas_limit_suspiciously_high(
+            gas_limit,
+            response.gas_usage().max_gas(),
+            self.config.gas_limit_warning_factor(),
+        ) {
+            warn!("Suspiciously high gas limit: {warning:?}");
+            response.set_warnings(vec![warning]);
         }
-        if let Some(cached) = self.use_cached_value_if_able(&tx, start)? {
-            return Ok(cached);
+        if let Some(fee_per_gas) = fee_per_gas {
+            let gas = U256::from(*response.gas_usage().max_gas());
+            response.set_estimated_fee_wei(Some(gas.saturating_mul(fee_per_gas)));
+        }
+        if let Some(blob_gas) = blob_gas {
+            let blob_base_fee = self.local_gas_estimator().blob_base_fee();
+            let blob_fee = blob_base_fee.map(|fee| U256::from(*blob_gas) * U256::from(fee));
+            response.set_blob_gas(Some(blob_gas));
+            response.set_blob_base_fee(blob_base_fee);
+            response.set_blob_fee(blob_fee);
+        }
+        Ok(response)
+    }
+
+    /// Estimates the gas usage of each transaction in `txs`, respecting the
+    /// per request `options`, returning the results in the same order. Items
+    /// wait for workers with [`WorkPriority::Low`], so that single requests
+    /// are served before them, and a failing item does not fail the others.
+    pub async fn estimate_gas_batch(
+        &self,
+        txs: Vec<Transaction>,
+        options: EstimateOptions,
+    ) -> Vec<Result<GasEstimateResponse>> {
+        futures_util::future::join_all(
+            txs.into_iter()
+                .map(|tx| self.estimate_gas_with_priority(tx, options.clone(), WorkPriority::Low)),
+        )
+        .await
+    }
+
+    /// Estimates the gas usage of each transaction of the stream `txs`,
+    /// respecting the per request `options`, yielding the results in the same
+    /// order as they are estimated, like [`Self::estimate_gas_batch`] but at
+    /// most `worker_pool_size` transactions are read ahead, so that memory
+    /// stays flat however many transactions are streamed. Items of `txs`
+    /// which failed to be read are yielded as is.
+    pub fn estimate_gas_stream(
+        self: Arc<Self>,
+        txs: impl Stream<Item = Result<Transaction>> + Send + 'static,
+        options: EstimateOptions,
+    ) -> impl Stream<Item = Result<GasEstimateResponse>> + Send + 'static {
+        let concurrency = self.config.worker_pool_size().max(1);
+        txs.map(move |tx| {
+            let gastimator = self.clone();
+            let options = options.clone();
+            async move {
+                gastimator
+                    .estimate_gas_with_priority(tx?, options, WorkPriority::Low)
+                    .await
+            }
+        })
+        .buffered(concurrency)
+    }
+
+    /// Estimates the gas usage of both the `original` pending transaction and
+    /// the `replacement` replacing it, each with its own fees, e.g. to confirm
+    /// that a replacement with higher fees uses the same gas.
+    pub async fn estimate_replacement(
+        &self,
+        original: PricedTransaction,
+        replacement: PricedTransaction,
+        options: EstimateOptions,
+    ) -> Result<ReplacementResponse> {
+        let (original, replacement) = futures_util::future::try_join(
+            self.estimate_gas_with_options(original.tx().clone(), original.options(&options)),
+            self.estimate_gas_with_options(replacement.tx().clone(), replacement.options(&options)),
+        )
+        .await?;
+        Ok(ReplacementResponse::new(original, replacement))
+    }
+
+    /// Estimates the gas usage of `tx`, see [`Self::estimate_gas`], and also
+    /// returns the changes to the state of all accounts changed by locally
+    /// simulating it, e.g. the balance and nonce of the sender.
+    ///
+    /// The state diff is never cached, so `tx` is always simulated locally.
+    pub async fn estimate_with_state_diff(
+        &self,
+        tx: Transaction,
+    ) -> Result<(GasEstimateResponse, StateDiff)> {
+        let response = self.estimate_gas(tx.clone()).await?;
+        let _permit = self.work_queue.acquire(WorkPriority::High).await;
+        let state_diff = self
+            .locally_simulate(move |estimator| estimator.locally_simulate_tx_with_state_diff(&tx))
+            .await??;
+        Ok((response, state_diff))
+    }
+
+    /// Locally simulates `tx` as a read-only call, i.e. like `eth_call`,
+    /// returning the data returned by it and the gas used, the remote gas
+    /// estimator is not used and the result is never cached.
+    ///
+    /// # Throws
+    /// Throws [`Error::SimulationReverted`] if the call reverts.
+    pub async fn simulate_call(&self, tx: Transaction) -> Result<CallOutput> {
+        let _permit = self.work_queue.acquire(WorkPriority::High).await;
+        self.locally_simulate(move |estimator| estimator.simulate_call(&tx))
+            .await?
+    }
+
+    /// Locally simulates `txs` in order against shared state, so that state
+    /// changed by a transaction carries over to the ones after it, e.g. an
+    /// approval followed by a swap, returning the gas used by each and in
+    /// total. The remote gas estimator is not used and the result is never
+    /// cached.
+    ///
+    /// # Throws
+    /// Throws [`Error::InvalidTransaction`] if `txs` is empty and
+    /// [`Error::BundleTransactionFailed`] if any transaction fails.
+    pub async fn simulate_bundle(&self, txs: Vec<Transaction>) -> Result<BundleSimulationResponse> {
+        if txs.is_empty() {
+            return Err(Error::InvalidTransaction {
+                reason: "Bundle is empty".to_owned(),
+            });
+        }
+        let _permit = self.work_queue.acquire(WorkPriority::High).await;
+        self.locally_simulate(move |estimator| estimator.simulate_bundle(&txs))
+            .await?
+            .map(BundleSimulationResponse::new)
+    }
+
+    /// Statistics of the effectiveness of the cache of estimates.
+    pub fn cache_stats(&self) -> CacheStats {
+        self.state.cache.stats()
+    }
+
+    /// The metrics of this gastimator, i.e. request counts and latencies.
+    pub fn metrics(&self) -> &Metrics {
+        &self.state.metrics
+    }
+
+    /// The metrics of this gastimator, including the statistics of the
+    /// cache, in the Prometheus text exposition format.
+    pub fn render_metrics(&self) -> String {
+        self.state.metrics.render(&self.cache_stats())
+    }
+
+    /// Removes all cached estimates, including kind-stable cached local
+    /// estimates, e.g. after deploying new gas rules, returning the number of
+    /// entries removed.
+    pub fn clear_cache(&self) -> usize {
+        let removed = self.state.cache.clear() + self.state.kind_stable_cache.clear();
+        info!("Cleared {removed} cached estimates");
+        removed
+    }
+
+    /// The response stored under the idempotency `key` for the request with
+    /// `request_hash`, see [`IdempotencyKey::request_hash`], if any and not
+    /// older than the [`GastimatorConfig::idempotency_ttl`].
+    ///
+    /// # Throws
+    /// Throws [`Error::IdempotencyKeyReused`] if a response is stored under
+    /// `key` for another request.
+    pub fn idempotent_response(
+        &self,
+        key: &IdempotencyKey,
+        request_hash: B256,
+    ) -> Result<Option<IdempotentResponse>> {
+        match self.state.idempotent_responses.get(key) {
+            Some((stored_hash, _)) if stored_hash != request_hash => {
+                Err(Error::IdempotencyKeyReused {
+                    key: key.key().clone(),
+                })
+            }
+            stored => Ok(stored.map(|(_, response)| response)),
+        }
+    }
+
+    /// Stores `response` of the request with `request_hash` under the
+    /// idempotency `key` for the [`GastimatorConfig::idempotency_ttl`], see
+    /// [`Self::idempotent_response`]. Not stored if the TTL is zero or too
+    /// many responses are stored.
+    pub fn store_idempotent_response(
+        &self,
+        key: IdempotencyKey,
+        request_hash: B256,
+        response: impl Into<IdempotentResponse>,
+    ) {
+        let responses = &self.state.idempotent_responses;
+        if responses.ttl().is_zero() {
+            return;
+        }
+        if responses.len() >= MAX_IDEMPOTENT_RESPONSES {
+            responses.remove_expired();
+        }
+        if responses.len() >= MAX_IDEMPOTENT_RESPONSES {
+            warn!(
+                "Too many idempotent responses stored, not storing key `{}`",
+                key.key()
+            );
+            return;
+        }
+        responses.insert(key, (request_hash, response.into()));
+    }
+
+    /// Whether the gastimator is ready to serve estimates, that is, if it has
+    /// completed its warm-up, see [`Self::warm_up`], and either is configured
+    /// to be local only or the remote gas estimator has responded successfully
+    /// at least once.
+    pub fn is_ready(&self) -> bool {
+        self.state.warmed_up.load(Ordering::Relaxed)
+            && (self.config.mode().is_local_only()
+                || self.state.remote_reachable.load(Ordering::Relaxed))
+    }
+
+    /// Warms up the gastimator by locally simulating a minimal transaction,
+    /// unless remote only, initializing the EVM (and with a fork, fetching
+    /// the chain state), and by pinging the remote gas estimator. Failures
+    /// are logged, not returned, after completion the gastimator is
+    /// considered warmed up.
+    pub async fn warm_up(&self) {
+        let start = Instant::now();
+        if !self.config.mode().is_remote_only() {
+            self.warm_up_local().await;
+        }
+        if let Err(e) = self.ping_remote().await {
+            warn!("Remote warm-up ping failed: {e}");
         }
-        let (local, remote) = self.compute_estimates(&tx).await?;
-        self.build_response(tx, local, remote, start)
+        self.state.warmed_up.store(true, Ordering::Relaxed);
+        info!("Warm-up completed in {:?}", start.elapsed());
+    }
+
+    /// Pings the remote gas estimator by estimating a minimal transaction,
+    /// on success the gastimator is considered ready, see [`Self::is_ready`].
+    /// Does nothing if configured to be local only.
+    pub async fn ping_remote(&self) -> Result<()> {
+        if self.config.mode().is_local_only() {
+            return Ok(());
+        }
+        self.remote_gas_estimator()
+            .estimate_gas(&Self::minimal_transaction())
+            .await
+            .inspect(|_| self.mark_remote_reachable())
+            .map(|_| ())
     }
 }
 
@@ -61,27 +474,255 @@ impl Gastimator {
 // Private Implementation
 // ========================================
 impl Gastimator {
+    /// Initializes the EVM (and with a fork, fetches the chain state) by
+    /// locally simulating a minimal transaction, logging failures.
+    async fn warm_up_local(&self) {
+        let local = self
+            .locally_simulate(|estimator| {
+                estimator.locally_simulate_tx(&Self::minimal_transaction())
+            })
+            .await;
+        match local {
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => warn!("Local warm-up simulation failed: {e}"),
+            Err(e) => warn!("Local warm-up simulation panicked: {e}"),
+        }
+    }
+
+    /// The RPC client of the remote and the URL of its RPC node, using the
+    /// RPC URL of `config` if set, else Alchemy if an API key is set, else
+    /// `None`.
+    fn remote_rpc(config: &Config) -> Result<Option<RemoteRpc>> {
+        if let Some(rpc_url) = config.rpc_url() {
+            let rpc = GenericRpcClient::new(rpc_url)
+                .with_bearer_token(config.rpc_bearer_token().clone())
+                .with_timeout(config.remote_timeout())
+                .with_id_strategy(config.rpc_id_strategy())
+                .with_block(config.remote_block())
+                .with_max_concurrent_requests(config.max_concurrent_remote_requests());
+            let rpc = Arc::new(rpc);
+            return Ok(Some(RemoteRpc {
+                estimator: rpc.clone(),
+                transactions: rpc,
+                url: rpc_url.clone(),
+            }));
+        }
+        let Some(api_key) = config.alchemy_api_key() else {
+            return Ok(None);
+        };
+        let alchemy = AlchemyRpcClient::for_chain(api_key, config.chain_id())?
+            .with_timeout(config.remote_timeout())
+            .with_id_strategy(config.rpc_id_strategy())
+            .with_block(config.remote_block())
+            .with_max_concurrent_requests(config.max_concurrent_remote_requests());
+        let url = alchemy.url();
+        let alchemy = Arc::new(alchemy);
+        Ok(Some(RemoteRpc {
+            estimator: alchemy.clone(),
+            transactions: alchemy,
+            url,
+        }))
+    }
+
+    /// Whether the remote gas estimator should be called for `tx`, always
+    /// if configured to be remote only or for transactions which are not
+    /// cacheable, else for a fraction of them given by
+    /// [`GastimatorConfig::remote_sample_rate`]. Sampling is deterministic,
+    /// e.g. with rate `0.1` every tenth transaction is sampled.
+    fn is_sampled_for_remote(&self, tx: &Transaction) -> bool {
+        if self.config.mode().is_remote_only() || !tx.is_cacheable() {
+            return true;
+        }
+        let rate = self.config.remote_sample_rate().clamp(0.0, 1.0);
+        let n = self
+            .state
+            .remote_sample_counter
+            .fetch_add(1, Ordering::Relaxed) as f64;
+        ((n + 1.0) * rate).floor() > (n * rate).floor()
+    }
+
+    /// A minimal transaction, a call to the zero address, used for warm-up
+    /// and pinging the remote gas estimator.
+    fn minimal_transaction() -> Transaction {
+        TransactionBuilder::default()
+            .to(Address::ZERO)
+            .build()
+            .unwrap()
+    }
+
+    fn mark_remote_reachable(&self) {
+        self.state.remote_reachable.store(true, Ordering::Relaxed);
+    }
+
+    /// Estimates the execution gas usage of `tx`, i.e. excluding any blob gas.
+    #[instrument(
+        name = "estimate",
+        skip_all,
+        fields(tx_kind = ?tx.kind(), cacheable = tx.is_cacheable(), branch = Empty)
+    )]
+    async fn estimate_execution_gas(
+        &self,
+        tx: Transaction,
+        options: EstimateOptions,
+        priority: WorkPriority,
+    ) -> Result<GasEstimateResponse> {
+        let start = Instant::now();
+        let deadline = self.config.total_budget().map(|budget| start + budget);
+        info!("Received transaction: {:?}, options: {:?}", tx, options);
+        self.check_gas_limit_within_max(&tx)?;
+        let record_branch = |branch: &str| {
+            Span::current().record("branch", branch);
+        };
+        if let Some(response) = self.check_empty_transaction(&tx, start)? {
+            record_branch("empty_transaction");
+            return Ok(response);
+        }
+        tx.validate()?;
+        if let Some(precomputed) = tx.precomputed_access_list() {
+            let (entries, max) = (precomputed.entries(), self.config.max_access_list_entries());
+            if entries > max {
+                return Err(Error::AccessListTooLarge { entries, max });
+            }
+            precomputed.validate()?;
+        }
+        Self::check_intrinsic_gas_within_limit(&tx)?;
+        if let Some(response) = self.check_native_transfer(&tx, start)? {
+            record_branch("native_transfer");
+            return Ok(response);
+        }
+        if let Some(response) = self.check_precompile_call(&tx, start)? {
+            record_branch("precompile");
+            return Ok(response);
+        }
+        if let Some(cached) = self.use_cached_value_if_able(&tx, &options, start)? {
+            record_branch("cache_hit");
+            return Ok(cached);
+        }
+        let (local, remote) = self
+            .compute_estimates_single_flight(&tx, options.mode(), deadline, priority)
+            .await?;
+        let (local, gas_breakdown) = match local {
+            Ok((gas, breakdown)) => (Ok(gas), breakdown),
+            Err(e) => (Err(e), None),
+        };
+        let (remote, raw_remote_response) = match remote {
+            Some(Ok((gas, raw))) => (Some(Ok(gas)), raw),
+            Some(Err(e)) => (Some(Err(e)), None),
+            None => (None, None),
+        };
+        let mut response = self.build_response(tx, local, remote, &options, start)?;
+        response.set_gas_breakdown(gas_breakdown);
+        if options.raw_remote() {
+            response.set_raw_remote_response(raw_remote_response);
+        }
+        Ok(response)
+    }
+
     /// Tries to use a cached value for the transaction if able, that is, if
     /// the transaction is considered "cacheable", and if there is a cached
-    /// value for it.
+    /// value for it. A cached range is collapsed if requested in `options`.
+    /// The cache is bypassed in [`EstimateMode::Local`], since its entries
+    /// may include the remote estimate.
+    #[instrument(skip_all)]
     fn use_cached_value_if_able(
         &self,
         tx: &Transaction,
+        options: &EstimateOptions,
         start: Instant,
     ) -> Result<Option<GasEstimateResponse>> {
         if !tx.is_cacheable() {
+            self.state.cache.record_uncacheable();
+            return Ok(None);
+        }
+        if options.mode().is_local() {
             return Ok(None);
         }
-        if let Some(cached) = self.state.cache.get(tx) {
-            debug!("Found cached estimate: {:?}", cached.value());
-            return Ok(Some(Self::build_response_raw(cached.clone(), start)));
+        // Expired entries are misses
+        if let Some(cached) = self.state.cache.get(&CacheKey::of(tx)) {
+            debug!("Found cached estimate: {:?}", cached);
+            // The entry may be shared with a transaction of a higher limit
+            let gas_limit_or_max = tx.gas_limit_else_max();
+            let min_gas = cached.gas_usage().min_gas();
+            if gas_limit_or_max < min_gas {
+                return Err(Error::GasExceedsLimit {
+                    estimated_cost: Some(min_gas),
+                    gas_limit: gas_limit_or_max,
+                });
+            }
+            let mut response =
+                Self::build_response_raw(cached.gas_usage().clone(), EstimateSource::Cached, start);
+            self.pad_and_collapse(&mut response, gas_limit_or_max, options);
+            response.set_original_compute_millis(Some(cached.compute_millis()));
+            response.set_from_cache(true);
+            return Ok(Some(response));
         }
         Ok(None)
     }
 
+    /// If the transaction is empty, either reject it or return the exact gas
+    /// usage of an empty contract creation, depending on the configured
+    /// [`EmptyTransactionPolicy`].
+    fn check_empty_transaction(
+        &self,
+        tx: &Transaction,
+        start: Instant,
+    ) -> Result<Option<GasEstimateResponse>> {
+        if !tx.is_empty() {
+            return Ok(None);
+        }
+        match self.config.empty_transaction_policy() {
+            EmptyTransactionPolicy::Reject => Err(Error::EmptyTransaction),
+            EmptyTransactionPolicy::MinimalEstimate => {
+                let exact = Gas::exact_empty_contract_creation();
+                let gas_limit_or_max = tx.gas_limit_else_max();
+                if gas_limit_or_max < exact {
+                    return Err(Error::GasExceedsLimit {
+                        estimated_cost: Some(exact),
+                        gas_limit: gas_limit_or_max,
+                    });
+                }
+                Ok(Some(Self::build_response_raw(
+                    GasUsage::Exact {
+                        kind: tx.kind(),
+                        gas: exact,
+                    },
+                    EstimateSource::ExactRule,
+                    start,
+                )))
+            }
+        }
+    }
+
+    /// Fails fast, without simulating, if the gas limit of `tx` exceeds the
+    /// configured max allowed gas limit.
+    fn check_gas_limit_within_max(&self, tx: &Transaction) -> Result<()> {
+        match (*tx.gas_limit(), self.config.max_allowed_gas_limit()) {
+            (Some(gas_limit), Some(max)) if gas_limit > max => {
+                Err(Error::GasLimitTooHigh { gas_limit, max })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Fails fast, without simulating, if the gas limit of `tx` is less than
+    /// its intrinsic gas, see [`Transaction::intrinsic_gas`].
+    fn check_intrinsic_gas_within_limit(tx: &Transaction) -> Result<()> {
+        let intrinsic = tx.intrinsic_gas();
+        let gas_limit_or_max = tx.gas_limit_else_max();
+        if gas_limit_or_max < intrinsic {
+            return Err(Error::GasExceedsLimit {
+                estimated_cost: Some(intrinsic),
+                gas_limit: gas_limit_or_max,
+            });
+        }
+        Ok(())
+    }
+
     /// if the transaction is a native token transfer, check if the gas limit is
     /// sufficient. If it is, return the exact gas limit.
-    /// If it is not, return a `GasExceedsLimit` error.
+    /// If it is not, return a `GasExceedsLimit` error. A transfer to an
+    /// account with overridden code is a contract call, and is simulated.
+    #[instrument(skip_all)]
     fn check_native_transfer(
         &self,
         tx: &Transaction,
@@ -91,11 +732,20 @@ impl Gastimator {
         if !kind.is_native_token_transfer() {
             return Ok(None);
         }
+        let overrides_code_of_recipient = tx
+            .state_override()
+            .as_ref()
+            .zip(tx.to().to())
+            .is_some_and(|(state_override, to)| state_override.overrides_code_at(to));
+        if overrides_code_of_recipient {
+            return Ok(None);
+        }
         let exact = Gas::exact_native_token_transfer();
         let gas_limit_or_max = tx.gas_limit_else_max();
         if gas_limit_or_max >= exact {
             Ok(Some(Self::build_response_raw(
                 GasUsage::Exact { kind, gas: exact },
+                EstimateSource::ExactRule,
                 start,
             )))
         } else {
@@ -106,36 +756,297 @@ impl Gastimator {
         }
     }
 
-    /// In parallel fetch local and remote gas estimates.
-    async fn compute_estimates(&self, tx: &Transaction) -> Result<(Result<Gas>, Result<Gas>)> {
+    /// If the transaction calls a recognized precompile, see [`Precompile`],
+    /// and the spec of the local simulation is known, return the exact gas
+    /// usage computed from the size of the input, or a `GasExceedsLimit`
+    /// error if the gas limit is not sufficient.
+    fn check_precompile_call(
+        &self,
+        tx: &Transaction,
+        start: Instant,
+    ) -> Result<Option<GasEstimateResponse>> {
+        if tx.precomputed_access_list().is_some() || tx.is_blob_carrying() {
+            return Ok(None);
+        }
+        let Some(precompile) = tx.to().to().and_then(Precompile::at) else {
+            return Ok(None);
+        };
+        let Some(spec) = self.local_gas_estimator().spec() else {
+            return Ok(None);
+        };
+        let exact = precompile.gas_of_call(tx.input(), spec);
+        let gas_limit_or_max = tx.gas_limit_else_max();
+        if gas_limit_or_max < exact {
+            return Err(Error::GasExceedsLimit {
+                estimated_cost: Some(exact),
+                gas_limit: gas_limit_or_max,
+            });
+        }
+        debug!("Exact gas of call to precompile {precompile:?}: {exact}");
+        Ok(Some(Self::build_response_raw(
+            GasUsage::Exact {
+                kind: tx.kind(),
+                gas: exact,
+            },
+            EstimateSource::ExactRule,
+            start,
+        )))
+    }
+
+    /// Runs `simulate` with the local gas estimator on a blocking thread, once
+    /// one of the `local_simulation_threads` permits is available, so that
+    /// CPU bound simulations neither exceed the configured parallelism nor
+    /// block the async runtime. The permit is held until `simulate` returns,
+    /// even if the returned future is dropped.
+    async fn locally_simulate<T, F>(&self, simulate: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&(dyn LocalTxSimulator + Send + Sync)) -> T + Send + 'static,
+    {
+        let permit = self
+            .local_simulations
+            .clone()
+            .acquire_owned()
+            .await
+            .map_err(Error::local_simulation_failed)?;
+        let estimator = self.local_gas_estimator();
+        // Spans are not propagated to blocking threads, so enter it there.
+        let span = info_span!("local_simulation");
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            let _span = span.entered();
+            simulate(estimator.as_ref())
+        })
+        .await
+        .map_err(Error::local_simulation_failed)
+    }
+
+    /// In parallel fetch local and remote gas estimates, the local estimate
+    /// is accompanied by a breakdown of the gas used and the remote estimate
+    /// by the raw response of the remote, if available. If the transaction has
+    /// a precomputed access list, its gas used is used as the remote estimate.
+    ///
+    /// Estimates not available by `deadline`, if any, are failed, and if
+    /// neither is the estimate fails with [`Error::BudgetExhausted`]. In
+    /// [`EstimateMode::Local`], or if `tx` is not sampled, see
+    /// [`Self::is_sampled_for_remote`], the remote is not called, its
+    /// estimate is `None`, and a local estimate not available by `deadline`
+    /// fails the estimate with [`Error::Timeout`] of the local phase.
+    #[instrument(skip_all)]
+    async fn compute_estimates(
+        &self,
+        tx: &Transaction,
+        mode: EstimateMode,
+        deadline: Option<Instant>,
+    ) -> ComputedEstimates {
         // Allows for **parallel execution** of local and remote estimations,
         // which is possible since they are independent.
-        let local = tokio::spawn({
-            let estimator = self.local_gas_estimator();
-            let tx = tx.clone();
-            async move { estimator.locally_simulate_tx(&tx) }
-        });
-        let remote = tokio::spawn({
+        let local = async {
+            if self.config.mode().is_remote_only() {
+                return Ok(Err(Error::local_simulation_failed(
+                    "Configured to be remote only",
+                )));
+            }
+            let kind_stable_key = self
+                .config
+                .kind_stable_cache()
+                .then(|| KindStableKey::of(tx))
+                .flatten();
+            if let Some(cached) = kind_stable_key
+                .as_ref()
+                .and_then(|key| self.state.kind_stable_cache.get(key))
+            {
+                debug!("Found kind-stable cached local estimate: {:?}", cached);
+                // The key ignores the gas limit, the entry may be of a
+                // transaction of a higher limit.
+                let gas_limit_or_max = tx.gas_limit_else_max();
+                if gas_limit_or_max < cached.0 {
+                    return Ok(Err(Error::GasExceedsLimit {
+                        estimated_cost: Some(cached.0),
+                        gas_limit: gas_limit_or_max,
+                    }));
+                }
+                return Ok(Ok(cached));
+            }
+            let local = self
+                .locally_simulate({
+                    let tx = tx.clone();
+                    let metrics = self.state.metrics.clone();
+                    move |estimator| {
+                        let start = Instant::now();
+                        let local = estimator.locally_simulate_tx_with_breakdown(&tx);
+                        metrics.record_local(local.is_ok(), start.elapsed());
+                        local
+                    }
+                })
+                .await;
+            if let (Some(key), Ok(Ok(estimate))) = (kind_stable_key, &local) {
+                self.state.kind_stable_cache.insert(key, *estimate);
+            }
+            local
+        };
+        // A precomputed access list stands in for the remote, it is not
+        // sampled.
+        let skip_remote = mode.is_local()
+            || (tx.precomputed_access_list().is_none() && !self.is_sampled_for_remote(tx));
+        if skip_remote {
+            // The remote task is not spawned at all.
+            let local = match Self::until_deadline(deadline, local).await {
+                Some(joined) => joined?,
+                None => {
+                    return Err(Error::Timeout {
+                        phase: EstimatePhase::Local,
+                    });
+                }
+            };
+            return Ok((local, None));
+        }
+        let mut remote = tokio::spawn({
             let estimator = self.remote_gas_estimator();
             let tx = tx.clone();
-            async move { estimator.estimate_gas(&tx).await }
+            let metrics = self.state.metrics.clone();
+            async move {
+                // Trust the gas used of a precomputed access list, if any.
+                match tx.precomputed_access_list() {
+                    Some(precomputed) => precomputed.gas_used().map(|gas| (gas, None)),
+                    None => {
+                        let start = Instant::now();
+                        let remote = estimator.estimate_gas_with_raw_response(&tx).await;
+                        metrics.record_remote(remote.is_ok(), start.elapsed());
+                        remote
+                    }
+                }
+            }
+            .instrument(info_span!("remote_estimate"))
         });
-        Ok((
-            local.await.map_err(Error::local_simulation_failed)?,
-            remote.await.map_err(Error::remote_gas_estimate_failed)?,
-        ))
+        let local = match Self::until_deadline(deadline, local).await {
+            Some(joined) => joined?,
+            None => Err(Error::Timeout {
+                phase: EstimatePhase::Local,
+            }),
+        };
+        let remote = match Self::until_deadline(deadline, &mut remote).await {
+            Some(joined) => joined.map_err(Error::remote_gas_estimate_failed)?,
+            None => {
+                warn!("Remote estimate not available within budget");
+                remote.abort();
+                if local.is_err() {
+                    return Err(self.budget_exhausted());
+                }
+                Err(Error::Timeout {
+                    phase: EstimatePhase::Remote,
+                })
+            }
+        };
+        if remote.is_ok() && tx.precomputed_access_list().is_none() {
+            self.mark_remote_reachable();
+        }
+        Ok((local, Some(remote)))
+    }
+
+    /// Computes the estimates of `tx` once a worker with `priority` is
+    /// available, see [`Self::compute_estimates`], sharing the computation
+    /// with concurrent requests of the identical transaction and mode
+    /// (single-flight), so that e.g. a burst of identical requests calls the
+    /// remote once. If the request computing is dropped, e.g. timed out,
+    /// one of the waiting requests takes over.
+    ///
+    /// Estimates which missed the `deadline` of the request computing them
+    /// are not shared, since the deadlines of the waiting requests differ,
+    /// instead one of them computes anew.
+    async fn compute_estimates_single_flight(
+        &self,
+        tx: &Transaction,
+        mode: EstimateMode,
+        deadline: Option<Instant>,
+        priority: WorkPriority,
+    ) -> ComputedEstimates {
+        let key = (tx.clone(), mode);
+        let in_flight = self.state.in_flight.entry(key.clone()).or_default().clone();
+        let compute = in_flight.get_or_try_init(|| async {
+            let Some(_permit) =
+                Self::until_deadline(deadline, self.work_queue.acquire(priority)).await
+            else {
+                return Err(Err(self.budget_exhausted()));
+            };
+            let computed = self.compute_estimates(tx, mode, deadline).await;
+            if Self::missed_deadline(&computed) {
+                return Err(computed);
+            }
+            Ok(computed)
+        });
+        let computed = match Self::until_deadline(deadline, compute).await {
+            Some(Ok(shared)) => shared.clone(),
+            Some(Err(unshared)) => unshared,
+            None => Err(self.budget_exhausted()),
+        };
+        self.state
+            .in_flight
+            .remove_if(&key, |_, cell| Arc::ptr_eq(cell, &in_flight));
+        computed
+    }
+
+    /// Awaits `future` until `deadline`, `None` if the deadline passed first,
+    /// without a deadline `future` is awaited until completion.
+    async fn until_deadline<F: IntoFuture>(
+        deadline: Option<Instant>,
+        future: F,
+    ) -> Option<F::Output> {
+        match deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline.into(), future).await.ok(),
+            None => Some(future.await),
+        }
     }
 
+    /// Whether `computed`, or any of its estimates, failed since the
+    /// deadline passed.
+    fn missed_deadline(computed: &ComputedEstimates) -> bool {
+        let is_deadline_error = |error: &Error| error.is_budget_exhausted() || error.is_timeout();
+        match computed {
+            Ok((local, remote)) => {
+                local.as_ref().err().is_some_and(is_deadline_error)
+                    || remote
+                        .as_ref()
+                        .and_then(|remote| remote.as_ref().err())
+                        .is_some_and(is_deadline_error)
+            }
+            Err(error) => is_deadline_error(error),
+        }
+    }
+
+    fn budget_exhausted(&self) -> Error {
+        Error::BudgetExhausted {
+            budget_millis: self.config.total_budget().unwrap_or_default().as_millis(),
+        }
+    }
+
+    /// Builds the response from the `local` and `remote` estimates, the
+    /// remote `None` if it was not called, in which case the local estimate
+    /// is corrected by [`AppState::correction_factor`], which in turn is
+    /// updated by the divergence of both, caches it if able, unless in
+    /// [`EstimateMode::Local`] which must not pin the less reliable local
+    /// estimate for requests in other modes, and collapses a
+    /// range if requested in `options`, the range is cached uncollapsed and
+    /// not capped by the gas limit of `tx`, since the entry is shared by
+    /// transactions differing only in gas limit.
     fn build_response(
         &self,
         tx: Transaction,
         local: Result<Gas>,
-        remote: Result<Gas>,
+        remote: Option<Result<Gas>>,
+        options: &EstimateOptions,
         start: Instant,
     ) -> Result<GasEstimateResponse> {
         let gas_limit_or_max = tx.gas_limit_else_max();
-        let dont_exceed_limit = |gas: Gas| min(gas, gas_limit_or_max);
         let kind = tx.kind();
+        let source = match (&local, &remote) {
+            (Ok(_), Some(Ok(_))) => Some(EstimateSource::Both),
+            (Ok(_), _) => Some(EstimateSource::LocalOnly),
+            (Err(_), Some(Ok(_))) => Some(EstimateSource::RemoteOnly),
+            (Err(_), _) => None,
+        };
+        self.state.metrics.record_estimate_source(source);
+        Span::current().record("branch", computed_source_label(source));
 
         match (local, remote) {
             (
@@ -143,59 +1054,167 @@ impl Gastimator {
                     estimated_cost,
                     gas_limit,
                 }),
-                Err(_), // we primarily trust the local error, which also provides the `estimated_cost`
+                None | Some(Err(_)), // we primarily trust the local error, which also provides the `estimated_cost`
             ) => Err(Error::GasExceedsLimit {
                 estimated_cost,
                 gas_limit,
             }),
-            (Err(local_err), Err(remote_err)) => {
-                error!("Local err: {:?}, Remote err: {:?}", local_err, remote_err);
+            (
+                Err(
+                    local_err @ (Error::SimulationReverted { .. } | Error::SimulationHalted { .. }),
+                ),
+                remote @ (None | Some(Err(_))),
+            ) => {
+                // the reason of the local revert is more useful than a generic failure
+                error!("Local err: {:?}, Remote: {:?}", local_err, remote);
+                Err(local_err)
+            }
+            (Err(_), Some(Err(remote_err))) if self.config.mode().is_remote_only() => {
+                // the remote is the only estimator, its error is the reason
+                error!("Remote err: {:?}", remote_err);
+                Err(remote_err)
+            }
+            (Err(local_err), remote @ (None | Some(Err(_)))) => {
+                error!("Local err: {:?}, Remote: {:?}", local_err, remote);
                 Err(Error::FailedToCalculateGasEstimate)
             }
-            (Err(_), Ok(remote)) => {
+            (Err(_), Some(Ok(remote))) => {
                 warn!("Local failed, using remote: {}", remote);
-                Ok(Self::build_response_raw(
-                    GasUsage::Estimate {
+                // without local simulation, the heuristic cost of the code of
+                // a creation stands in for the local estimate, widening the
+                // range if the remote estimate is lower, which stays the
+                // recommended since it is authoritative. Remote only the
+                // remote estimate is returned alone.
+                let heuristic = (kind.is_contract_creation()
+                    && !self.config.mode().is_remote_only())
+                .then(|| Self::heuristic_contract_creation_estimate(&tx))
+                .filter(|heuristic| *heuristic > remote);
+                let gas_usage = match heuristic {
+                    Some(heuristic) => GasUsage::EstimateWithRange {
                         kind,
-                        gas: dont_exceed_limit(remote),
+                        low: remote,
+                        high: heuristic,
+                        recommended: remote,
                     },
+                    None => GasUsage::Estimate { kind, gas: remote },
+                };
+                Ok(Self::build_response_raw(
+                    gas_usage,
+                    EstimateSource::RemoteOnly,
                     start,
                 ))
             }
-            (Ok(local), Err(_)) => {
-                warn!("Remote failed, using local: {}", local);
+            (Ok(local), remote @ (None | Some(Err(_)))) => {
+                // not sampled for the remote, corrected by the divergence of
+                // the sampled, unless local mode was requested explicitly.
+                let local = if remote.is_none() && !options.mode().is_local() {
+                    self.state.correction_factor.corrected(local)
+                } else {
+                    local
+                };
+                if let Some(Err(remote_err)) = remote {
+                    warn!(
+                        "Remote failed, using local: {}, remote err: {:?}",
+                        local, remote_err
+                    );
+                }
                 Ok(Self::build_response_raw(
-                    GasUsage::Estimate {
-                        kind,
-                        gas: dont_exceed_limit(local),
-                    },
+                    GasUsage::Estimate { kind, gas: local },
+                    EstimateSource::LocalOnly,
                     start,
                 ))
             }
-            (Ok(local), Ok(remote)) => {
+            (Ok(local), Some(Ok(remote))) => {
                 info!("Local: {}, Remote: {}", local, remote);
+                if tx.precomputed_access_list().is_none() {
+                    self.state.correction_factor.update(local, remote);
+                }
                 // low is `min`
                 let low = min(local, remote);
-                // high is `max`
-                // `dont_exceed_limit` is used to ensure that the gas limit is not exceeded
-                let high = dont_exceed_limit(max(local, remote));
+                // high is `max`, capped by the gas limit in `pad_and_collapse`
+                let high = max(local, remote);
+                let recommended = min(self.recommended(local, remote), high);
                 Ok(Self::build_response_raw(
-                    GasUsage::EstimateWithRange { kind, low, high },
+                    GasUsage::EstimateWithRange {
+                        kind,
+                        low,
+                        high,
+                        recommended,
+                    },
+                    EstimateSource::Both,
                     start,
                 ))
             }
         }
-        .map(|resp| {
-            if tx.is_cacheable() {
-                self.state.cache.insert(tx, resp.gas_usage().clone());
+        .inspect(|resp| {
+            if tx.is_cacheable() && !options.mode().is_local() {
+                let estimate = CachedEstimateBuilder::default()
+                    .gas_usage(resp.gas_usage().clone())
+                    .compute_millis(*resp.time_elapsed_in_millis())
+                    .build()
+                    .unwrap();
+                self.state.cache.insert(CacheKey::of(&tx), estimate);
             }
+        })
+        .map(|mut resp| {
+            self.pad_and_collapse(&mut resp, gas_limit_or_max, options);
             resp
         })
     }
 
-    fn build_response_raw(gas_usage: GasUsage, start: Instant) -> GasEstimateResponse {
+    /// The recommended gas of the `local` and `remote` estimates, picked by
+    /// [`GastimatorConfig::estimate_policy`].
+    fn recommended(&self, local: Gas, remote: Gas) -> Gas {
+        self.config
+            .estimate_policy()
+            .recommended(local, remote, self.config.remote_weight())
+    }
+
+    /// A heuristic estimate of the contract creation `tx` without simulating
+    /// it, the `21000` base and the approximated cost of depositing its code,
+    /// see [`Gas::min_contract_creation_with_code`].
+    fn heuristic_contract_creation_estimate(tx: &Transaction) -> Gas {
+        Gas::from(
+            *Gas::exact_native_token_transfer() + *Gas::min_contract_creation_with_code(tx.input()),
+        )
+    }
+
+    /// Caps the gas usage of `response` at `gas_limit_or_max` and pads it
+    /// with the safety margin, also capped by `gas_limit_or_max`, keeping the
+    /// unpadded gas usage if changed, and collapses both if requested in
+    /// `options`.
+    fn pad_and_collapse(
+        &self,
+        response: &mut GasEstimateResponse,
+        gas_limit_or_max: Gas,
+        options: &EstimateOptions,
+    ) {
+        let unpadded = response.gas_usage().clone().capped(gas_limit_or_max);
+        let padded = unpadded
+            .clone()
+            .padded(self.config.safety_margin(), gas_limit_or_max);
+        if padded != unpadded {
+            response.set_unpadded_gas_usage(Some(Self::collapse_if_requested(unpadded, options)));
+        }
+        response.set_gas_usage(Self::collapse_if_requested(padded, options));
+    }
+
+    fn collapse_if_requested(gas_usage: GasUsage, options: &EstimateOptions) -> GasUsage {
+        match options.collapse() {
+            Some(collapse) => gas_usage.collapsed(collapse),
+            None => gas_usage,
+        }
+    }
+
+    fn build_response_raw(
+        gas_usage: GasUsage,
+        source: EstimateSource,
+        start: Instant,
+    ) -> GasEstimateResponse {
         GasEstimateResponseBuilder::default()
+            .kind(gas_usage.transaction_kind().clone())
             .gas_usage(gas_usage)
+            .source(source)
             .time_elapsed_in_millis(start.elapsed().as_millis())
             .build()
             .unwrap()
@@ -209,6 +1228,19 @@ mod tests {
 
     type Sut = Gastimator;
 
+    fn sut_with_config(
+        local: Arc<dyn LocalTxSimulator + Send + Sync>,
+        remote: Arc<dyn RemoteGasEstimator + Send + Sync>,
+        config: GastimatorConfig,
+    ) -> Sut {
+        let dependencies = DependenciesBuilder::default()
+            .local_gas_estimator(local)
+            .remote_gas_estimator(remote)
+            .build()
+            .unwrap();
+        Sut::with_config(Arc::new(dependencies), config)
+    }
+
     struct FailLocal;
     impl FailLocal {
         fn new() -> Arc<Self> {
@@ -263,19 +1295,51 @@ mod tests {
         }
     }
 
-    #[tokio::test]
-    async fn fail() {
-        let sut = Arc::new(Sut::with_dependencies(FailLocal::new(), FailRemote::new()));
-        let res = sut.estimate_gas(Transaction::default()).await;
-
-        assert!(res.is_err());
+    struct RemoteHardcodedWithRawResponse(Gas);
+    #[async_trait::async_trait]
+    impl RemoteGasEstimator for RemoteHardcodedWithRawResponse {
+        async fn estimate_gas(&self, _: &Transaction) -> Result<Gas> {
+            Ok(self.0)
+        }
+        async fn estimate_gas_with_raw_response(
+            &self,
+            _: &Transaction,
+        ) -> Result<(Gas, Option<String>)> {
+            let raw = format!(r#"{{"jsonrpc":"2.0","id":1,"result":"0x{:x}"}}"#, *self.0);
+            Ok((self.0, Some(raw)))
+        }
+    }
+    impl RemoteHardcodedWithRawResponse {
+        fn new(hardcoded: Gas) -> Arc<Self> {
+            Arc::new(Self(hardcoded))
+        }
     }
 
-    #[tokio::test]
+    struct SlowRemote(Gas);
+    #[async_trait::async_trait]
+    impl RemoteGasEstimator for SlowRemote {
+        async fn estimate_gas(&self, _: &Transaction) -> Result<Gas> {
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            Ok(self.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn fail() {
+        let sut = Arc::new(Sut::with_dependencies(
+            Some(FailLocal::new()),
+            FailRemote::new(),
+        ));
+        let res = sut.estimate_gas(Transaction::default()).await;
+
+        assert!(res.is_err());
+    }
+
+    #[tokio::test]
     async fn remote_fail_local_ok() {
         let local_estimate = Gas::from(60000);
         let sut = Arc::new(Sut::with_dependencies(
-            LocalTxSimulatorHardCoded::new(local_estimate),
+            Some(LocalTxSimulatorHardCoded::new(local_estimate)),
             FailRemote::new(),
         ));
         let res = sut
@@ -291,9 +1355,9 @@ mod tests {
 
     #[tokio::test]
     async fn remote_ok_local_fail() {
-        let remote_estimate = Gas::from(60000);
+        let remote_estimate = Gas::from(160000);
         let sut = Arc::new(Sut::with_dependencies(
-            FailLocal::new(),
+            Some(FailLocal::new()),
             RemoteHardcoded::new(remote_estimate),
         ));
         let res = sut
@@ -307,12 +1371,177 @@ mod tests {
         assert_eq!(res.unwrap().gas_usage(), expected);
     }
 
+    #[tokio::test]
+    async fn remote_ok_local_fail_contract_creation_is_ranged_by_heuristic() {
+        let sut = Arc::new(Sut::with_dependencies(
+            Some(FailLocal::new()),
+            RemoteHardcoded::new(Gas::from(60000)),
+        ));
+        let tx = Transaction::sample_contract_creation();
+        let res = sut.estimate_gas(tx.clone()).await;
+
+        // 21000 + 32000 + 400 * 200 deposit + 400 * 16 calldata
+        let expected = &GasUsage::EstimateWithRange {
+            kind: TransactionKind::ContractCreation,
+            low: Gas::from(60000),
+            high: Gas::from(139_400),
+            recommended: Gas::from(60000), // not raised, the remote is authoritative
+        };
+        assert_eq!(res.unwrap().gas_usage(), expected);
+    }
+
+    #[tokio::test]
+    async fn remote_only_contract_creation_is_not_ranged_by_heuristic() {
+        let sut = Sut::with_dependencies(None, RemoteHardcoded::new(Gas::from(60000)));
+
+        let res = sut
+            .estimate_gas(Transaction::sample_contract_creation())
+            .await;
+
+        let expected = &GasUsage::Estimate {
+            kind: TransactionKind::ContractCreation,
+            gas: Gas::from(60000),
+        };
+        assert_eq!(res.unwrap().gas_usage(), expected);
+    }
+
+    #[tokio::test]
+    async fn remote_only_estimates_by_remote() {
+        let remote_estimate = Gas::from(60000);
+        let sut = Sut::with_dependencies(None, RemoteHardcoded::new(remote_estimate));
+        let tx = TransactionBuilder::default()
+            .to(Address::from([0xcc; 20]))
+            .input(Bytes::from_static(&[0xde, 0xad]))
+            .build()
+            .unwrap();
+
+        let res = sut.estimate_gas(tx).await;
+
+        let expected = &GasUsage::Estimate {
+            kind: TransactionKind::ContractCall {
+                with_native_token_transfer: false,
+            },
+            gas: remote_estimate,
+        };
+        assert_eq!(res.unwrap().gas_usage(), expected);
+        let (ok, error) = sut.metrics().local_counts();
+        assert_eq!(ok + error, 0);
+    }
+
+    #[tokio::test]
+    async fn remote_only_surfaces_remote_error() {
+        let sut = Sut::with_dependencies(None, FailRemote::new());
+
+        let res = sut
+            .estimate_gas(Transaction::sample_contract_creation())
+            .await;
+
+        assert!(res.unwrap_err().is_remote_gas_estimate_failed());
+    }
+
+    #[tokio::test]
+    async fn safety_margin_pads_estimate_keeping_unpadded() {
+        let sut = sut_with_config(
+            LocalTxSimulatorHardCoded::new(Gas::from(40_000)),
+            RemoteHardcoded::new(Gas::from(60_000)),
+            GastimatorConfigBuilder::default()
+                .safety_margin(1.1)
+                .build()
+                .unwrap(),
+        );
+        let tx = Transaction::sample_contract_creation_gas_limit(Gas::from(65_000));
+
+        let res = sut.estimate_gas(tx).await.unwrap();
+
+        assert_eq!(
+            res.gas_usage(),
+            &GasUsage::EstimateWithRange {
+                kind: TransactionKind::ContractCreation,
+                low: Gas::from(40_000),
+                high: Gas::from(65_000), // capped by gas limit
+                recommended: Gas::from(60_500),
+            }
+        );
+        assert_eq!(
+            res.unpadded_gas_usage(),
+            &Some(GasUsage::EstimateWithRange {
+                kind: TransactionKind::ContractCreation,
+                low: Gas::from(40_000),
+                high: Gas::from(60_000),
+                recommended: Gas::from(55_000),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn local_mode_does_not_call_remote() {
+        let local_estimate = Gas::from(40000);
+        let remote = Arc::new(CountingRemote::default());
+        let sut = Sut::with_dependencies(
+            Some(LocalTxSimulatorHardCoded::new(local_estimate)),
+            remote.clone(),
+        );
+        let options = EstimateOptionsBuilder::default()
+            .mode(EstimateMode::Local)
+            .build()
+            .unwrap();
+
+        let res = sut
+            .estimate_gas_with_options(Transaction::sample_contract_creation(), options)
+            .await;
+
+        let expected = &GasUsage::Estimate {
+            kind: TransactionKind::ContractCreation,
+            gas: local_estimate,
+        };
+        assert_eq!(res.unwrap().gas_usage(), expected);
+        assert_eq!(remote.0.load(std::sync::atomic::Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn local_mode_neither_reads_nor_writes_cache() {
+        let local_estimate = Gas::from(40_000);
+        let remote = Arc::new(CountingRemote::default());
+        let sut = Sut::with_dependencies(
+            Some(LocalTxSimulatorHardCoded::new(local_estimate)),
+            remote.clone(),
+        );
+        let local_mode = || {
+            EstimateOptionsBuilder::default()
+                .mode(EstimateMode::Local)
+                .build()
+                .unwrap()
+        };
+        let tx = Transaction::sample_contract_creation_cachable();
+
+        sut.estimate_gas_with_options(tx.clone(), local_mode())
+            .await
+            .unwrap();
+        let default_mode = sut.estimate_gas(tx.clone()).await.unwrap();
+        let local = sut
+            .estimate_gas_with_options(tx, local_mode())
+            .await
+            .unwrap();
+
+        assert_eq!(remote.0.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert!(!default_mode.from_cache());
+        assert_eq!(default_mode.source(), EstimateSource::Both);
+        assert!(!local.from_cache());
+        assert_eq!(
+            local.gas_usage(),
+            &GasUsage::Estimate {
+                kind: TransactionKind::ContractCreation,
+                gas: local_estimate,
+            }
+        );
+    }
+
     #[tokio::test]
     async fn remote_ok_local_fail_with_limit() {
         let limit = Gas::from(10000);
         let remote_estimate = Gas::from(60000);
         let sut = Arc::new(Sut::with_dependencies(
-            FailLocal::new(),
+            Some(FailLocal::new()),
             RemoteHardcoded::new(remote_estimate),
         ));
         let res = sut
@@ -333,7 +1562,7 @@ mod tests {
         let local_estimate = Gas::from(40000);
         let remote_estimate = Gas::from(60000);
         let sut = Arc::new(Sut::with_dependencies(
-            LocalTxSimulatorHardCoded::new(local_estimate),
+            Some(LocalTxSimulatorHardCoded::new(local_estimate)),
             RemoteHardcoded::new(remote_estimate),
         ));
         let res = sut
@@ -344,18 +1573,45 @@ mod tests {
             kind: TransactionKind::ContractCreation,
             low: local_estimate,
             high: remote_estimate,
+            recommended: Gas::from(55_000), // weighted towards remote
         };
         assert_eq!(res.unwrap().gas_usage(), expected);
     }
 
     #[tokio::test]
-    async fn both_ok_gives_range_with_limit() {
+    async fn both_ok_recommends_by_estimate_policy() {
         let local_estimate = Gas::from(40000);
         let remote_estimate = Gas::from(60000);
-        let limit = Gas::from(50000);
-        let sut = Arc::new(Sut::with_dependencies(
+        let sut = sut_with_config(
             LocalTxSimulatorHardCoded::new(local_estimate),
             RemoteHardcoded::new(remote_estimate),
+            GastimatorConfigBuilder::default()
+                .estimate_policy(EstimatePolicy::PreferRemote)
+                .build()
+                .unwrap(),
+        );
+        let res = sut
+            .estimate_gas(Transaction::sample_contract_creation())
+            .await;
+
+        let expected = &GasUsage::EstimateWithRange {
+            kind: TransactionKind::ContractCreation,
+            low: local_estimate,
+            high: remote_estimate,
+            recommended: remote_estimate,
+        };
+        assert_eq!(res.unwrap().gas_usage(), expected);
+    }
+
+    #[tokio::test]
+    async fn both_ok_gives_range_with_limit() {
+        // above the intrinsic gas of the sample contract creation
+        let local_estimate = Gas::from(70000);
+        let remote_estimate = Gas::from(90000);
+        let limit = Gas::from(80000);
+        let sut = Arc::new(Sut::with_dependencies(
+            Some(LocalTxSimulatorHardCoded::new(local_estimate)),
+            RemoteHardcoded::new(remote_estimate),
         ));
         let res = sut
             .estimate_gas(Transaction::sample_contract_creation_gas_limit(limit))
@@ -365,7 +1621,1464 @@ mod tests {
             kind: TransactionKind::ContractCreation,
             low: local_estimate,
             high: limit, // not remote_estimate
+            recommended: limit,
         };
         assert_eq!(res.unwrap().gas_usage(), expected);
     }
+
+    #[tokio::test]
+    async fn gas_limit_below_intrinsic_gas_fails_without_simulating() {
+        let remote = Arc::new(CountingRemote::default());
+        let sut = Sut::with_dependencies(Some(FailLocal::new()), remote.clone());
+        let tx = Transaction::sample_contract_creation_gas_limit(Gas::from(50_000));
+
+        let res = sut.estimate_gas(tx.clone()).await;
+
+        assert_eq!(
+            res.err(),
+            Some(Error::GasExceedsLimit {
+                estimated_cost: Some(tx.intrinsic_gas()),
+                gas_limit: Gas::from(50_000)
+            })
+        );
+        assert_eq!(remote.0.load(std::sync::atomic::Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn local_revert_is_surfaced_if_remote_fails() {
+        let contract = Address::new([0xcc; 20]);
+        let sut = Sut::with_dependencies(Some(Arc::new(RevmTxSimulator::new())), FailRemote::new());
+        let tx = TransactionBuilder::default()
+            .to(contract)
+            .state_override(StateOverride::new([(
+                contract,
+                AccountOverrideBuilder::default()
+                    // PUSH1 0 DUP1 REVERT
+                    .code(Bytes::from([0x60, 0x00, 0x80, 0xfd]))
+                    .build()
+                    .unwrap(),
+            )]))
+            .build()
+            .unwrap();
+
+        let res = sut.estimate_gas(tx).await;
+
+        assert!(matches!(
+            res,
+            Err(Error::SimulationReverted { reason, .. }) if reason == "without reason"
+        ));
+    }
+
+    #[tokio::test]
+    async fn raw_remote_response_is_included_when_requested() {
+        let remote_estimate = Gas::from(60000);
+        let sut = Arc::new(Sut::with_dependencies(
+            Some(LocalTxSimulatorHardCoded::new(Gas::from(40000))),
+            RemoteHardcodedWithRawResponse::new(remote_estimate),
+        ));
+        let res = sut
+            .estimate_gas_with_options(
+                Transaction::sample_contract_creation(),
+                EstimateOptionsBuilder::default()
+                    .raw_remote(true)
+                    .build()
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let raw = res.raw_remote_response().as_ref().unwrap();
+        let parsed: RpcResponse = serde_json::from_str(raw).unwrap();
+        let gas = parsed.result.to_u64().unwrap();
+        assert_eq!(Gas::from(gas), remote_estimate);
+    }
+
+    #[tokio::test]
+    async fn raw_remote_response_is_omitted_by_default() {
+        let sut = Arc::new(Sut::with_dependencies(
+            Some(LocalTxSimulatorHardCoded::new(Gas::from(40000))),
+            RemoteHardcodedWithRawResponse::new(Gas::from(60000)),
+        ));
+        let res = sut
+            .estimate_gas(Transaction::sample_contract_creation())
+            .await
+            .unwrap();
+
+        assert_eq!(res.raw_remote_response(), &None);
+    }
+
+    #[tokio::test]
+    async fn single_request_is_not_starved_by_large_batch() {
+        let config = GastimatorConfigBuilder::default()
+            .worker_pool_size(1usize)
+            .build()
+            .unwrap();
+        let sut = Arc::new(sut_with_config(
+            LocalTxSimulatorHardCoded::new(Gas::from(40000)),
+            Arc::new(SlowRemote(Gas::from(60000))),
+            config,
+        ));
+        let finished = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+        let batch = (0..10)
+            .map(|i| {
+                let sut = sut.clone();
+                let finished = finished.clone();
+                tokio::spawn(async move {
+                    // Distinct transactions, identical ones would share one
+                    // computation.
+                    sut.estimate_gas_with_priority(
+                        Transaction::sample_contract_creation_gas_limit(Gas::from(1_000_000 + i)),
+                        EstimateOptions::default(),
+                        WorkPriority::Low,
+                    )
+                    .await
+                    .unwrap();
+                    finished.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                })
+            })
+            .collect::<Vec<_>>();
+        // let the batch occupy the worker and fill the queue
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+
+        sut.estimate_gas(Transaction::sample_contract_creation())
+            .await
+            .unwrap();
+        let finished_before_single = finished.load(std::sync::atomic::Ordering::SeqCst);
+
+        for item in batch {
+            item.await.unwrap();
+        }
+        // Only the batch item occupying the worker when the single request
+        // arrived is allowed to finish before it.
+        assert!(finished_before_single <= 1);
+    }
+
+    /// Local simulator which tracks the max number of concurrent simulations.
+    #[derive(Default)]
+    struct ConcurrencyTrackingLocal {
+        running: std::sync::atomic::AtomicUsize,
+        max_running: std::sync::atomic::AtomicUsize,
+    }
+
+    impl LocalTxSimulator for ConcurrencyTrackingLocal {
+        fn locally_simulate_tx(&self, _: &Transaction) -> Result<Gas> {
+            let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_running.fetch_max(running, Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            self.running.fetch_sub(1, Ordering::SeqCst);
+            Ok(Gas::from(40_000))
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrent_local_simulations_do_not_exceed_configured_threads() {
+        let local = Arc::new(ConcurrencyTrackingLocal::default());
+        let sut = sut_with_config(
+            local.clone(),
+            RemoteHardcoded::new(Gas::from(60_000)),
+            GastimatorConfigBuilder::default()
+                .worker_pool_size(8usize)
+                .local_simulation_threads(2usize)
+                .build()
+                .unwrap(),
+        );
+        // Distinct transactions, identical ones would share one computation.
+        let txs = (0..8)
+            .map(|i| Transaction::sample_contract_creation_gas_limit(Gas::from(1_000_000 + i)))
+            .collect();
+
+        let res = sut
+            .estimate_gas_batch(txs, EstimateOptions::default())
+            .await;
+
+        assert!(res.iter().all(Result::is_ok));
+        assert_eq!(local.max_running.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn blob_carrying_surfaces_blob_gas_separately() {
+        let local_estimate = Gas::from(21_080);
+        let sut = Arc::new(Sut::with_dependencies(
+            Some(LocalTxSimulatorHardCoded::new(local_estimate)),
+            FailRemote::new(),
+        ));
+        let res = sut
+            .estimate_gas(Transaction::sample_blob_carrying())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            res.gas_usage(),
+            &GasUsage::Estimate {
+                kind: TransactionKind::BlobCarrying,
+                gas: local_estimate,
+            }
+        );
+        assert_eq!(res.blob_gas(), &Some(Gas::blob_gas(2)));
+    }
+
+    #[tokio::test]
+    async fn blob_carrying_reports_blob_base_fee_and_blob_fee() {
+        let excess_blob_gas = 10_000_000;
+        let local = Arc::new(
+            RevmTxSimulator::builder()
+                .excess_blob_gas(excess_blob_gas)
+                .build(),
+        );
+        let blob_base_fee = local.blob_base_fee().unwrap();
+        let sut = Arc::new(Sut::with_dependencies(Some(local), FailRemote::new()));
+        let res = sut
+            .estimate_gas(Transaction::sample_blob_carrying())
+            .await
+            .unwrap();
+
+        assert_eq!(res.blob_base_fee(), &Some(blob_base_fee));
+        assert_eq!(
+            res.blob_fee(),
+            &Some(U256::from(*Gas::blob_gas(2)) * U256::from(blob_base_fee))
+        );
+    }
+
+    #[tokio::test]
+    async fn gas_breakdown_of_local_simulation_is_included() {
+        let sut = Arc::new(Sut::with_dependencies(
+            Some(Arc::new(RevmTxSimulator::new())),
+            FailRemote::new(),
+        ));
+        let res = sut
+            .estimate_gas(Transaction::sample_contract_creation())
+            .await
+            .unwrap();
+
+        let GasUsage::Estimate { gas, .. } = res.gas_usage() else {
+            panic!("Expected estimate, got: {:?}", res.gas_usage());
+        };
+        let breakdown = res.gas_breakdown().unwrap();
+        assert_eq!(breakdown.total(), *gas);
+        assert!(*breakdown.intrinsic() > Gas::exact_native_token_transfer());
+    }
+
+    #[tokio::test]
+    async fn gas_breakdown_omitted_if_local_fails() {
+        let sut = Arc::new(Sut::with_dependencies(
+            Some(FailLocal::new()),
+            RemoteHardcoded::new(Gas::from(50_000)),
+        ));
+        let res = sut
+            .estimate_gas(Transaction::sample_contract_creation())
+            .await
+            .unwrap();
+
+        assert_eq!(res.gas_breakdown(), &None);
+    }
+
+    fn empty_transaction() -> Transaction {
+        TransactionBuilder::default()
+            .to(TxKind::Create)
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn empty_transaction_is_rejected_by_default() {
+        let sut = Arc::new(Sut::with_dependencies(
+            Some(LocalTxSimulatorHardCoded::new(Gas::from(53_000))),
+            RemoteHardcoded::new(Gas::from(53_000)),
+        ));
+        let res = sut.estimate_gas(empty_transaction()).await;
+        assert_eq!(res, Err(Error::EmptyTransaction));
+    }
+
+    #[tokio::test]
+    async fn contract_creation_without_init_code_is_rejected() {
+        let sut = Arc::new(Sut::with_dependencies(
+            Some(LocalTxSimulatorHardCoded::new(Gas::from(53_000))),
+            RemoteHardcoded::new(Gas::from(53_000)),
+        ));
+        let tx = TransactionBuilder::default()
+            .to(TxKind::Create)
+            .value(U256::from(1))
+            .build()
+            .unwrap();
+        let res = sut.estimate_gas(tx).await;
+        assert!(res.unwrap_err().is_invalid_transaction());
+    }
+
+    #[tokio::test]
+    async fn empty_transaction_minimal_estimate() {
+        let sut = sut_with_config(
+            FailLocal::new(),
+            FailRemote::new(),
+            GastimatorConfigBuilder::default()
+                .empty_transaction_policy(EmptyTransactionPolicy::MinimalEstimate)
+                .build()
+                .unwrap(),
+        );
+        let res = sut.estimate_gas(empty_transaction()).await.unwrap();
+        assert_eq!(
+            res.gas_usage(),
+            &GasUsage::Exact {
+                kind: TransactionKind::Unknown,
+                gas: Gas::exact_empty_contract_creation(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn collapse_range_is_applied_and_range_is_cached() {
+        let sut = Arc::new(Sut::with_dependencies(
+            Some(LocalTxSimulatorHardCoded::new(Gas::from(30_000))),
+            RemoteHardcoded::new(Gas::from(40_000)),
+        ));
+        let tx = TransactionBuilder::default()
+            .nonce(1)
+            .from(Address::from([0xab; 20]))
+            .to(TxKind::Create)
+            .input(Bytes::from([0xab; 10]))
+            .build()
+            .unwrap();
+        let options = EstimateOptionsBuilder::default()
+            .collapse(RangeCollapse::High)
+            .build()
+            .unwrap();
+
+        let res = sut
+            .estimate_gas_with_options(tx.clone(), options)
+            .await
+            .unwrap();
+        assert_eq!(
+            res.gas_usage(),
+            &GasUsage::Estimate {
+                kind: TransactionKind::ContractCreation,
+                gas: Gas::from(40_000),
+            }
+        );
+
+        // cached uncollapsed
+        let res = sut.estimate_gas(tx).await.unwrap();
+        assert_eq!(
+            res.gas_usage(),
+            &GasUsage::EstimateWithRange {
+                kind: TransactionKind::ContractCreation,
+                low: Gas::from(30_000),
+                high: Gas::from(40_000),
+                recommended: Gas::from(37_500),
+            }
+        );
+    }
+
+    /// Local simulator which signals that it has started and then blocks
+    /// until released.
+    struct BlockingLocal {
+        started: std::sync::mpsc::Sender<()>,
+        release: std::sync::Mutex<std::sync::mpsc::Receiver<()>>,
+    }
+    impl LocalTxSimulator for BlockingLocal {
+        fn locally_simulate_tx(&self, _: &Transaction) -> Result<Gas> {
+            self.started.send(()).unwrap();
+            self.release.lock().unwrap().recv().unwrap();
+            Ok(Gas::from(21_000))
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn not_ready_during_warm_up_ready_after() {
+        let (started_tx, started) = std::sync::mpsc::channel();
+        let (release, release_rx) = std::sync::mpsc::channel();
+        let sut = Arc::new(Sut::with_dependencies(
+            Some(Arc::new(BlockingLocal {
+                started: started_tx,
+                release: std::sync::Mutex::new(release_rx),
+            })),
+            RemoteHardcoded::new(Gas::from(21_000)),
+        ));
+        let warm_up = tokio::spawn({
+            let sut = sut.clone();
+            async move { sut.warm_up().await }
+        });
+        started.recv().unwrap();
+        assert!(!sut.is_ready());
+
+        release.send(()).unwrap();
+        warm_up.await.unwrap();
+
+        assert!(sut.is_ready());
+    }
+
+    #[tokio::test]
+    async fn not_ready_until_warmed_up() {
+        let sut = Sut::with_dependencies(
+            Some(LocalTxSimulatorHardCoded::new(Gas::from(30_000))),
+            RemoteHardcoded::new(Gas::from(40_000)),
+        );
+        sut.estimate_gas(Transaction::sample_contract_creation())
+            .await
+            .unwrap();
+        assert!(!sut.is_ready());
+
+        sut.warm_up().await;
+
+        assert!(sut.is_ready());
+    }
+
+    #[tokio::test]
+    async fn not_ready_if_remote_fails() {
+        let sut = Sut::with_dependencies(
+            Some(LocalTxSimulatorHardCoded::new(Gas::from(30_000))),
+            FailRemote::new(),
+        );
+        sut.warm_up().await;
+        assert!(sut.ping_remote().await.is_err());
+
+        assert!(!sut.is_ready());
+    }
+
+    #[tokio::test]
+    async fn local_only_is_ready_after_warm_up() {
+        let sut = sut_with_config(
+            LocalTxSimulatorHardCoded::new(Gas::from(30_000)),
+            Arc::new(NoRemoteGasEstimator),
+            GastimatorConfigBuilder::default()
+                .mode(GastimatorMode::LocalOnly)
+                .build()
+                .unwrap(),
+        );
+        assert!(!sut.is_ready());
+
+        sut.warm_up().await;
+
+        assert!(sut.is_ready());
+    }
+
+    /// Remote which counts the number of times it is called.
+    #[derive(Default)]
+    struct CountingRemote(std::sync::atomic::AtomicUsize);
+    #[async_trait::async_trait]
+    impl RemoteGasEstimator for CountingRemote {
+        async fn estimate_gas(&self, _: &Transaction) -> Result<Gas> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(Gas::from(50_000))
+        }
+    }
+
+    #[tokio::test]
+    async fn transactions_differing_only_in_gas_limit_share_cache_entry() {
+        let remote = Arc::new(CountingRemote::default());
+        let sut = Sut::with_dependencies(
+            Some(LocalTxSimulatorHardCoded::new(Gas::from(30_000))),
+            remote.clone(),
+        );
+        let with_limit = |gas_limit: u64| {
+            TransactionBuilder::default()
+                .nonce(0)
+                .from(Address::from([0xab; 20]))
+                .to(Address::from([0xcd; 20]))
+                .input(Bytes::from([0xab; 10]))
+                .gas_limit(Gas::from(gas_limit))
+                .build()
+                .unwrap()
+        };
+
+        sut.estimate_gas(with_limit(100_000)).await.unwrap();
+        let capped = sut.estimate_gas(with_limit(40_000)).await.unwrap();
+        let exceeding = sut.estimate_gas(with_limit(25_000)).await;
+
+        assert_eq!(remote.0.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(sut.cache_stats().hits(), 2);
+        assert_eq!(capped.gas_usage().max_gas(), Gas::from(40_000));
+        assert_eq!(
+            exceeding.err(),
+            Some(Error::GasExceedsLimit {
+                estimated_cost: Some(Gas::from(30_000)),
+                gas_limit: Gas::from(25_000),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn concurrent_identical_requests_share_one_computation() {
+        let remote = Arc::new(CountingRemote::default());
+        let sut = Sut::with_dependencies(
+            Some(LocalTxSimulatorHardCoded::new(Gas::from(40_000))),
+            remote.clone(),
+        );
+        let tx = Transaction::sample_contract_creation();
+
+        let responses =
+            futures_util::future::join_all((0..10).map(|_| sut.estimate_gas(tx.clone()))).await;
+
+        assert_eq!(remote.0.load(std::sync::atomic::Ordering::SeqCst), 1);
+        let first = responses[0].as_ref().unwrap().gas_usage();
+        assert!(
+            responses
+                .iter()
+                .all(|response| response.as_ref().unwrap().gas_usage() == first)
+        );
+        assert!(sut.state.in_flight.is_empty());
+
+        // Once computed, a later request computes anew.
+        sut.estimate_gas(tx).await.unwrap();
+        assert_eq!(remote.0.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    /// Local which counts the number of times it is called.
+    #[derive(Default)]
+    struct CountingLocal(std::sync::atomic::AtomicUsize);
+    impl LocalTxSimulator for CountingLocal {
+        fn locally_simulate_tx(&self, _: &Transaction) -> Result<Gas> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(Gas::from(40_000))
+        }
+    }
+
+    async fn local_calls_of_two_uncacheable_estimates(kind_stable_cache: bool) -> usize {
+        let local = Arc::new(CountingLocal::default());
+        let sut = sut_with_config(
+            local.clone(),
+            RemoteHardcoded::new(Gas::from(60_000)),
+            GastimatorConfigBuilder::default()
+                .kind_stable_cache(kind_stable_cache)
+                .build()
+                .unwrap(),
+        );
+        for gas_limit in [100_000, 200_000] {
+            let tx = Transaction::sample_contract_creation_gas_limit(Gas::from(gas_limit));
+            assert!(!tx.is_cacheable());
+            sut.estimate_gas(tx).await.unwrap();
+        }
+        local.0.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    #[tokio::test]
+    async fn kind_stable_cache_skips_local_simulation() {
+        assert_eq!(local_calls_of_two_uncacheable_estimates(true).await, 1);
+        assert_eq!(local_calls_of_two_uncacheable_estimates(false).await, 2);
+    }
+
+    #[tokio::test]
+    async fn kind_stable_cache_hit_checks_gas_limit() {
+        let sut = sut_with_config(
+            LocalTxSimulatorHardCoded::new(Gas::from(200_000)),
+            FailRemote::new(),
+            GastimatorConfigBuilder::default()
+                .kind_stable_cache(true)
+                .build()
+                .unwrap(),
+        );
+        let tx = Transaction::sample_contract_creation_gas_limit(Gas::from(300_000));
+        sut.estimate_gas(tx).await.unwrap();
+
+        let limit = Gas::from(150_000);
+        let res = sut
+            .estimate_gas(Transaction::sample_contract_creation_gas_limit(limit))
+            .await;
+
+        assert_eq!(
+            res,
+            Err(Error::GasExceedsLimit {
+                estimated_cost: Some(Gas::from(200_000)),
+                gas_limit: limit,
+            })
+        );
+    }
+
+    fn tx_with_precomputed_access_list(error: Option<&str>) -> Transaction {
+        use alloy::eips::eip2930::{AccessList, AccessListItem, AccessListResult};
+        let access_list = AccessList(vec![AccessListItem {
+            address: Address::from([0xcc; 20]),
+            storage_keys: vec![B256::repeat_byte(1)],
+        }]);
+        TransactionBuilder::default()
+            .to(Address::from([0xcc; 20]))
+            .input(Bytes::new())
+            .precomputed_access_list(PrecomputedAccessList::new(AccessListResult {
+                access_list,
+                gas_used: U256::from(30_000),
+                error: error.map(ToOwned::to_owned),
+            }))
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn precomputed_access_list_skips_remote_and_is_in_intrinsic_cost() {
+        let remote = Arc::new(CountingRemote::default());
+        let sut = Sut::with_dependencies(Some(Arc::new(RevmTxSimulator::new())), remote.clone());
+
+        let res = sut
+            .estimate_gas(tx_with_precomputed_access_list(None))
+            .await
+            .unwrap();
+
+        assert_eq!(remote.0.load(std::sync::atomic::Ordering::SeqCst), 0);
+        // 21_000 base + 2_400 per address + 1_900 per storage key
+        let intrinsic = Gas::from(21_000 + 2_400 + 1_900);
+        assert_eq!(*res.gas_breakdown().unwrap().intrinsic(), intrinsic);
+        assert_eq!(
+            res.gas_usage(),
+            &GasUsage::EstimateWithRange {
+                kind: TransactionKind::ContractCall {
+                    with_native_token_transfer: false
+                },
+                low: intrinsic,
+                high: Gas::from(30_000),
+                recommended: Gas::from(28_825),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_precomputed_access_list_is_rejected() {
+        let remote = Arc::new(CountingRemote::default());
+        let sut = Sut::with_dependencies(Some(Arc::new(RevmTxSimulator::new())), remote.clone());
+
+        let res = sut
+            .estimate_gas(tx_with_precomputed_access_list(Some("execution reverted")))
+            .await;
+
+        assert!(res.unwrap_err().is_invalid_access_list());
+        assert_eq!(remote.0.load(std::sync::atomic::Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn access_list_exceeding_max_entries_is_rejected() {
+        let remote = Arc::new(CountingRemote::default());
+        let sut = sut_with_config(
+            Arc::new(RevmTxSimulator::new()),
+            remote.clone(),
+            GastimatorConfigBuilder::default()
+                .max_access_list_entries(1usize)
+                .build()
+                .unwrap(),
+        );
+
+        let res = sut
+            .estimate_gas(tx_with_precomputed_access_list(None))
+            .await;
+
+        assert_eq!(res, Err(Error::AccessListTooLarge { entries: 2, max: 1 }));
+        assert_eq!(remote.0.load(std::sync::atomic::Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn gas_limit_exceeding_max_is_rejected() {
+        let remote = Arc::new(CountingRemote::default());
+        let sut = sut_with_config(
+            Arc::new(RevmTxSimulator::new()),
+            remote.clone(),
+            GastimatorConfigBuilder::default()
+                .max_allowed_gas_limit(Gas::from(30_000_000))
+                .build()
+                .unwrap(),
+        );
+        let tx = Transaction::sample_contract_creation_gas_limit(Gas::from(30_000_001));
+
+        let res = sut.estimate_gas(tx).await;
+
+        assert_eq!(
+            res,
+            Err(Error::GasLimitTooHigh {
+                gas_limit: Gas::from(30_000_001),
+                max: Gas::from(30_000_000)
+            })
+        );
+        assert_eq!(remote.0.load(std::sync::atomic::Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn batch_partial_failure_does_not_fail_batch() {
+        let sut = Sut::with_dependencies(
+            Some(LocalTxSimulatorHardCoded::new(Gas::from(30_000))),
+            RemoteHardcoded::new(Gas::from(40_000)),
+        );
+        let txs = vec![
+            Transaction::sample_contract_creation(),
+            Transaction::default(), // empty
+            Transaction::sample_native_token_transfer(),
+        ];
+
+        let res = sut
+            .estimate_gas_batch(txs, EstimateOptions::default())
+            .await;
+
+        assert_eq!(res.len(), 3);
+        assert_eq!(
+            res[0].as_ref().unwrap().gas_usage(),
+            &GasUsage::EstimateWithRange {
+                kind: TransactionKind::ContractCreation,
+                low: Gas::from(30_000),
+                high: Gas::from(40_000),
+                recommended: Gas::from(37_500),
+            }
+        );
+        assert_eq!(res[1], Err(Error::EmptyTransaction));
+        assert_eq!(
+            res[2].as_ref().unwrap().gas_usage(),
+            &GasUsage::Exact {
+                kind: TransactionKind::NativeTokenTransfer,
+                gas: Gas::from(21_000),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn replacement_with_higher_fees_has_equivalent_gas_usage() {
+        let sut = Sut::with_dependencies(
+            Some(LocalTxSimulatorHardCoded::new(Gas::from(30_000))),
+            RemoteHardcoded::new(Gas::from(40_000)),
+        );
+        let priced = |max_fee_per_gas: u64| {
+            PricedTransactionBuilder::default()
+                .tx(Transaction::sample_contract_creation())
+                .max_fee_per_gas(U256::from(max_fee_per_gas))
+                .build()
+                .unwrap()
+        };
+
+        let res = sut
+            .estimate_replacement(
+                priced(1_000_000_000),
+                priced(2_000_000_000),
+                EstimateOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        assert!(res.equivalent());
+        assert_eq!(res.original().gas_usage(), res.replacement().gas_usage());
+        assert_eq!(
+            *res.original().estimated_fee_wei(),
+            Some(U256::from(40_000_000_000_000u64))
+        );
+        assert_eq!(
+            *res.replacement().estimated_fee_wei(),
+            Some(U256::from(80_000_000_000_000u64))
+        );
+    }
+
+    #[tokio::test]
+    async fn inflated_gas_limit_is_warned_about() {
+        let sut = Sut::with_dependencies(
+            Some(LocalTxSimulatorHardCoded::new(Gas::from(30_000))),
+            RemoteHardcoded::new(Gas::from(40_000)),
+        );
+
+        let inflated = sut
+            .estimate_gas(Transaction::sample_contract_creation_gas_limit(Gas::from(
+                1_000_000,
+            )))
+            .await
+            .unwrap();
+        let reasonable = sut
+            .estimate_gas(Transaction::sample_contract_creation_gas_limit(Gas::from(
+                100_000,
+            )))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            inflated.warnings(),
+            &vec![EstimateWarning::GasLimitSuspiciouslyHigh {
+                gas_limit: Gas::from(1_000_000),
+                estimate: Gas::from(40_000),
+                factor: DEFAULT_GAS_LIMIT_WARNING_FACTOR,
+            }]
+        );
+        assert!(reasonable.warnings().is_empty());
+    }
+
+    #[tokio::test]
+    async fn metrics_count_estimates_and_sources() {
+        let sut = Sut::with_dependencies(
+            Some(LocalTxSimulatorHardCoded::new(Gas::from(30_000))),
+            FailRemote::new(),
+        );
+
+        sut.estimate_gas(Transaction::sample_contract_creation())
+            .await
+            .unwrap();
+        sut.estimate_gas(Transaction::sample_contract_creation_gas_limit(Gas::from(
+            1,
+        )))
+        .await
+        .unwrap_err();
+
+        assert_eq!(sut.metrics().estimate_counts(), (1, 1));
+        let rendered = sut.render_metrics();
+        assert!(rendered.contains("gastimator_local_simulations_total{outcome=\"ok\"} 1\n"));
+        assert!(rendered.contains("gastimator_remote_estimates_total{outcome=\"error\"} 1\n"));
+        assert!(rendered.contains("gastimator_estimate_source_total{source=\"local_only\"} 1\n"));
+
+        let stats = sut.metrics().estimator_stats();
+        assert_eq!(stats.local(), OutcomeCounts::from((1, 0)));
+        assert_eq!(stats.remote(), OutcomeCounts::from((0, 1)));
+        assert_eq!(stats.estimate_sources()["local_only"], 1);
+        assert_eq!(stats.estimate_sources()["both"], 0);
+    }
+
+    fn sut_with_remote_sample_rate(rate: f64, remote: Arc<CountingRemote>) -> Sut {
+        sut_with_config(
+            LocalTxSimulatorHardCoded::new(Gas::from(30_000)),
+            remote,
+            GastimatorConfigBuilder::default()
+                .remote_sample_rate(rate)
+                .build()
+                .unwrap(),
+        )
+    }
+
+    /// Distinct cacheable contract creations, i.e. not served from cache.
+    fn cacheable_contract_creations(n: u64) -> Vec<Transaction> {
+        (0..n)
+            .map(|nonce| {
+                TransactionBuilder::default()
+                    .nonce(nonce)
+                    .from(Address::from([0xab; 20]))
+                    .to(TxKind::Create)
+                    .input(Bytes::from([0xab; 10]))
+                    .build()
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn remote_sample_rate_zero_never_calls_remote() {
+        let remote = Arc::new(CountingRemote::default());
+        let sut = sut_with_remote_sample_rate(0.0, remote.clone());
+
+        for tx in cacheable_contract_creations(10) {
+            let res = sut.estimate_gas(tx).await.unwrap();
+            assert_eq!(
+                res.gas_usage(),
+                &GasUsage::Estimate {
+                    kind: TransactionKind::ContractCreation,
+                    gas: Gas::from(30_000),
+                }
+            );
+        }
+
+        assert_eq!(remote.0.load(std::sync::atomic::Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn remote_sample_rate_one_always_calls_remote() {
+        let remote = Arc::new(CountingRemote::default());
+        let sut = sut_with_remote_sample_rate(1.0, remote.clone());
+
+        for tx in cacheable_contract_creations(10) {
+            sut.estimate_gas(tx).await.unwrap();
+        }
+
+        assert_eq!(remote.0.load(std::sync::atomic::Ordering::SeqCst), 10);
+    }
+
+    #[tokio::test]
+    async fn remote_sample_rate_samples_fraction_of_cacheable() {
+        let remote = Arc::new(CountingRemote::default());
+        let sut = sut_with_remote_sample_rate(0.1, remote.clone());
+
+        for tx in cacheable_contract_creations(20) {
+            sut.estimate_gas(tx).await.unwrap();
+        }
+        // not cacheable, always calls remote
+        sut.estimate_gas(Transaction::sample_contract_creation())
+            .await
+            .unwrap();
+
+        assert_eq!(remote.0.load(std::sync::atomic::Ordering::SeqCst), 2 + 1);
+    }
+
+    #[tokio::test]
+    async fn remote_only_ignores_remote_sample_rate() {
+        let remote = Arc::new(CountingRemote::default());
+        let sut = sut_with_config(
+            LocalTxSimulatorHardCoded::new(Gas::from(30_000)),
+            remote.clone(),
+            GastimatorConfigBuilder::default()
+                .mode(GastimatorMode::RemoteOnly)
+                .remote_sample_rate(0.0)
+                .build()
+                .unwrap(),
+        );
+
+        for tx in cacheable_contract_creations(3) {
+            sut.estimate_gas(tx).await.unwrap();
+        }
+
+        assert_eq!(remote.0.load(std::sync::atomic::Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn precomputed_access_list_is_not_sampled() {
+        use alloy::eips::eip2930::{AccessList, AccessListResult};
+        let remote = Arc::new(CountingRemote::default());
+        let sut = sut_with_remote_sample_rate(0.5, remote.clone());
+        let tx = TransactionBuilder::default()
+            .nonce(0)
+            .from(Address::from([0xab; 20]))
+            .to(TxKind::Create)
+            .input(Bytes::from([0xab; 10]))
+            .precomputed_access_list(PrecomputedAccessList::new(AccessListResult {
+                access_list: AccessList::default(),
+                gas_used: U256::from(30_000),
+                error: None,
+            }))
+            .build()
+            .unwrap();
+
+        sut.estimate_gas(tx).await.unwrap();
+        // only these advance the sampling, one of the two is sampled
+        for tx in cacheable_contract_creations(2) {
+            sut.estimate_gas(tx).await.unwrap();
+        }
+
+        assert_eq!(remote.0.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(sut.state.remote_sample_counter.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn divergence_of_sampled_corrects_not_sampled() {
+        let remote = Arc::new(CountingRemote::default());
+        let sut = sut_with_remote_sample_rate(0.5, remote.clone());
+        let mut txs = cacheable_contract_creations(3).into_iter();
+        let mut estimate = async || {
+            sut.estimate_gas(txs.next().unwrap())
+                .await
+                .unwrap()
+                .gas_usage()
+                .max_gas()
+        };
+
+        // not sampled, nothing to correct by yet
+        assert_eq!(estimate().await, Gas::from(30_000));
+        // sampled, remote estimates 50_000
+        assert_eq!(estimate().await, Gas::from(50_000));
+        // not sampled, corrected towards the remote
+        let corrected = estimate().await;
+
+        assert_eq!(remote.0.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert!(corrected > Gas::from(30_000));
+        assert_eq!(
+            corrected,
+            sut.state.correction_factor.corrected(Gas::from(30_000))
+        );
+    }
+
+    #[tokio::test]
+    async fn local_mode_is_not_corrected() {
+        let remote = Arc::new(CountingRemote::default());
+        let sut = sut_with_remote_sample_rate(1.0, remote.clone());
+        let mut txs = cacheable_contract_creations(2).into_iter();
+        sut.estimate_gas(txs.next().unwrap()).await.unwrap();
+        let options = EstimateOptionsBuilder::default()
+            .mode(EstimateMode::Local)
+            .build()
+            .unwrap();
+
+        let res = sut
+            .estimate_gas_with_options(txs.next().unwrap(), options)
+            .await
+            .unwrap();
+
+        assert!(sut.state.correction_factor.get() > 1.0);
+        assert_eq!(res.gas_usage().max_gas(), Gas::from(30_000));
+    }
+
+    #[tokio::test]
+    async fn not_sampled_is_not_a_remote_failure() {
+        let remote = Arc::new(CountingRemote::default());
+        let sut = sut_with_remote_sample_rate(0.0, remote.clone());
+
+        for tx in cacheable_contract_creations(3) {
+            sut.estimate_gas(tx).await.unwrap();
+        }
+
+        let stats = sut.metrics().estimator_stats();
+        assert_eq!(stats.remote(), OutcomeCounts::from((0, 0)));
+        assert_eq!(stats.estimate_sources()["local_only"], 3);
+    }
+
+    #[tokio::test]
+    async fn expired_cache_entry_is_recomputed() {
+        let remote = Arc::new(CountingRemote::default());
+        let sut = sut_with_config(
+            LocalTxSimulatorHardCoded::new(Gas::from(30_000)),
+            remote.clone(),
+            GastimatorConfigBuilder::default()
+                .cache_ttl(Duration::ZERO)
+                .build()
+                .unwrap(),
+        );
+        let tx = cacheable_contract_creations(1).remove(0);
+
+        sut.estimate_gas(tx.clone()).await.unwrap();
+        sut.estimate_gas(tx).await.unwrap();
+
+        assert_eq!(remote.0.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn idempotent_response_is_stored_by_key() {
+        let sut = Sut::with_dependencies(
+            Some(LocalTxSimulatorHardCoded::new(Gas::from(30_000))),
+            RemoteHardcoded::new(Gas::from(30_000)),
+        );
+        let response = sut
+            .estimate_gas(Transaction::sample_contract_creation())
+            .await
+            .unwrap();
+
+        let key = IdempotencyKey::new("/tx", "key");
+        let hash = B256::repeat_byte(1);
+
+        sut.store_idempotent_response(key.clone(), hash, response.clone());
+
+        assert_eq!(
+            sut.idempotent_response(&key, hash),
+            Ok(Some(IdempotentResponse::Estimate(response)))
+        );
+        assert_eq!(
+            sut.idempotent_response(&IdempotencyKey::new("/tx", "other"), hash),
+            Ok(None)
+        );
+        assert_eq!(
+            sut.idempotent_response(&IdempotencyKey::new("/rlp", "key"), hash),
+            Ok(None)
+        );
+        assert_eq!(
+            sut.idempotent_response(&key, B256::repeat_byte(2)),
+            Err(Error::IdempotencyKeyReused {
+                key: "key".to_owned()
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn idempotent_response_not_stored_with_zero_ttl() {
+        let sut = sut_with_config(
+            LocalTxSimulatorHardCoded::new(Gas::from(30_000)),
+            RemoteHardcoded::new(Gas::from(30_000)),
+            GastimatorConfigBuilder::default()
+                .idempotency_ttl(Duration::ZERO)
+                .build()
+                .unwrap(),
+        );
+        let response = sut
+            .estimate_gas(Transaction::sample_contract_creation())
+            .await
+            .unwrap();
+
+        let key = IdempotencyKey::new("/tx", "key");
+        let hash = B256::repeat_byte(1);
+
+        sut.store_idempotent_response(key.clone(), hash, response);
+
+        assert_eq!(sut.idempotent_response(&key, hash), Ok(None));
+    }
+
+    #[tokio::test]
+    async fn estimate_with_state_diff_of_native_transfer() {
+        let sender = Address::new([0xaa; 20]);
+        let recipient = Address::new([0xbb; 20]);
+        let value = U256::from(1_000);
+        let sut = Sut::with_dependencies(Some(Arc::new(RevmTxSimulator::new())), FailRemote::new());
+        let tx = TransactionBuilder::default()
+            .from(sender)
+            .to(recipient)
+            .value(value)
+            .build()
+            .unwrap();
+
+        let (response, state_diff) = sut.estimate_with_state_diff(tx).await.unwrap();
+
+        assert_eq!(
+            *response.gas_usage(),
+            GasUsage::Exact {
+                kind: TransactionKind::NativeTokenTransfer,
+                gas: Gas::exact_native_token_transfer()
+            }
+        );
+        assert_eq!(
+            *state_diff.account(&sender).unwrap().nonce(),
+            Change::new(0, 1)
+        );
+        assert_eq!(
+            *state_diff.account(&recipient).unwrap().balance(),
+            Change::new(U256::ZERO, value)
+        );
+    }
+
+    #[tokio::test]
+    async fn simulate_call_returns_output() {
+        let contract = Address::new([0xcc; 20]);
+        let sut = Sut::with_dependencies(Some(Arc::new(RevmTxSimulator::new())), FailRemote::new());
+        let tx = TransactionBuilder::default()
+            .to(contract)
+            .state_override(StateOverride::new([(
+                contract,
+                AccountOverrideBuilder::default()
+                    // PUSH1 42 PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+                    .code(Bytes::from([
+                        0x60, 0x2a, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3,
+                    ]))
+                    .build()
+                    .unwrap(),
+            )]))
+            .build()
+            .unwrap();
+
+        let output = sut.simulate_call(tx).await.unwrap();
+
+        assert_eq!(
+            *output.output(),
+            Bytes::from(U256::from(42).to_be_bytes::<32>())
+        );
+        assert!(output.gas_used() > Gas::exact_native_token_transfer());
+    }
+
+    #[tokio::test]
+    async fn simulate_call_is_not_supported_by_hard_coded_simulator() {
+        let sut = Sut::with_dependencies(
+            Some(LocalTxSimulatorHardCoded::new(Gas::from(30_000))),
+            FailRemote::new(),
+        );
+        let tx = TransactionBuilder::default()
+            .to(Address::new([0xcc; 20]))
+            .build()
+            .unwrap();
+
+        let res = sut.simulate_call(tx).await;
+
+        assert!(res.unwrap_err().is_local_simulation_failed());
+    }
+
+    #[tokio::test]
+    async fn simulate_bundle_sums_gas() {
+        let sut = Sut::with_dependencies(Some(Arc::new(RevmTxSimulator::new())), FailRemote::new());
+        let tx = Transaction::sample_native_token_transfer();
+
+        let response = sut.simulate_bundle(vec![tx.clone(), tx]).await.unwrap();
+
+        assert_eq!(
+            response.gas_used(),
+            &vec![Gas::exact_native_token_transfer(); 2]
+        );
+        assert_eq!(response.total_gas(), Gas::from(42_000));
+    }
+
+    #[tokio::test]
+    async fn simulate_empty_bundle_is_invalid() {
+        let sut = Sut::with_dependencies(Some(Arc::new(RevmTxSimulator::new())), FailRemote::new());
+        let res = sut.simulate_bundle(Vec::new()).await;
+        assert!(res.unwrap_err().is_invalid_transaction());
+    }
+
+    struct VerySlowRemote;
+    #[async_trait::async_trait]
+    impl RemoteGasEstimator for VerySlowRemote {
+        async fn estimate_gas(&self, _: &Transaction) -> Result<Gas> {
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            Ok(Gas::from(50_000))
+        }
+    }
+
+    fn sut_with_total_budget(
+        local: Arc<dyn LocalTxSimulator + Send + Sync>,
+        budget: Duration,
+    ) -> Sut {
+        sut_with_config(
+            local,
+            Arc::new(VerySlowRemote),
+            GastimatorConfigBuilder::default()
+                .total_budget(budget)
+                .build()
+                .unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn total_budget_exhausted_returns_local_estimate() {
+        let budget = Duration::from_millis(50);
+        let sut = sut_with_total_budget(LocalTxSimulatorHardCoded::new(Gas::from(30_000)), budget);
+        let start = Instant::now();
+
+        let response = sut
+            .estimate_gas(Transaction::sample_contract_creation())
+            .await
+            .unwrap();
+
+        assert!(start.elapsed() < budget * 10);
+        assert_eq!(
+            *response.gas_usage(),
+            GasUsage::Estimate {
+                kind: TransactionKind::ContractCreation,
+                gas: Gas::from(30_000)
+            }
+        );
+    }
+
+    /// Remote which is very slow the first time it is called, fast after.
+    #[derive(Default)]
+    struct SlowFirstRemote(std::sync::atomic::AtomicUsize);
+    #[async_trait::async_trait]
+    impl RemoteGasEstimator for SlowFirstRemote {
+        async fn estimate_gas(&self, _: &Transaction) -> Result<Gas> {
+            if self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0 {
+                tokio::time::sleep(Duration::from_secs(10)).await;
+            }
+            Ok(Gas::from(60_000))
+        }
+    }
+
+    #[tokio::test]
+    async fn single_flight_does_not_share_missed_deadline() {
+        let sut = sut_with_config(
+            LocalTxSimulatorHardCoded::new(Gas::from(40_000)),
+            Arc::new(SlowFirstRemote::default()),
+            GastimatorConfigBuilder::default()
+                .total_budget(Duration::from_millis(100))
+                .build()
+                .unwrap(),
+        );
+        let tx = Transaction::sample_contract_creation();
+
+        let (first, second) = tokio::join!(sut.estimate_gas(tx.clone()), async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            sut.estimate_gas(tx.clone()).await
+        });
+
+        // The first missed the remote estimate, the second computed anew.
+        assert_eq!(first.unwrap().gas_usage().max_gas(), Gas::from(40_000));
+        assert_eq!(second.unwrap().gas_usage().max_gas(), Gas::from(60_000));
+    }
+
+    #[tokio::test]
+    async fn local_mode_local_timeout_is_timeout_of_local_phase() {
+        let sut = sut_with_total_budget(
+            Arc::new(ConcurrencyTrackingLocal::default()),
+            Duration::from_millis(5),
+        );
+        let options = EstimateOptionsBuilder::default()
+            .mode(EstimateMode::Local)
+            .build()
+            .unwrap();
+
+        let result = sut
+            .estimate_gas_with_options(Transaction::sample_contract_creation(), options)
+            .await;
+
+        assert_eq!(
+            result,
+            Err(Error::Timeout {
+                phase: EstimatePhase::Local
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn total_budget_exhausted_without_local_estimate_fails() {
+        let sut = sut_with_total_budget(FailLocal::new(), Duration::from_millis(50));
+
+        let result = sut
+            .estimate_gas(Transaction::sample_contract_creation())
+            .await;
+
+        assert_eq!(result, Err(Error::BudgetExhausted { budget_millis: 50 }));
+    }
+
+    #[tokio::test]
+    async fn native_transfer_to_overridden_code_is_simulated() {
+        let recipient = Address::new([0xbb; 20]);
+        let remote = Arc::new(CountingRemote::default());
+        let sut = Sut::with_dependencies(Some(Arc::new(RevmTxSimulator::new())), remote.clone());
+        let tx = TransactionBuilder::default()
+            .to(recipient)
+            .value(U256::from(1_000))
+            .state_override(StateOverride::new([(
+                recipient,
+                AccountOverrideBuilder::default()
+                    // PUSH1 0 PUSH1 0 LOG0
+                    .code(Bytes::from([0x60, 0x00, 0x60, 0x00, 0xa0]))
+                    .build()
+                    .unwrap(),
+            )]))
+            .build()
+            .unwrap();
+
+        sut.estimate_gas(tx).await.unwrap();
+
+        assert_eq!(remote.0.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn cache_hit_reports_original_compute_time() {
+        let sut = Sut::with_dependencies(
+            Some(LocalTxSimulatorHardCoded::new(Gas::from(30_000))),
+            Arc::new(SlowRemote(Gas::from(40_000))),
+        );
+        let tx = cacheable_contract_creations(1).remove(0);
+
+        let computed = sut.estimate_gas(tx.clone()).await.unwrap();
+        assert_eq!(*computed.original_compute_millis(), None);
+        assert!(*computed.time_elapsed_in_millis() >= 20);
+
+        let cached = sut.estimate_gas(tx).await.unwrap();
+        assert_eq!(
+            *cached.original_compute_millis(),
+            Some(*computed.time_elapsed_in_millis())
+        );
+        assert!(*cached.time_elapsed_in_millis() < *computed.time_elapsed_in_millis());
+    }
+
+    #[tokio::test]
+    async fn source_of_each_branch() {
+        let source_of =
+            async |sut: Sut, tx: Transaction| sut.estimate_gas(tx).await.unwrap().source();
+        let tx = Transaction::sample_contract_creation();
+
+        assert_eq!(
+            source_of(
+                Sut::with_dependencies(
+                    Some(FailLocal::new()),
+                    RemoteHardcoded::new(Gas::from(40_000))
+                ),
+                tx.clone()
+            )
+            .await,
+            EstimateSource::RemoteOnly
+        );
+        assert_eq!(
+            source_of(
+                Sut::with_dependencies(
+                    Some(LocalTxSimulatorHardCoded::new(Gas::from(30_000))),
+                    FailRemote::new()
+                ),
+                tx
+            )
+            .await,
+            EstimateSource::LocalOnly
+        );
+        assert_eq!(
+            source_of(
+                Sut::with_dependencies(Some(FailLocal::new()), FailRemote::new()),
+                Transaction::sample_native_token_transfer()
+            )
+            .await,
+            EstimateSource::ExactRule
+        );
+
+        let sut = Sut::with_dependencies(
+            Some(LocalTxSimulatorHardCoded::new(Gas::from(30_000))),
+            RemoteHardcoded::new(Gas::from(40_000)),
+        );
+        let tx = cacheable_contract_creations(1).remove(0);
+        let computed = sut.estimate_gas(tx.clone()).await.unwrap();
+        assert_eq!(computed.source(), EstimateSource::Both);
+        assert!(!computed.from_cache());
+        let cached = sut.estimate_gas(tx).await.unwrap();
+        assert_eq!(cached.source(), EstimateSource::Cached);
+        assert!(cached.from_cache());
+    }
+
+    #[tokio::test]
+    async fn estimated_fee_uses_high_bound_of_range() {
+        let sut = Sut::with_dependencies(
+            Some(LocalTxSimulatorHardCoded::new(Gas::from(30_000))),
+            RemoteHardcoded::new(Gas::from(40_000)),
+        );
+        let max_fee_per_gas = U256::from(30_000_000_000u64);
+        let options = EstimateOptionsBuilder::default()
+            .max_fee_per_gas(max_fee_per_gas)
+            .build()
+            .unwrap();
+
+        let response = sut
+            .estimate_gas_with_options(Transaction::sample_contract_creation(), options)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *response.estimated_fee_wei(),
+            Some(U256::from(40_000) * max_fee_per_gas)
+        );
+        let response = sut
+            .estimate_gas(Transaction::sample_contract_creation())
+            .await
+            .unwrap();
+        assert_eq!(*response.estimated_fee_wei(), None);
+    }
+
+    /// Calls the precompile at `address` with `input`.
+    fn call_precompile(address: u8, input: Bytes) -> Transaction {
+        TransactionBuilder::default()
+            .to(Address::with_last_byte(address))
+            .input(input)
+            .build()
+            .unwrap()
+    }
+
+    async fn assert_exact_precompile_gas(tx: Transaction, spec: SpecId, expected: Gas) {
+        let remote = Arc::new(CountingRemote::default());
+        let local = Arc::new(RevmTxSimulator::builder().spec(spec).build());
+        let sut = Sut::with_dependencies(Some(local.clone()), remote.clone());
+
+        let response = sut.estimate_gas(tx.clone()).await.unwrap();
+
+        assert_eq!(
+            *response.gas_usage(),
+            GasUsage::Exact {
+                kind: tx.kind(),
+                gas: expected
+            }
+        );
+        assert_eq!(remote.0.load(std::sync::atomic::Ordering::SeqCst), 0);
+        // formula agrees with simulation
+        assert_eq!(local.locally_simulate_tx(&tx).unwrap(), expected);
+    }
+
+    #[tokio::test]
+    async fn identity_precompile_is_exact() {
+        let input = Bytes::from([0xab; 100]);
+        // 21_000 base + 100 non zero bytes of calldata + 15 + 3 per word
+        let expected = Gas::from(21_000 + 100 * 16 + 15 + 3 * 4);
+        let tx = call_precompile(0x04, input);
+        assert_exact_precompile_gas(tx, SpecId::CANCUN, expected).await;
+    }
+
+    #[tokio::test]
+    async fn sha256_precompile_is_exact() {
+        let input = Bytes::from([0xab; 64]);
+        // 21_000 base + 64 non zero bytes of calldata + 60 + 12 per word
+        let expected = Gas::from(21_000 + 64 * 16 + 60 + 12 * 2);
+        let tx = call_precompile(0x02, input);
+        assert_exact_precompile_gas(tx, SpecId::CANCUN, expected).await;
+    }
+
+    #[tokio::test]
+    async fn sha256_precompile_is_exact_with_calldata_floor() {
+        let input = Bytes::from([0xab; 64]);
+        // EIP-7623 floor: 21_000 + 10 per token, 4 tokens per non zero byte
+        let expected = Gas::from(21_000 + 64 * 4 * 10);
+        let tx = call_precompile(0x02, input);
+        assert_exact_precompile_gas(tx, SpecId::PRAGUE, expected).await;
+    }
+
+    #[tokio::test]
+    async fn precompile_call_exceeding_gas_limit_fails() {
+        let sut = Sut::with_dependencies(Some(Arc::new(RevmTxSimulator::new())), FailRemote::new());
+        let mut tx = call_precompile(0x01, Bytes::new());
+        tx.set_gas_limit(Some(Gas::from(21_000)));
+
+        let res = sut.estimate_gas(tx).await;
+
+        assert_eq!(
+            res,
+            Err(Error::GasExceedsLimit {
+                estimated_cost: Some(Gas::from(24_000)),
+                gas_limit: Gas::from(21_000)
+            })
+        );
+    }
 }
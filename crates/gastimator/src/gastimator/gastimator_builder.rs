@@ -0,0 +1,202 @@
+use crate::prelude::*;
+
+impl Gastimator {
+    /// A builder of a `Gastimator` with custom local and remote gas
+    /// estimators and config, e.g. for embedding it in a service without the
+    /// REST server.
+    pub fn builder() -> GastimatorBuilder {
+        GastimatorBuilder::default()
+    }
+}
+
+/// Builder of a [`Gastimator`], defaults to simulating Ethereum mainnet
+/// transactions locally with [`RevmTxSimulator`], without any remote gas
+/// estimator, i.e. local only, and the default [`GastimatorConfig`].
+pub struct GastimatorBuilder {
+    local_gas_estimator: Arc<dyn LocalTxSimulator + Send + Sync>,
+    remote_gas_estimator: Option<Arc<dyn RemoteGasEstimator + Send + Sync>>,
+    transaction_source: Option<Arc<dyn TransactionSource + Send + Sync>>,
+    config: GastimatorConfig,
+}
+
+impl Default for GastimatorBuilder {
+    fn default() -> Self {
+        Self {
+            local_gas_estimator: Arc::new(RevmTxSimulator::new()),
+            remote_gas_estimator: None,
+            transaction_source: None,
+            config: GastimatorConfig::default(),
+        }
+    }
+}
+
+impl GastimatorBuilder {
+    /// The local gas estimator, e.g. a [`RevmTxSimulator`] built for another
+    /// chain or a custom implementation of [`LocalTxSimulator`].
+    pub fn local_gas_estimator(
+        mut self,
+        local_gas_estimator: impl LocalTxSimulator + Send + Sync + 'static,
+    ) -> Self {
+        self.local_gas_estimator = Arc::new(local_gas_estimator);
+        self
+    }
+
+    /// The remote gas estimator, e.g. an [`AlchemyRpcClient`], a
+    /// [`GenericRpcClient`] or a custom implementation of
+    /// [`RemoteGasEstimator`].
+    pub fn remote_gas_estimator(
+        mut self,
+        remote_gas_estimator: impl RemoteGasEstimator + Send + Sync + 'static,
+    ) -> Self {
+        self.remote_gas_estimator = Some(Arc::new(remote_gas_estimator));
+        self
+    }
+
+    /// The source of mined transactions, to estimate transactions by hash,
+    /// e.g. an [`AlchemyRpcClient`] or a [`GenericRpcClient`].
+    pub fn transaction_source(
+        mut self,
+        transaction_source: impl TransactionSource + Send + Sync + 'static,
+    ) -> Self {
+        self.transaction_source = Some(Arc::new(transaction_source));
+        self
+    }
+
+    /// Uses the Alchemy API of Ethereum mainnet with `api_key` as remote gas
+    /// estimator and source of mined transactions.
+    pub fn alchemy_api_key(mut self, api_key: impl AsRef<str>) -> Self {
+        let alchemy = Arc::new(AlchemyRpcClient::new(api_key));
+        self.remote_gas_estimator = Some(alchemy.clone());
+        self.transaction_source = Some(alchemy);
+        self
+    }
+
+    /// How estimates are orchestrated, see [`GastimatorConfig`].
+    pub fn config(mut self, config: GastimatorConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Constructs the `Gastimator`, local only if no remote gas estimator
+    /// was set.
+    pub fn build(self) -> Gastimator {
+        let (remote_gas_estimator, config) = match self.remote_gas_estimator {
+            Some(remote_gas_estimator) => (remote_gas_estimator, self.config),
+            None => {
+                let remote_gas_estimator: Arc<dyn RemoteGasEstimator + Send + Sync> =
+                    Arc::new(NoRemoteGasEstimator);
+                (
+                    remote_gas_estimator,
+                    self.config.with_mode(GastimatorMode::LocalOnly),
+                )
+            }
+        };
+        let dependencies = DependenciesBuilder::default()
+            .local_gas_estimator(self.local_gas_estimator)
+            .remote_gas_estimator(remote_gas_estimator)
+            .transaction_source(self.transaction_source)
+            .build()
+            .unwrap();
+        Gastimator::with_config(Arc::new(dependencies), config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedRemote(Gas);
+
+    #[async_trait::async_trait]
+    impl RemoteGasEstimator for FixedRemote {
+        async fn estimate_gas(&self, _: &Transaction) -> Result<Gas> {
+            Ok(self.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn without_remote_is_local_only() {
+        let sut = Gastimator::builder().build();
+
+        let response = sut
+            .estimate_gas(Transaction::sample_contract_creation())
+            .await
+            .unwrap();
+
+        assert!(response.gas_usage().is_estimate());
+        sut.warm_up().await;
+        assert!(sut.is_ready());
+    }
+
+    #[tokio::test]
+    async fn with_remote_estimates_range() {
+        let sut = Gastimator::builder()
+            .remote_gas_estimator(FixedRemote(Gas::from(200_000)))
+            .config(
+                GastimatorConfigBuilder::default()
+                    .remote_weight(1.0)
+                    .build()
+                    .unwrap(),
+            )
+            .build();
+
+        let response = sut
+            .estimate_gas(Transaction::sample_contract_creation())
+            .await
+            .unwrap();
+
+        let GasUsage::EstimateWithRange { recommended, .. } = response.gas_usage() else {
+            panic!("Expected a range estimate");
+        };
+        assert_eq!(*recommended, Gas::from(200_000));
+    }
+
+    struct FixedTransactions(Transaction, Gas);
+
+    #[async_trait::async_trait]
+    impl TransactionSource for FixedTransactions {
+        async fn transaction_by_hash(&self, _: B256) -> Result<Transaction> {
+            Ok(self.0.clone())
+        }
+
+        async fn gas_used_by(&self, _: B256) -> Result<Gas> {
+            Ok(self.1)
+        }
+    }
+
+    #[tokio::test]
+    async fn transaction_by_hash_requires_source() {
+        let hash = B256::repeat_byte(0xab);
+        let tx = Transaction::sample_contract_creation_cachable();
+
+        let without = Gastimator::builder().build();
+        assert_eq!(
+            without.transaction_by_hash(hash).await,
+            Err(Error::NoTransactionSource)
+        );
+
+        let sut = Gastimator::builder()
+            .transaction_source(FixedTransactions(tx.clone(), Gas::from(21_000)))
+            .build();
+        assert_eq!(sut.transaction_by_hash(hash).await, Ok(tx));
+    }
+
+    #[tokio::test]
+    async fn accuracy_compares_against_actual_gas() {
+        let sut = Gastimator::builder()
+            .transaction_source(FixedTransactions(
+                Transaction::sample_native_token_transfer_cachable(),
+                Gas::from(20_000),
+            ))
+            .build();
+
+        let response = sut
+            .accuracy(B256::repeat_byte(0xab), EstimateOptions::default())
+            .await
+            .unwrap();
+
+        assert_eq!(response.actual_gas(), Gas::from(20_000));
+        assert_eq!(response.delta(), 1_000);
+        assert_eq!(response.estimate().source(), EstimateSource::ExactRule);
+    }
+}
@@ -1,6 +1,8 @@
 mod dependencies;
 #[allow(clippy::module_inception)]
 mod gastimator;
+mod gastimator_builder;
 
 pub use dependencies::*;
 pub use gastimator::*;
+pub use gastimator_builder::*;
@@ -1,22 +1,32 @@
 mod alchemy_rpc;
 mod app_state;
 mod decode_rlp;
+mod forking_tx_simulator;
 mod gastimator;
 mod local_gas_estimator;
+mod metrics;
 mod models;
+#[cfg(feature = "otel")]
+mod otel_exporter;
 mod remote_gas_estimator;
 mod traits;
+mod work_queue;
 
 pub mod prelude {
     // INTERNAL MODULES
     pub use crate::alchemy_rpc::*;
     pub use crate::app_state::*;
     pub(crate) use crate::decode_rlp::*;
+    pub(crate) use crate::forking_tx_simulator::*;
     pub use crate::gastimator::*;
-    pub(crate) use crate::local_gas_estimator::*;
+    pub use crate::local_gas_estimator::*;
+    pub use crate::metrics::*;
     pub use crate::models::*;
-    pub(crate) use crate::remote_gas_estimator::*;
+    #[cfg(feature = "otel")]
+    pub use crate::otel_exporter::*;
+    pub use crate::remote_gas_estimator::*;
     pub use crate::traits::*;
+    pub use crate::work_queue::*;
 
     // STD
     pub use std::{
@@ -25,19 +35,21 @@ pub mod prelude {
         collections::HashMap,
         net::SocketAddr,
         sync::{Arc, RwLock},
-        time::Instant,
+        time::{Duration, Instant},
     };
 
     // EXTERNAL CRATES
-    pub use alloy_consensus::TxEip1559;
+    pub use alloy::eips::BlockNumberOrTag;
+    pub use alloy_consensus::{TxEip1559, TxEip4844};
     pub use alloy_primitives::TxKind;
-    pub use alloy_primitives::{Address, Bytes, U256};
+    pub use alloy_primitives::{Address, B256, Bytes, U256};
     pub use derive_builder::Builder;
     pub use derive_more::{Deref, DerefMut};
     pub use getset::Setters;
     pub use getset::{CopyGetters, Getters};
     pub use log::{debug, error, info, warn};
     pub use reqwest::Client;
+    pub use revm::primitives::hardfork::SpecId;
     pub use serde::{Deserialize, Serialize};
     pub use thiserror::Error as ThisError;
 }
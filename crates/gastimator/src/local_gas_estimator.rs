@@ -1,44 +1,105 @@
 use std::convert::Infallible;
 
 use crate::prelude::*;
+use alloy::sol_types::{Revert, SolError, decode_revert_reason};
 use revm::{
-    Context, ExecuteEvm, MainBuilder, MainContext,
+    Context, DatabaseCommit, ExecuteEvm, MainBuilder, MainContext,
     context::{
-        BlockEnv, CfgEnv, Evm, TxEnv,
-        result::{EVMError, InvalidTransaction, ResultAndState},
+        BlockEnv, CfgEnv, Evm, TransactionType, TxEnv,
+        result::{EVMError, ExecutionResult, HaltReason, InvalidTransaction, ResultAndState},
     },
-    database::{CacheDB, EmptyDB, EmptyDBTyped},
+    context_interface::Block,
+    database::{CacheDB, Database, DatabaseRef, EmptyDB, EmptyDBTyped},
     handler::{EthPrecompiles, instructions::EthInstructions},
-    interpreter::interpreter::EthInterpreter,
+    interpreter::{
+        gas::{InitialAndFloorGas, calculate_initial_tx_gas_for_tx},
+        interpreter::EthInterpreter,
+    },
+    primitives::hardfork::SpecId,
+    state::{Bytecode, EvmState},
 };
 
-/// A typealias for the type of the EVM we are using.
+/// A typealias for the type of the EVM we are using, by default backed by
+/// an in-memory database.
 #[allow(clippy::upper_case_acronyms)]
-type EVM = Evm<
-    Context<BlockEnv, TxEnv, CfgEnv, CacheDB<EmptyDBTyped<Infallible>>>,
+pub(crate) type EVM<DB = CacheDB<EmptyDBTyped<Infallible>>> = Evm<
+    Context<BlockEnv, TxEnv, CfgEnv, DB>,
     (),
-    EthInstructions<
-        EthInterpreter,
-        Context<BlockEnv, TxEnv, CfgEnv, CacheDB<EmptyDBTyped<Infallible>>>,
-    >,
+    EthInstructions<EthInterpreter, Context<BlockEnv, TxEnv, CfgEnv, DB>>,
     EthPrecompiles,
 >;
 
 /// An EVM transaction simulator that can be used to simulate transactions locally.
 /// It uses the `revm` crate to simulate the transaction and returns the gas used.
+///
+/// Simulations run in parallel, each checks out an EVM from a pool, which
+/// grows to the number of concurrent simulations, and returns it after.
 pub struct RevmTxSimulator {
-    evm: RwLock<EVM>,
+    /// The configuration of the EVMs of the pool.
+    config: RevmTxSimulatorBuilder,
+
+    /// EVMs not currently used by any simulation.
+    pool: std::sync::Mutex<Vec<EVM>>,
 }
 
 /// A simulator of transaction that happens locally.
 /// It is used to simulate transactions locally and returns the gas used.
 pub trait LocalTxSimulator {
     fn locally_simulate_tx(&self, tx: &Transaction) -> Result<Gas>;
+
+    /// Simulates `tx` and also returns a breakdown of the gas used, if
+    /// available.
+    ///
+    /// Default implementation does not have access to any breakdown and
+    /// returns `None` for it.
+    fn locally_simulate_tx_with_breakdown(
+        &self,
+        tx: &Transaction,
+    ) -> Result<(Gas, Option<GasBreakdown>)> {
+        self.locally_simulate_tx(tx).map(|gas| (gas, None))
+    }
+
+    /// Simulates `tx` and returns the changes to the state of all accounts
+    /// changed by it.
+    ///
+    /// Default implementation does not have access to the state and fails.
+    fn locally_simulate_tx_with_state_diff(&self, _tx: &Transaction) -> Result<StateDiff> {
+        Err(Error::local_simulation_failed("State diff not supported"))
+    }
+
+    /// Simulates `tx` as a read-only call, i.e. like `eth_call`, returning
+    /// the data returned by it and the gas used.
+    ///
+    /// Default implementation does not have access to the output and fails.
+    fn simulate_call(&self, _tx: &Transaction) -> Result<CallOutput> {
+        Err(Error::local_simulation_failed("Call not supported"))
+    }
+
+    /// Simulates `txs` in order against shared state, so that changes made by
+    /// a transaction are seen by the ones after it, returning the gas used by
+    /// each transaction.
+    ///
+    /// Default implementation does not have access to the state and fails.
+    fn simulate_bundle(&self, _txs: &[Transaction]) -> Result<Vec<Gas>> {
+        Err(Error::local_simulation_failed("Bundle not supported"))
+    }
+
+    /// The blob base fee (in wei per blob gas) used when simulating blob
+    /// carrying transactions, `None` if unknown.
+    fn blob_base_fee(&self) -> Option<u128> {
+        None
+    }
+
+    /// The spec (hardfork) transactions are simulated with, `None` if
+    /// unknown.
+    fn spec(&self) -> Option<SpecId> {
+        None
+    }
 }
 
 impl From<Transaction> for TxEnv {
     fn from(tx: Transaction) -> Self {
-        TxEnv {
+        let tx_env = TxEnv {
             nonce: tx.nonce().unwrap_or_default(),
             caller: tx.from().unwrap_or_default(),
             kind: *tx.to(),
@@ -46,6 +107,25 @@ impl From<Transaction> for TxEnv {
             gas_limit: *tx.gas_limit_else_max(),
             value: *tx.value(),
             ..Default::default()
+        };
+        let tx_env = match tx.precomputed_access_list() {
+            Some(precomputed) => TxEnv {
+                tx_type: TransactionType::Eip2930 as u8,
+                access_list: precomputed.access_list().clone(),
+                ..tx_env
+            },
+            None => tx_env,
+        };
+        if !tx.is_blob_carrying() {
+            return tx_env;
+        }
+        TxEnv {
+            tx_type: TransactionType::Eip4844 as u8,
+            blob_hashes: tx.blob_versioned_hashes().clone(),
+            // We estimate gas, not fees, so if no max fee per blob gas is
+            // specified we must not fail because blob gas price is too high.
+            max_fee_per_blob_gas: tx.max_fee_per_blob_gas().unwrap_or(u128::MAX),
+            ..tx_env
         }
     }
 }
@@ -57,38 +137,336 @@ impl RevmTxSimulator {
     /// Constructs an Evm instance using an in-memory database, simulating
     /// Ethereum mainnet transactions.
     pub fn new() -> Self {
+        Self::builder().build()
+    }
+
+    /// A builder of a simulator, allowing to configure chain, spec (hardfork)
+    /// and blob gas of the simulated block.
+    pub fn builder() -> RevmTxSimulatorBuilder {
+        RevmTxSimulatorBuilder::default()
+    }
+}
+
+impl Default for RevmTxSimulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Builder of a [`RevmTxSimulator`], defaults to simulating Ethereum mainnet
+/// transactions using the spec of [`spec_for_chain`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RevmTxSimulatorBuilder {
+    chain_id: u64,
+    spec: Option<SpecId>,
+    excess_blob_gas: u64,
+}
+
+impl Default for RevmTxSimulatorBuilder {
+    fn default() -> Self {
+        Self {
+            chain_id: MAINNET_CHAIN_ID,
+            spec: None,
+            excess_blob_gas: 0,
+        }
+    }
+}
+
+impl RevmTxSimulatorBuilder {
+    /// The id of the chain to simulate transactions on.
+    pub fn chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = chain_id;
+        self
+    }
+
+    /// The spec (hardfork) to simulate transactions with, overriding the
+    /// spec of the chain, see [`spec_for_chain`]. Intrinsic gas and opcode
+    /// costs depend on the spec, so this is useful to reproduce estimates
+    /// of older blocks or to try out upcoming forks.
+    pub fn spec(mut self, spec: impl Into<Option<SpecId>>) -> Self {
+        self.spec = spec.into();
+        self
+    }
+
+    /// The excess blob gas of the simulated block, from which the blob base
+    /// fee is derived, [see EIP-4844][eip].
+    ///
+    /// [eip]: https://eips.ethereum.org/EIPS/eip-4844#gas-accounting
+    pub fn excess_blob_gas(mut self, excess_blob_gas: u64) -> Self {
+        self.excess_blob_gas = excess_blob_gas;
+        self
+    }
+
+    /// Constructs a simulator of EVM instances using an in-memory database.
+    pub fn build(self) -> RevmTxSimulator {
+        RevmTxSimulator {
+            config: self,
+            pool: Default::default(),
+        }
+    }
+
+    /// The configured spec, else the spec of the chain.
+    fn resolved_spec(&self) -> SpecId {
+        self.spec.unwrap_or_else(|| spec_for_chain(self.chain_id))
+    }
+
+    /// Constructs an Evm instance using an in-memory database.
+    fn build_evm(&self) -> EVM {
+        let chain_id = self.chain_id;
+        let spec = self.resolved_spec();
+
         // Initialise empty in-memory-db
         let cache_db = CacheDB::new(EmptyDB::default());
 
         // Initialise an empty (default) EVM
-        let evm = Context::mainnet()
+        let context = Context::mainnet()
             .with_db(cache_db)
-            .modify_cfg_chained(|cfg| {
-                // Disable nonce checks, since we might not be providing nonces
-                cfg.disable_nonce_check = true;
-                // Disable balance checks, since we do not wanna have to have balance
-                // to run simulation
-                cfg.disable_balance_check = true; // requires feature flag "optional_balance_check"
+            .modify_cfg_chained(|cfg| configure_cfg(cfg, chain_id, spec));
+        let is_prague = spec.is_enabled_in(SpecId::PRAGUE);
+        context
+            .modify_block_chained(|block| {
+                block.set_blob_excess_gas_and_price(self.excess_blob_gas, is_prague);
             })
-            .build_mainnet();
+            .build_mainnet()
+    }
+}
 
-        Self {
-            evm: RwLock::new(evm),
-        }
+/// The EVM spec (hardfork) used to simulate transactions on the chain with
+/// id `chain_id`.
+///
+/// Ethereum mainnet and its testnets use the latest spec, known L2s use
+/// Cancun which is the latest L1 spec they are equivalent to, chain
+/// specific gas rules (e.g. L1 data fees) are not simulated. Unknown chains,
+/// e.g. devnets, use the latest spec.
+pub fn spec_for_chain(chain_id: u64) -> SpecId {
+    match chain_id {
+        // Optimism, Base, Arbitrum One, Polygon PoS - mainnets
+        10 | 8453 | 42161 | 137 => SpecId::CANCUN,
+        // Optimism, Base, Arbitrum - Sepolia testnets
+        11155420 | 84532 | 421614 => SpecId::CANCUN,
+        // Ethereum mainnet, testnets and unknown chains
+        _ => SpecId::default(),
     }
 }
 
+/// Applies the overrides of the environment of the block of `tx`, if any,
+/// e.g. its base fee or timestamp, to `block`.
+pub(crate) fn override_block(block: &mut BlockEnv, tx: &Transaction) {
+    if let Some(base_fee) = tx.base_fee() {
+        block.basefee = *base_fee;
+    }
+    let Some(block_env) = tx.block_env() else {
+        return;
+    };
+    if let Some(coinbase) = block_env.coinbase() {
+        block.beneficiary = *coinbase;
+    }
+    if let Some(timestamp) = block_env.timestamp() {
+        block.timestamp = *timestamp;
+    }
+    if let Some(number) = block_env.number() {
+        block.number = *number;
+    }
+    if let Some(prevrandao) = block_env.prevrandao() {
+        block.prevrandao = Some(*prevrandao);
+    }
+    if let Some(gas_limit) = block_env.gas_limit() {
+        block.gas_limit = *gas_limit;
+    }
+}
+
+/// Configures `cfg` for simulating transactions on the chain with id
+/// `chain_id` using `spec`, without requiring nonce or balance of the sender,
+/// nor a gas price and gas limit within those of the block.
+pub(crate) fn configure_cfg(cfg: &mut CfgEnv, chain_id: u64, spec: SpecId) {
+    cfg.chain_id = chain_id;
+    cfg.spec = spec;
+    // Disable nonce checks, since we might not be providing nonces
+    cfg.disable_nonce_check = true;
+    // Disable balance checks, since we do not wanna have to have balance
+    // to run simulation
+    cfg.disable_balance_check = true; // requires feature flag "optional_balance_check"
+    // Disable base fee and block gas limit checks, since we estimate gas,
+    // not fees, and the gas limit of the transaction might not be set,
+    // relevant when simulating in the environment of a real block.
+    cfg.disable_base_fee = true; // requires feature flag "optional_no_base_fee"
+    cfg.disable_block_gas_limit = true; // requires feature flag "optional_block_gas_limit"
+}
+
 // ========================================
 // Private Implementation
 // ========================================
 impl RevmTxSimulator {
-    fn simulate_tx(evm: &mut EVM, tx: TxEnv) -> Result<Gas> {
+    /// Calls `f` with an EVM for simulating `tx`, if `tx` overrides state or
+    /// the block a new EVM is built with the overrides applied, which is not
+    /// returned to the pool, else an EVM is checked out from the pool.
+    fn with_evm_for<R>(&self, tx: &Transaction, f: impl FnOnce(&mut EVM) -> R) -> Result<R> {
+        if tx.state_override().is_none() && !tx.overrides_block() {
+            return self.with_evm(f);
+        }
+        let mut evm = self.config.build_evm();
+        if let Some(state_override) = tx.state_override() {
+            Self::apply_state_override(&mut evm.journaled_state.database, state_override)?;
+        }
+        override_block(&mut evm.block, tx);
+        Ok(f(&mut evm))
+    }
+
+    /// Calls `f` with an EVM checked out from the pool, building a new one
+    /// if none is available, the lock of the pool is not held during `f`.
+    fn with_evm<R>(&self, f: impl FnOnce(&mut EVM) -> R) -> Result<R> {
+        let pooled = self
+            .pool
+            .lock()
+            .map_err(Error::local_simulation_failed)?
+            .pop();
+        let mut evm = pooled.unwrap_or_else(|| self.config.build_evm());
+        let result = f(&mut evm);
+        self.pool
+            .lock()
+            .map_err(Error::local_simulation_failed)?
+            .push(evm);
+        Ok(result)
+    }
+
+    /// Writes the overridden balance, nonce, code and storage of all
+    /// accounts in `state_override` into `db`, parts not overridden are kept
+    /// as read from the underlying database.
+    pub(crate) fn apply_state_override<ExtDB>(
+        db: &mut CacheDB<ExtDB>,
+        state_override: &StateOverride,
+    ) -> Result<()>
+    where
+        ExtDB: DatabaseRef,
+        ExtDB::Error: std::fmt::Display,
+    {
+        for (address, account) in state_override.iter() {
+            // Not `basic`, which would cache a missing account as not
+            // existing, hiding the info inserted below.
+            let mut info = db
+                .basic_ref(*address)
+                .map_err(Error::local_simulation_failed)?
+                .unwrap_or_default();
+            if let Some(balance) = account.balance() {
+                info.balance = *balance;
+            }
+            if let Some(nonce) = account.nonce() {
+                info.nonce = *nonce;
+            }
+            if let Some(code) = account.code() {
+                let code = Bytecode::new_raw(code.clone());
+                info.code_hash = code.hash_slow();
+                info.code = Some(code);
+            }
+            db.insert_account_info(*address, info);
+            for (slot, value) in account.storage() {
+                db.insert_account_storage(*address, *slot, *value)
+                    .map_err(Error::local_simulation_failed)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Simulates `tx` in `evm` returning the gas used and a breakdown of it,
+    /// `evm` can be backed by any database, e.g. in-memory or a fork of a
+    /// remote chain.
+    pub(crate) fn simulate_tx<DB>(evm: &mut EVM<DB>, tx: TxEnv) -> Result<(Gas, GasBreakdown)>
+    where
+        DB: Database,
+        DB::Error: std::fmt::Display,
+    {
+        Self::simulate_tx_with_state(evm, tx).map(|(gas, breakdown, _, _)| (gas, breakdown))
+    }
+
+    /// Simulates `tx` in `evm` returning the data returned by it and the gas
+    /// used.
+    pub(crate) fn simulate_call_in<DB>(evm: &mut EVM<DB>, tx: TxEnv) -> Result<CallOutput>
+    where
+        DB: Database,
+        DB::Error: std::fmt::Display,
+    {
+        Self::simulate_tx_with_state(evm, tx)
+            .map(|(gas, _, _, output)| CallOutput::new(output, gas))
+    }
+
+    /// Simulates `tx` in `evm` returning the changes to the state of all
+    /// accounts changed by it, the state of `evm` is not modified.
+    pub(crate) fn simulate_tx_with_state_diff<DB>(evm: &mut EVM<DB>, tx: TxEnv) -> Result<StateDiff>
+    where
+        DB: Database,
+        DB::Error: std::fmt::Display,
+    {
+        let (_, _, state, _) = Self::simulate_tx_with_state(evm, tx)?;
+        Self::state_diff(&mut evm.journaled_state.database, &state)
+    }
+
+    /// Diffs the changed accounts in `state` against their state before the
+    /// transaction, read from `db`.
+    fn state_diff<DB>(db: &mut DB, state: &EvmState) -> Result<StateDiff>
+    where
+        DB: Database,
+        DB::Error: std::fmt::Display,
+    {
+        let mut accounts = Vec::new();
+        for (address, account) in state.iter().filter(|(_, a)| a.is_touched()) {
+            let original = db
+                .basic(*address)
+                .map_err(Error::local_simulation_failed)?
+                .unwrap_or_default();
+            let storage = account
+                .storage
+                .iter()
+                .filter_map(|(slot, value)| {
+                    Change::new(value.original_value(), value.present_value())
+                        .map(|change| (*slot, change))
+                })
+                .collect::<std::collections::BTreeMap<_, _>>();
+            let diff = AccountDiffBuilder::default()
+                .balance(Change::new(original.balance, account.info.balance))
+                .nonce(Change::new(original.nonce, account.info.nonce))
+                .storage(storage)
+                .build()
+                .unwrap();
+            accounts.push((*address, diff));
+        }
+        Ok(StateDiff::new(accounts))
+    }
+
+    /// The reason of a revert with `output`, the message of an
+    /// `Error(string)`, else the decoded panic or custom error if possible,
+    /// else the hex of `output`.
+    fn revert_reason(output: &Bytes) -> String {
+        if output.is_empty() {
+            return "without reason".to_owned();
+        }
+        if let Ok(revert) = Revert::abi_decode(output, true) {
+            return revert.reason;
+        }
+        decode_revert_reason(output).unwrap_or_else(|| output.to_string())
+    }
+
+    /// Simulates `tx` in `evm` returning the gas used, a breakdown of it, the
+    /// resulting state of all accounts loaded by it and the data it returned.
+    fn simulate_tx_with_state<DB>(
+        evm: &mut EVM<DB>,
+        tx: TxEnv,
+    ) -> Result<(Gas, GasBreakdown, EvmState, Bytes)>
+    where
+        DB: Database,
+        DB::Error: std::fmt::Display,
+    {
         debug!("Simulating transaction: {tx:?}");
+        let input = tx.data.clone();
+        let InitialAndFloorGas {
+            initial_gas,
+            floor_gas,
+        } = calculate_initial_tx_gas_for_tx(&tx, evm.cfg.spec);
+        let gas_limit = tx.gas_limit;
         // Set the transaction as the current transaction
         evm.modify_tx(|t| *t = tx);
 
         // Transact the transaction that is set in the context.
-        let ResultAndState { result, state: _ } = evm.replay().map_err(|e| match e {
+        let ResultAndState { result, state } = evm.replay().map_err(|e| match e {
             EVMError::Transaction(InvalidTransaction::CallGasCostMoreThanGasLimit {
                 initial_gas,
                 gas_limit,
@@ -103,7 +481,42 @@ impl RevmTxSimulator {
             }
             _ => Error::local_simulation_failed(e),
         })?;
-        Ok(Gas::from(result.gas_used()))
+        let (gas_used, output) = match result {
+            ExecutionResult::Success {
+                gas_used, output, ..
+            } => (Gas::from(gas_used), output.into_data()),
+            ExecutionResult::Revert { gas_used, output } => {
+                return Err(Error::SimulationReverted {
+                    reason: Self::revert_reason(&output),
+                    gas_used: Gas::from(gas_used),
+                });
+            }
+            ExecutionResult::Halt {
+                reason: HaltReason::OutOfGas(reason),
+                gas_used,
+            } => {
+                // Ran out of gas mid execution, the gas needed to complete
+                // is unknown, only that it is more than the gas limit.
+                warn!("Ran out of gas: {reason:?}, used: {gas_used}");
+                return Err(Error::GasExceedsLimit {
+                    estimated_cost: None,
+                    gas_limit: Gas::from(gas_limit),
+                });
+            }
+            ExecutionResult::Halt { reason, gas_used } => {
+                return Err(Error::SimulationHalted {
+                    reason: format!("{reason:?}"),
+                    gas_used: Gas::from(gas_used),
+                });
+            }
+        };
+        let breakdown = GasBreakdown::new(
+            &input,
+            gas_used,
+            Gas::from(initial_gas),
+            Gas::from(floor_gas),
+        );
+        Ok((gas_used, breakdown, state, output))
     }
 }
 
@@ -112,16 +525,105 @@ impl RevmTxSimulator {
 // ========================================
 impl LocalTxSimulator for RevmTxSimulator {
     fn locally_simulate_tx(&self, tx: &Transaction) -> Result<Gas> {
-        let mut evm = self.evm.write().map_err(Error::local_simulation_failed)?;
-        let tx = TxEnv::from(tx.clone());
-        Self::simulate_tx(&mut evm, tx)
+        self.locally_simulate_tx_with_breakdown(tx)
+            .map(|(gas, _)| gas)
+    }
+
+    fn locally_simulate_tx_with_breakdown(
+        &self,
+        tx: &Transaction,
+    ) -> Result<(Gas, Option<GasBreakdown>)> {
+        let tx_env = TxEnv {
+            chain_id: Some(self.config.chain_id),
+            ..TxEnv::from(tx.clone())
+        };
+        self.with_evm_for(tx, |evm| Self::simulate_tx(evm, tx_env))?
             .inspect_err(|e| {
                 error!("Error while simulating transaction: {e}");
             })
-            .inspect(|gas| {
-                debug!("Local simulation - gas used: {gas}");
+            .inspect(|(gas, breakdown)| {
+                debug!("Local simulation - gas used: {gas}, breakdown: {breakdown:?}");
+            })
+            .map(|(gas, breakdown)| (gas, Some(breakdown)))
+    }
+
+    fn locally_simulate_tx_with_state_diff(&self, tx: &Transaction) -> Result<StateDiff> {
+        let tx_env = TxEnv {
+            chain_id: Some(self.config.chain_id),
+            ..TxEnv::from(tx.clone())
+        };
+        self.with_evm_for(tx, |evm| Self::simulate_tx_with_state_diff(evm, tx_env))?
+            .inspect_err(|e| {
+                error!("Error while simulating transaction for state diff: {e}");
+            })
+    }
+
+    fn simulate_call(&self, tx: &Transaction) -> Result<CallOutput> {
+        let tx_env = TxEnv {
+            chain_id: Some(self.config.chain_id),
+            ..TxEnv::from(tx.clone())
+        };
+        self.with_evm_for(tx, |evm| Self::simulate_call_in(evm, tx_env))?
+            .inspect_err(|e| {
+                error!("Error while simulating call: {e}");
+            })
+    }
+
+    fn simulate_bundle(&self, txs: &[Transaction]) -> Result<Vec<Gas>> {
+        // A new EVM, since the state of a pooled one must not be changed.
+        let mut evm = self.config.build_evm();
+        let block = evm.block.clone();
+        txs.iter()
+            .enumerate()
+            .map(|(index, tx)| {
+                let tx_env = TxEnv {
+                    chain_id: Some(self.config.chain_id),
+                    ..TxEnv::from(tx.clone())
+                };
+                let db = &mut evm.journaled_state.database;
+                if let Some(state_override) = tx.state_override() {
+                    Self::apply_state_override(db, state_override)?;
+                }
+                evm.block = block.clone();
+                override_block(&mut evm.block, tx);
+                let (gas, _, state, _) =
+                    Self::simulate_tx_with_state(&mut evm, tx_env).map_err(|e| {
+                        Error::BundleTransactionFailed {
+                            index,
+                            underlying: Box::new(e),
+                        }
+                    })?;
+                evm.journaled_state.database.commit(state);
+                Ok(gas)
+            })
+            .collect::<Result<Vec<_>>>()
+            .inspect_err(|e| {
+                error!("Error while simulating bundle: {e}");
             })
     }
+
+    fn blob_base_fee(&self) -> Option<u128> {
+        self.with_evm(|evm| evm.block.blob_gasprice()).ok()?
+    }
+
+    fn spec(&self) -> Option<SpecId> {
+        Some(self.config.resolved_spec())
+    }
+}
+
+/// A local simulator which always fails, used when configured to be remote
+/// only, i.e. estimates are computed by the remote gas estimator only.
+pub struct NoLocalTxSimulator;
+
+// ========================================
+// LocalTxSimulator Implementation
+// ========================================
+impl LocalTxSimulator for NoLocalTxSimulator {
+    fn locally_simulate_tx(&self, _: &Transaction) -> Result<Gas> {
+        Err(Error::local_simulation_failed(
+            "Configured to be remote only",
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -183,4 +685,408 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn bundle_shares_state() {
+        let contract = Address::new([0xcc; 20]);
+        let deploy = TransactionBuilder::default()
+            .to(contract)
+            .state_override(StateOverride::new([(
+                contract,
+                AccountOverrideBuilder::default()
+                    // PUSH1 1 PUSH1 0 SSTORE STOP
+                    .code(Bytes::from([0x60, 0x01, 0x60, 0x00, 0x55, 0x00]))
+                    .build()
+                    .unwrap(),
+            )]))
+            .build()
+            .unwrap();
+        let call = TransactionBuilder::default().to(contract).build().unwrap();
+        let sut = Sut::new();
+
+        let gas = sut.simulate_bundle(&[deploy, call.clone()]).unwrap();
+
+        // Setting the slot from zero costs more than setting it again.
+        assert_eq!(gas, [Gas::from(43_106), Gas::from(23_206)]);
+        // Shared state is not kept after the bundle.
+        assert_eq!(
+            sut.locally_simulate_tx(&call),
+            Ok(Gas::exact_native_token_transfer())
+        );
+    }
+
+    #[test]
+    fn base_fee_override_is_read_by_contract() {
+        let contract = Address::new([0xcc; 20]);
+        let with_base_fee = |base_fee: Option<u64>| {
+            TransactionBuilder::default()
+                .to(contract)
+                .base_fee(base_fee)
+                .state_override(StateOverride::new([(
+                    contract,
+                    AccountOverrideBuilder::default()
+                        // BASEFEE PUSH1 0 SSTORE STOP
+                        .code(Bytes::from([0x48, 0x60, 0x00, 0x55, 0x00]))
+                        .build()
+                        .unwrap(),
+                )]))
+                .build()
+                .unwrap()
+        };
+        let sut = Sut::new();
+
+        // Storing zero in an empty slot costs less than storing non zero.
+        assert_eq!(
+            sut.locally_simulate_tx(&with_base_fee(None)),
+            Ok(Gas::from(23_205))
+        );
+        assert_eq!(
+            sut.locally_simulate_tx(&with_base_fee(Some(7))),
+            Ok(Gas::from(43_105))
+        );
+    }
+
+    #[test]
+    fn block_env_override_is_read_by_contract() {
+        let contract = Address::new([0xcc; 20]);
+        let storing = |opcode: u8, block_env: BlockEnvOverride| {
+            TransactionBuilder::default()
+                .to(contract)
+                .block_env(block_env)
+                .state_override(StateOverride::new([(
+                    contract,
+                    AccountOverrideBuilder::default()
+                        // <opcode> PUSH1 0 SSTORE STOP
+                        .code(Bytes::from([opcode, 0x60, 0x00, 0x55, 0x00]))
+                        .build()
+                        .unwrap(),
+                )]))
+                .build()
+                .unwrap()
+        };
+        let sut = Sut::new();
+        let gas_of = |opcode: u8, block_env: BlockEnvOverride| {
+            sut.locally_simulate_tx(&storing(opcode, block_env))
+                .unwrap()
+        };
+        // Storing zero in an empty slot costs less than storing non zero.
+        let (zero, non_zero) = (Gas::from(23_205), Gas::from(43_105));
+        let empty = BlockEnvOverride::default();
+
+        // COINBASE
+        assert_eq!(gas_of(0x41, empty.clone()), zero);
+        let coinbase = BlockEnvOverrideBuilder::default()
+            .coinbase(Address::new([0xfe; 20]))
+            .build()
+            .unwrap();
+        assert_eq!(gas_of(0x41, coinbase), non_zero);
+
+        // TIMESTAMP, overriding the default of one
+        let timestamp = BlockEnvOverrideBuilder::default()
+            .timestamp(0u64)
+            .build()
+            .unwrap();
+        assert_eq!(gas_of(0x42, timestamp), zero);
+
+        // NUMBER
+        assert_eq!(gas_of(0x43, empty.clone()), zero);
+        let number = BlockEnvOverrideBuilder::default()
+            .number(21_000_000u64)
+            .build()
+            .unwrap();
+        assert_eq!(gas_of(0x43, number), non_zero);
+    }
+
+    #[test]
+    fn bundle_fails_with_index_of_failed_tx() {
+        let limit = Gas::from(100);
+        let tx = TransactionBuilder::default()
+            .to(Address::from([0x12; 20]))
+            .gas_limit(limit)
+            .build()
+            .unwrap();
+        let sut = Sut::new();
+
+        let res = sut.simulate_bundle(&[Transaction::sample_native_token_transfer(), tx]);
+
+        assert_eq!(
+            res,
+            Err(Error::BundleTransactionFailed {
+                index: 1,
+                underlying: Box::new(Error::GasExceedsLimit {
+                    estimated_cost: Some(Gas::exact_native_token_transfer()),
+                    gas_limit: limit
+                })
+            })
+        );
+    }
+
+    #[test]
+    fn blob_carrying_returns_execution_gas() {
+        let tx = Transaction::sample_blob_carrying();
+        let sut = Sut::new();
+        let gas_used = sut.locally_simulate_tx(&tx).unwrap();
+        // 21_000 base + EIP-7623 calldata floor for 2 non zero bytes (4 tokens
+        // each, 10 gas per token), blob gas is not included
+        assert_eq!(gas_used, Gas::from(21_000 + 2 * 4 * 10));
+    }
+
+    #[test]
+    fn blob_base_fee_is_derived_from_configured_excess_blob_gas() {
+        let excess_blob_gas = 10_000_000;
+        let sut = Sut::builder().excess_blob_gas(excess_blob_gas).build();
+        let expected = revm::context_interface::block::calc_blob_gasprice(excess_blob_gas, true);
+        assert!(expected > 1);
+        assert_eq!(sut.blob_base_fee(), Some(expected));
+    }
+
+    #[test]
+    fn blob_carrying_fails_if_max_fee_per_blob_gas_below_blob_base_fee() {
+        let sut = Sut::builder().excess_blob_gas(10_000_000).build();
+        let blob_base_fee = sut.blob_base_fee().unwrap();
+        let mut tx = Transaction::sample_blob_carrying();
+        tx.set_max_fee_per_blob_gas(Some(blob_base_fee - 1));
+        assert!(sut.locally_simulate_tx(&tx).is_err());
+        tx.set_max_fee_per_blob_gas(Some(blob_base_fee));
+        assert!(sut.locally_simulate_tx(&tx).is_ok());
+    }
+
+    #[test]
+    fn for_chain_sets_chain_id_and_spec() {
+        let sut = Sut::builder().chain_id(8453).build();
+        let (chain_id, spec) = sut
+            .with_evm(|evm| (evm.cfg.chain_id, evm.cfg.spec))
+            .unwrap();
+        assert_eq!(chain_id, 8453);
+        assert_eq!(spec, SpecId::CANCUN);
+    }
+
+    #[test]
+    fn new_is_mainnet() {
+        let sut = Sut::new();
+        let (chain_id, spec) = sut
+            .with_evm(|evm| (evm.cfg.chain_id, evm.cfg.spec))
+            .unwrap();
+        assert_eq!(chain_id, MAINNET_CHAIN_ID);
+        assert_eq!(spec, SpecId::default());
+    }
+
+    #[test]
+    fn blob_carrying_on_other_chain() {
+        let sut = Sut::builder().chain_id(11155111).build();
+        assert!(
+            sut.locally_simulate_tx(&Transaction::sample_blob_carrying())
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn builder_spec_overrides_spec_of_chain() {
+        let sut = Sut::builder().chain_id(8453).spec(SpecId::SHANGHAI).build();
+        let (chain_id, spec) = sut
+            .with_evm(|evm| (evm.cfg.chain_id, evm.cfg.spec))
+            .unwrap();
+        assert_eq!(chain_id, 8453);
+        assert_eq!(spec, SpecId::SHANGHAI);
+    }
+
+    #[test]
+    fn spec_affects_intrinsic_gas() {
+        // Prague applies the EIP-7623 calldata floor, Shanghai does not.
+        let tx = Transaction::sample_blob_carrying();
+        let tx = TransactionBuilder::default()
+            .to(*tx.to())
+            .input(tx.input().clone())
+            .build()
+            .unwrap();
+        let prague = Sut::builder().spec(SpecId::PRAGUE).build();
+        let shanghai = Sut::builder().spec(SpecId::SHANGHAI).build();
+        assert_eq!(
+            prague.locally_simulate_tx(&tx).unwrap(),
+            Gas::from(21_000 + 2 * 4 * 10)
+        );
+        assert_eq!(
+            shanghai.locally_simulate_tx(&tx).unwrap(),
+            Gas::from(21_000 + 2 * 16)
+        );
+    }
+
+    #[test]
+    fn blob_carrying_fails_before_cancun() {
+        let sut = Sut::builder().spec(SpecId::SHANGHAI).build();
+        assert!(
+            sut.locally_simulate_tx(&Transaction::sample_blob_carrying())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn breakdown_of_contract_creation() {
+        let tx = Transaction::sample_contract_creation();
+        let sut = Sut::new();
+        let (gas_used, breakdown) = sut.locally_simulate_tx_with_breakdown(&tx).unwrap();
+        let breakdown = breakdown.unwrap();
+        assert_eq!(breakdown.total(), gas_used);
+        assert_eq!(*breakdown.calldata(), Gas::calldata_cost(tx.input()));
+        // 53_000 creation base + EIP-3860 initcode cost of 13 words
+        assert_eq!(*breakdown.intrinsic(), Gas::from(53_000 + 13 * 2));
+    }
+
+    #[test]
+    fn state_diff_of_native_transfer() {
+        let sender = Address::new([0xaa; 20]);
+        let recipient = Address::new([0xbb; 20]);
+        let balance = U256::from(1_000_000);
+        let value = U256::from(1_000);
+        let sut = Sut::new();
+        let tx = TransactionBuilder::default()
+            .from(sender)
+            .to(recipient)
+            .value(value)
+            .state_override(StateOverride::new([(
+                sender,
+                AccountOverrideBuilder::default()
+                    .balance(balance)
+                    .build()
+                    .unwrap(),
+            )]))
+            .build()
+            .unwrap();
+
+        let diff = sut.locally_simulate_tx_with_state_diff(&tx).unwrap();
+
+        let sender_diff = diff.account(&sender).unwrap();
+        assert_eq!(
+            *sender_diff.balance(),
+            Change::new(balance, balance - value)
+        );
+        assert_eq!(*sender_diff.nonce(), Change::new(0, 1));
+        assert_eq!(
+            *diff.account(&recipient).unwrap().balance(),
+            Change::new(U256::ZERO, value)
+        );
+        // Simulation does not commit the state
+        assert_eq!(sut.locally_simulate_tx_with_state_diff(&tx).unwrap(), diff);
+    }
+
+    #[test]
+    fn simulations_do_not_share_a_lock() {
+        let sut = Sut::new();
+        let tx = Transaction::sample_contract_creation();
+        // Simulating while another EVM is checked out does not deadlock
+        let nested = sut
+            .with_evm(|_| sut.locally_simulate_tx(&tx))
+            .unwrap()
+            .unwrap();
+        assert_eq!(nested, sut.locally_simulate_tx(&tx).unwrap());
+        assert_eq!(sut.pool.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn state_override_seeds_code_and_storage() {
+        let token = Address::new([0xaa; 20]);
+        // Reverts unless storage slot 1 is non zero:
+        // PUSH1 1 SLOAD PUSH1 10 JUMPI PUSH1 0 DUP1 REVERT JUMPDEST STOP
+        let code = Bytes::from([
+            0x60, 0x01, 0x54, 0x60, 0x0a, 0x57, 0x60, 0x00, 0x80, 0xfd, 0x5b, 0x00,
+        ]);
+        let sut = Sut::new();
+        let tx = TransactionBuilder::default()
+            .from(Address::new([0xbb; 20]))
+            .to(token)
+            .state_override(StateOverride::new([(
+                token,
+                AccountOverrideBuilder::default()
+                    .code(code)
+                    .storage([(U256::from(1), U256::from(42))])
+                    .build()
+                    .unwrap(),
+            )]))
+            .build()
+            .unwrap();
+
+        // 21_000 base + PUSH1, cold SLOAD, PUSH1, JUMPI, JUMPDEST, STOP
+        assert_eq!(
+            sut.locally_simulate_tx(&tx).unwrap(),
+            Gas::from(21_000 + 3 + 2_100 + 3 + 10 + 1)
+        );
+        // The override is not kept in the pool
+        assert!(sut.pool.lock().unwrap().is_empty());
+        let without_override = TransactionBuilder::default()
+            .from(Address::new([0xbb; 20]))
+            .to(token)
+            .build()
+            .unwrap();
+        assert_eq!(
+            sut.locally_simulate_tx(&without_override).unwrap(),
+            Gas::from(21_000)
+        );
+    }
+
+    /// A call to a contract with `code`, injected with a state override.
+    fn call_with_code(code: Bytes) -> Transaction {
+        let contract = Address::new([0xcc; 20]);
+        TransactionBuilder::default()
+            .to(contract)
+            .state_override(StateOverride::new([(
+                contract,
+                AccountOverrideBuilder::default()
+                    .code(code)
+                    .build()
+                    .unwrap(),
+            )]))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn revert_reason_is_decoded() {
+        let payload = Revert::from("insufficient allowance").abi_encode();
+        // CODECOPY the payload after the 13 bytes of code to memory and REVERT with it
+        let len = payload.len() as u8;
+        let mut code = vec![
+            0x60, len, 0x60, 0x0d, 0x60, 0x00, 0x39, 0x60, len, 0x60, 0x00, 0xfd, 0x00,
+        ];
+        code.extend(payload);
+
+        let result = Sut::new().locally_simulate_tx(&call_with_code(Bytes::from(code)));
+
+        assert!(matches!(
+            result,
+            Err(Error::SimulationReverted { reason, .. }) if reason == "insufficient allowance"
+        ));
+    }
+
+    #[test]
+    fn halt_consumes_all_gas() {
+        let mut tx = call_with_code(Bytes::from([0xfe])); // INVALID
+        tx.set_gas_limit(Some(Gas::from(30_000)));
+
+        let result = Sut::new().locally_simulate_tx(&tx);
+
+        assert_eq!(
+            result,
+            Err(Error::SimulationHalted {
+                reason: "InvalidFEOpcode".to_owned(),
+                gas_used: Gas::from(30_000)
+            })
+        );
+    }
+
+    #[test]
+    fn out_of_gas_exceeds_limit() {
+        // JUMPDEST, PUSH1 0, JUMP, i.e. loop until out of gas
+        let mut tx = call_with_code(Bytes::from([0x5b, 0x60, 0x00, 0x56]));
+        tx.set_gas_limit(Some(Gas::from(30_000)));
+
+        let result = Sut::new().locally_simulate_tx(&tx);
+
+        assert_eq!(
+            result,
+            Err(Error::GasExceedsLimit {
+                estimated_cost: None,
+                gas_limit: Gas::from(30_000)
+            })
+        );
+    }
 }
@@ -0,0 +1,377 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::prelude::*;
+
+/// Upper bounds, in seconds, of the buckets of the [`Histogram`]s of
+/// [`Metrics`].
+const DURATION_BUCKETS_IN_SECS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// The sources a computed, i.e. not cached nor exact, estimate can be built
+/// from, see [`Metrics::record_estimate_source`], `None` if neither local nor
+/// remote succeeded.
+const COMPUTED_SOURCES: [Option<EstimateSource>; 4] = [
+    Some(EstimateSource::Both),
+    Some(EstimateSource::LocalOnly),
+    Some(EstimateSource::RemoteOnly),
+    None,
+];
+
+/// The label of the source of a computed estimate, e.g. `"both"`, or
+/// `"none"` if neither local nor remote succeeded.
+pub fn computed_source_label(source: Option<EstimateSource>) -> &'static str {
+    source.as_ref().map_or("none", EstimateSource::label)
+}
+
+/// A histogram of durations with fixed buckets, see
+/// [`DURATION_BUCKETS_IN_SECS`].
+#[derive(Debug, Default)]
+pub struct Histogram {
+    /// Non-cumulative count per bucket, the last is the `+Inf` bucket.
+    buckets: [AtomicU64; DURATION_BUCKETS_IN_SECS.len() + 1],
+    sum_micros: AtomicU64,
+    count: AtomicU64,
+}
+
+impl Histogram {
+    /// Records an observation of `duration`.
+    pub fn observe(&self, duration: Duration) {
+        let secs = duration.as_secs_f64();
+        let index = DURATION_BUCKETS_IN_SECS
+            .iter()
+            .position(|bound| secs <= *bound)
+            .unwrap_or(DURATION_BUCKETS_IN_SECS.len());
+        self.buckets[index].fetch_add(1, Ordering::Relaxed);
+        self.sum_micros
+            .fetch_add(duration.as_micros() as u64, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Number of observations.
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        let _ = writeln!(out, "# HELP {name} {help}");
+        let _ = writeln!(out, "# TYPE {name} histogram");
+        let mut cumulative = 0;
+        for (bound, bucket) in DURATION_BUCKETS_IN_SECS.iter().zip(&self.buckets) {
+            cumulative += bucket.load(Ordering::Relaxed);
+            let _ = writeln!(out, "{name}_bucket{{le=\"{bound}\"}} {cumulative}");
+        }
+        cumulative += self.buckets[DURATION_BUCKETS_IN_SECS.len()].load(Ordering::Relaxed);
+        let _ = writeln!(out, "{name}_bucket{{le=\"+Inf\"}} {cumulative}");
+        let sum = self.sum_micros.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+        let _ = writeln!(out, "{name}_sum {sum}");
+        let _ = writeln!(out, "{name}_count {}", self.count());
+    }
+}
+
+/// A pair of counters of successes and failures.
+#[derive(Debug, Default)]
+pub struct OutcomeCounter {
+    ok: AtomicU64,
+    error: AtomicU64,
+}
+
+impl OutcomeCounter {
+    /// Counts a success if `ok`, else a failure.
+    pub fn record(&self, ok: bool) {
+        let counter = if ok { &self.ok } else { &self.error };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Number of successes and failures.
+    pub fn counts(&self) -> (u64, u64) {
+        (
+            self.ok.load(Ordering::Relaxed),
+            self.error.load(Ordering::Relaxed),
+        )
+    }
+
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        let (ok, error) = self.counts();
+        let _ = writeln!(out, "# HELP {name} {help}");
+        let _ = writeln!(out, "# TYPE {name} counter");
+        let _ = writeln!(out, "{name}{{outcome=\"ok\"}} {ok}");
+        let _ = writeln!(out, "{name}{{outcome=\"error\"}} {error}");
+    }
+}
+
+/// Number of successes and failures of an [`OutcomeCounter`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, CopyGetters)] // deserialize for tests
+pub struct OutcomeCounts {
+    /// Number of successes.
+    #[getset(get_copy = "pub")]
+    ok: u64,
+
+    /// Number of failures.
+    #[getset(get_copy = "pub")]
+    error: u64,
+}
+
+impl From<(u64, u64)> for OutcomeCounts {
+    fn from((ok, error): (u64, u64)) -> Self {
+        Self { ok, error }
+    }
+}
+
+/// A JSON friendly snapshot of the counters of [`Metrics`] of the local and
+/// remote estimators, e.g. served on `GET /stats`, to track how often each
+/// fails and which of them estimates are built from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Getters, CopyGetters)] // deserialize for tests
+pub struct EstimatorStats {
+    /// Outcome of local simulations.
+    #[getset(get_copy = "pub")]
+    local: OutcomeCounts,
+
+    /// Outcome of calls to the remote gas estimator.
+    #[getset(get_copy = "pub")]
+    remote: OutcomeCounts,
+
+    /// Number of computed estimates by which of local and remote succeeded,
+    /// keyed by [`computed_source_label`], e.g. `"both"`.
+    #[getset(get = "pub")]
+    estimate_sources: BTreeMap<String, u64>,
+}
+
+/// Metrics of the [`Gastimator`], rendered in the Prometheus text format by
+/// [`Metrics::render`], e.g. served on `GET /metrics`.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    /// Outcome of estimate requests, including cache hits.
+    estimates: OutcomeCounter,
+
+    /// Duration of estimate requests, including cache hits.
+    estimate_duration: Histogram,
+
+    /// Outcome of local simulations.
+    local: OutcomeCounter,
+
+    /// Duration of local simulations.
+    local_duration: Histogram,
+
+    /// Outcome of calls to the remote gas estimator.
+    remote: OutcomeCounter,
+
+    /// Duration of calls to the remote gas estimator.
+    remote_duration: Histogram,
+
+    /// Number of computed estimates per source, in the order of
+    /// [`COMPUTED_SOURCES`].
+    sources: [AtomicU64; 4],
+}
+
+// ========================================
+// Public Implementation
+// ========================================
+impl Metrics {
+    /// Records an estimate request which took `duration`.
+    pub fn record_estimate(&self, ok: bool, duration: Duration) {
+        self.estimates.record(ok);
+        self.estimate_duration.observe(duration);
+    }
+
+    /// Records a local simulation which took `duration`.
+    pub fn record_local(&self, ok: bool, duration: Duration) {
+        self.local.record(ok);
+        self.local_duration.observe(duration);
+    }
+
+    /// Records a call to the remote gas estimator which took `duration`.
+    pub fn record_remote(&self, ok: bool, duration: Duration) {
+        self.remote.record(ok);
+        self.remote_duration.observe(duration);
+    }
+
+    /// Records which estimates a computed estimate was built from, `None` if
+    /// neither local nor remote succeeded.
+    pub fn record_estimate_source(&self, source: Option<EstimateSource>) {
+        let index = COMPUTED_SOURCES
+            .iter()
+            .position(|s| *s == source)
+            .expect("COMPUTED_SOURCES contains all computed sources");
+        self.sources[index].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Number of successful and failed estimate requests.
+    pub fn estimate_counts(&self) -> (u64, u64) {
+        self.estimates.counts()
+    }
+
+    /// Number of successful and failed local simulations.
+    pub fn local_counts(&self) -> (u64, u64) {
+        self.local.counts()
+    }
+
+    /// Number of successful and failed calls to the remote gas estimator.
+    pub fn remote_counts(&self) -> (u64, u64) {
+        self.remote.counts()
+    }
+
+    /// A snapshot of the counters of the local and remote estimators.
+    pub fn estimator_stats(&self) -> EstimatorStats {
+        EstimatorStats {
+            local: self.local_counts().into(),
+            remote: self.remote_counts().into(),
+            estimate_sources: COMPUTED_SOURCES
+                .iter()
+                .zip(&self.sources)
+                .map(|(source, count)| {
+                    (
+                        computed_source_label(*source).to_owned(),
+                        count.load(Ordering::Relaxed),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Renders all metrics, and the statistics of the cache `cache_stats`,
+    /// in the Prometheus text exposition format.
+    pub fn render(&self, cache_stats: &CacheStats) -> String {
+        let mut out = String::new();
+        self.estimates.render(
+            &mut out,
+            "gastimator_estimates_total",
+            "Number of estimate requests, including cache hits.",
+        );
+        self.estimate_duration.render(
+            &mut out,
+            "gastimator_estimate_duration_seconds",
+            "Duration of estimate requests, including cache hits.",
+        );
+        self.local.render(
+            &mut out,
+            "gastimator_local_simulations_total",
+            "Number of local simulations.",
+        );
+        self.local_duration.render(
+            &mut out,
+            "gastimator_local_simulation_duration_seconds",
+            "Duration of local simulations.",
+        );
+        self.remote.render(
+            &mut out,
+            "gastimator_remote_estimates_total",
+            "Number of calls to the remote gas estimator.",
+        );
+        self.remote_duration.render(
+            &mut out,
+            "gastimator_remote_estimate_duration_seconds",
+            "Duration of calls to the remote gas estimator.",
+        );
+        let name = "gastimator_estimate_source_total";
+        let _ = writeln!(
+            out,
+            "# HELP {name} Number of computed estimates by which of local and remote succeeded."
+        );
+        let _ = writeln!(out, "# TYPE {name} counter");
+        for (source, count) in COMPUTED_SOURCES.iter().zip(&self.sources) {
+            let _ = writeln!(
+                out,
+                "{name}{{source=\"{}\"}} {}",
+                computed_source_label(*source),
+                count.load(Ordering::Relaxed)
+            );
+        }
+        for (name, kind, help, value) in [
+            (
+                "gastimator_cache_hits_total",
+                "counter",
+                "Number of lookups with a fresh entry.",
+                cache_stats.hits().to_string(),
+            ),
+            (
+                "gastimator_cache_misses_total",
+                "counter",
+                "Number of lookups without a fresh entry.",
+                cache_stats.misses().to_string(),
+            ),
+            (
+                "gastimator_cache_uncacheable_total",
+                "counter",
+                "Number of transactions which were not cacheable.",
+                cache_stats.uncacheable().to_string(),
+            ),
+            (
+                "gastimator_cache_size",
+                "gauge",
+                "Number of entries, including expired ones.",
+                cache_stats.size().to_string(),
+            ),
+            (
+                "gastimator_cache_hit_ratio",
+                "gauge",
+                "Hits divided by lookups.",
+                cache_stats.hit_rate().to_string(),
+            ),
+        ] {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} {kind}");
+            let _ = writeln!(out, "{name} {value}");
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = Metrics;
+
+    #[test]
+    fn histogram_buckets_are_cumulative() {
+        let sut = Sut::default();
+        sut.record_local(true, Duration::from_millis(3));
+        sut.record_local(false, Duration::from_millis(30));
+        sut.record_local(true, Duration::from_secs(60));
+
+        let rendered = sut.render(&<Cache>::default().stats());
+
+        assert!(rendered.contains("gastimator_local_simulations_total{outcome=\"ok\"} 2\n"));
+        assert!(rendered.contains("gastimator_local_simulations_total{outcome=\"error\"} 1\n"));
+        assert!(
+            rendered
+                .contains("gastimator_local_simulation_duration_seconds_bucket{le=\"0.005\"} 1\n")
+        );
+        assert!(
+            rendered
+                .contains("gastimator_local_simulation_duration_seconds_bucket{le=\"0.05\"} 2\n")
+        );
+        assert!(
+            rendered.contains("gastimator_local_simulation_duration_seconds_bucket{le=\"10\"} 2\n")
+        );
+        assert!(
+            rendered
+                .contains("gastimator_local_simulation_duration_seconds_bucket{le=\"+Inf\"} 3\n")
+        );
+        assert!(rendered.contains("gastimator_local_simulation_duration_seconds_count 3\n"));
+    }
+
+    #[test]
+    fn estimate_source_and_cache_stats() {
+        let sut = Sut::default();
+        sut.record_estimate_source(Some(EstimateSource::LocalOnly));
+        let stats = CacheStatsBuilder::default()
+            .hits(3u64)
+            .misses(1u64)
+            .uncacheable(0u64)
+            .size(1usize)
+            .hit_rate(0.75)
+            .build()
+            .unwrap();
+
+        let rendered = sut.render(&stats);
+
+        assert!(rendered.contains("gastimator_estimate_source_total{source=\"local_only\"} 1\n"));
+        assert!(rendered.contains("gastimator_estimate_source_total{source=\"remote_only\"} 0\n"));
+        assert!(rendered.contains("gastimator_cache_hits_total 3\n"));
+        assert!(rendered.contains("gastimator_cache_hit_ratio 0.75\n"));
+    }
+}
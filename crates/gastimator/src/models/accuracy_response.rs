@@ -0,0 +1,73 @@
+use crate::prelude::*;
+
+/// The estimate of a mined transaction compared against the gas it actually
+/// used, see [`Gastimator::accuracy`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Getters, CopyGetters)] // deserialize for tests
+pub struct AccuracyResponse {
+    /// The estimate of the transaction, its fields are flattened into this
+    /// response.
+    #[serde(flatten)]
+    #[getset(get = "pub")]
+    estimate: GasEstimateResponse,
+
+    /// The gas used by the mined transaction, from its receipt.
+    #[getset(get_copy = "pub")]
+    actual_gas: Gas,
+
+    /// The unpadded recommended estimate minus `actual_gas`, positive if
+    /// overestimated, negative if underestimated.
+    #[getset(get_copy = "pub")]
+    delta: i64,
+}
+
+impl AccuracyResponse {
+    /// Compares `estimate` against `actual_gas`, using the unpadded estimate,
+    /// of a range the recommended gas.
+    pub fn new(estimate: GasEstimateResponse, actual_gas: Gas) -> Self {
+        let estimated = estimate
+            .unpadded_gas_usage()
+            .as_ref()
+            .unwrap_or(estimate.gas_usage())
+            .clone()
+            .collapsed(RangeCollapse::Recommended)
+            .max_gas();
+        let delta = *estimated as i64 - *actual_gas as i64;
+        Self {
+            estimate,
+            actual_gas,
+            delta,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = AccuracyResponse;
+
+    #[test]
+    fn delta_of_unpadded_recommended() {
+        let kind = TransactionKind::ContractCreation;
+        let range = |recommended: u64| GasUsage::EstimateWithRange {
+            kind: kind.clone(),
+            low: Gas::from(50_000),
+            high: Gas::from(70_000),
+            recommended: Gas::from(recommended),
+        };
+        let estimate = GasEstimateResponseBuilder::default()
+            .kind(kind.clone())
+            .gas_usage(range(66_000))
+            .unpadded_gas_usage(Some(range(60_000)))
+            .source(EstimateSource::Both)
+            .time_elapsed_in_millis(1u128)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            Sut::new(estimate.clone(), Gas::from(63_197)).delta(),
+            -3_197
+        );
+        assert_eq!(Sut::new(estimate, Gas::from(55_000)).delta(), 5_000);
+    }
+}
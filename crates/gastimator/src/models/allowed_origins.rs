@@ -0,0 +1,56 @@
+/// The origins from which browsers are allowed to call the REST server, i.e.
+/// which are answered with CORS headers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AllowedOrigins {
+    /// Any origin, i.e. `Access-Control-Allow-Origin: *`.
+    Any,
+    /// Only these origins, e.g. `["https://app.example.com"]`, none if empty.
+    List(Vec<String>),
+}
+
+impl Default for AllowedOrigins {
+    /// Any origin in debug builds, to ease local development, no origin in
+    /// release builds.
+    fn default() -> Self {
+        if cfg!(debug_assertions) {
+            Self::Any
+        } else {
+            Self::List(Vec::new())
+        }
+    }
+}
+
+// ========================================
+// Public Implementation
+// ========================================
+impl AllowedOrigins {
+    /// `Any` if `origins` contains `"*"`, else a `List` of `origins`.
+    pub fn new(origins: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        let origins = origins.into_iter().map(Into::into).collect::<Vec<_>>();
+        if origins.iter().any(|origin| origin == "*") {
+            Self::Any
+        } else {
+            Self::List(origins)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = AllowedOrigins;
+
+    #[test]
+    fn wildcard_is_any() {
+        assert_eq!(Sut::new(["https://a.example.com", "*"]), Sut::Any);
+    }
+
+    #[test]
+    fn list_of_origins() {
+        assert_eq!(
+            Sut::new(["https://app.example.com/"]),
+            Sut::List(vec!["https://app.example.com/".to_owned()])
+        );
+    }
+}
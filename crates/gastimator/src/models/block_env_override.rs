@@ -0,0 +1,41 @@
+use crate::prelude::*;
+
+/// Overrides of the environment of the block a transaction is locally
+/// simulated in, for contracts reading e.g. `block.timestamp` or
+/// `block.coinbase`, parts which are `None` or omitted are those of the
+/// simulated block, i.e. defaults unless forking.
+///
+/// Overrides are only applied to the local simulation, the remote gas
+/// estimator is not passed them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Builder, Getters)]
+#[builder(setter(into), default)]
+pub struct BlockEnvOverride {
+    /// The beneficiary of the block, i.e. `block.coinbase`, if overridden.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub")]
+    coinbase: Option<Address>,
+
+    /// The unix timestamp in seconds, i.e. `block.timestamp`, if overridden.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub")]
+    timestamp: Option<u64>,
+
+    /// The number of the block, i.e. `block.number`, if overridden. Unlike
+    /// `block_number` of the transaction this does not change the state
+    /// simulated against.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub")]
+    number: Option<u64>,
+
+    /// The randomness of the beacon chain, i.e. `block.prevrandao`, if
+    /// overridden.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub")]
+    prevrandao: Option<B256>,
+
+    /// The gas limit of the block, i.e. `block.gaslimit`, if overridden, it
+    /// does not limit the gas of the transaction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub")]
+    gas_limit: Option<u64>,
+}
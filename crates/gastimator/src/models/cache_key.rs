@@ -0,0 +1,55 @@
+use crate::prelude::*;
+
+/// Key of the [`Cache`] of estimates, the transaction without its gas limit,
+/// since the gas usage of a transaction does not depend on its limit, so
+/// that transactions differing only in gas limit share one entry.
+///
+/// The limit still gates the estimate, a cached estimate is capped by the
+/// gas limit of the transaction looking it up, or rejected if it exceeds it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey(Transaction);
+
+// ========================================
+// Public Implementation
+// ========================================
+impl CacheKey {
+    /// The key of `tx`.
+    pub fn of(tx: &Transaction) -> Self {
+        let mut tx = tx.clone();
+        tx.set_gas_limit(None);
+        Self(tx)
+    }
+}
+
+impl From<&Transaction> for CacheKey {
+    fn from(tx: &Transaction) -> Self {
+        Self::of(tx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = CacheKey;
+
+    #[test]
+    fn ignores_gas_limit() {
+        let tx = Transaction::sample_native_token_transfer_cachable();
+        let mut other = tx.clone();
+        other.set_gas_limit(Some(Gas::from(30_000)));
+        assert_eq!(Sut::of(&tx), Sut::of(&other));
+    }
+
+    #[test]
+    fn distinguishes_other_fields() {
+        let with_nonce = |nonce: u64| {
+            TransactionBuilder::default()
+                .from(Address::with_last_byte(0xaa))
+                .nonce(nonce)
+                .build()
+                .unwrap()
+        };
+        assert_ne!(Sut::of(&with_nonce(1)), Sut::of(&with_nonce(2)));
+    }
+}
@@ -0,0 +1,24 @@
+use crate::prelude::*;
+
+/// The result of simulating a transaction as a read-only call, i.e. like
+/// `eth_call`, the data returned by it and the gas used.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Getters, CopyGetters)] // deserialize for tests
+pub struct CallOutput {
+    /// The data returned by the call, hex encoded, e.g. `"0x2a"`.
+    #[getset(get = "pub")]
+    output: Bytes,
+
+    /// The gas used by the call.
+    #[getset(get_copy = "pub")]
+    gas_used: Gas,
+}
+
+impl CallOutput {
+    /// The output of a call which returned `output` using `gas_used`.
+    pub fn new(output: impl Into<Bytes>, gas_used: impl Into<Gas>) -> Self {
+        Self {
+            output: output.into(),
+            gas_used: gas_used.into(),
+        }
+    }
+}
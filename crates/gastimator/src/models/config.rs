@@ -1,17 +1,80 @@
 use crate::prelude::*;
 
 /// Run configuration for the server
-/// requires an `alchemy_api_key` and a [`ServerConfig`] config.
-#[derive(Debug, Clone, Builder, Getters)]
+/// requires a [`ServerConfig`] config and either an `alchemy_api_key` or an
+/// `rpc_url`, and optionally a [`GastimatorConfig`].
+#[derive(Debug, Clone, Builder, Getters, CopyGetters)]
 #[builder(setter(into))]
 pub struct Config {
     #[getset(get = "pub")]
     server: ServerConfig,
 
+    /// The API key for the Alchemy API, used for remote estimates unless
+    /// `rpc_url` is set.
+    #[builder(default)]
     #[getset(get = "pub")]
-    alchemy_api_key: String,
+    alchemy_api_key: Option<String>,
+
+    /// The full URL of a JSON-RPC endpoint used for remote estimates instead
+    /// of Alchemy, e.g. `"http://my-node:8545"` for a self hosted node.
+    #[builder(default)]
+    #[getset(get = "pub")]
+    rpc_url: Option<String>,
+
+    /// An optional token sent as `Authorization: Bearer <token>` header in
+    /// requests to `rpc_url`.
+    #[builder(default)]
+    #[getset(get = "pub")]
+    rpc_bearer_token: Option<String>,
+
+    /// The strategy used to generate ids of JSON-RPC requests to the remote,
+    /// e.g. [`IdStrategy::Random`] if the provider deduplicates by id.
+    #[builder(default)]
+    #[getset(get_copy = "pub")]
+    rpc_id_strategy: IdStrategy,
+
+    /// The block transactions are estimated at by the remote, passed as
+    /// second parameter of `eth_estimateGas`, e.g. `pending` for mempool
+    /// accurate estimates or a block number for reproducible ones.
+    /// E.g. `BlockNumberOrTag::Latest`
+    #[builder(default)]
+    #[getset(get_copy = "pub")]
+    remote_block: BlockNumberOrTag,
+
+    /// The id of the chain to estimate transactions for, used both by the
+    /// local simulation and to select the remote network.
+    /// E.g. `1` for Ethereum mainnet
+    #[builder(default = "MAINNET_CHAIN_ID")]
+    #[getset(get_copy = "pub")]
+    chain_id: u64,
+
+    /// The maximum duration of a request to the remote gas estimator, after
+    /// which the remote estimate is considered failed and the local estimate
+    /// is used.
+    /// E.g. `Duration::from_secs(10)`
+    #[builder(default = "DEFAULT_REMOTE_TIMEOUT")]
+    #[getset(get_copy = "pub")]
+    remote_timeout: Duration,
+
+    /// The maximum number of concurrent requests to the remote, excess
+    /// requests wait until one completes, e.g. to stay within the compute
+    /// unit budget of Alchemy under batch load. Unlimited if `None`.
+    /// E.g. `Some(8)`
+    #[builder(default)]
+    #[getset(get_copy = "pub")]
+    max_concurrent_remote_requests: Option<usize>,
+
+    #[builder(default)]
+    #[getset(get = "pub")]
+    gastimator: GastimatorConfig,
 }
 
+/// The chain id of Ethereum mainnet.
+pub const MAINNET_CHAIN_ID: u64 = 1;
+
+/// Default maximum duration of a request to the remote gas estimator.
+pub const DEFAULT_REMOTE_TIMEOUT: Duration = Duration::from_secs(10);
+
 // ========================================
 // Public Implementation
 // ========================================
@@ -20,13 +83,349 @@ impl Config {
     pub fn address_with_port(&self) -> String {
         self.server.address_with_port()
     }
+
+    /// Checks all values of the config, returning all problems found, not
+    /// just the first, so that they can be fixed at once. Port `0` is valid,
+    /// the OS then picks a free port.
+    pub fn validate(&self) -> std::result::Result<(), Vec<ConfigError>> {
+        let mut problems = Vec::new();
+        let gastimator = &self.gastimator;
+
+        if self.server.address().trim().is_empty() {
+            problems.push(ConfigError::EmptyAddress);
+        }
+        if self
+            .server
+            .admin_token()
+            .as_ref()
+            .is_some_and(String::is_empty)
+        {
+            problems.push(ConfigError::EmptyAdminToken);
+        }
+        if let Some(endpoint) = self.server.otlp_endpoint() {
+            Self::validate_url("otlp_endpoint", endpoint, &mut problems);
+        }
+        if let AllowedOrigins::List(origins) = self.server.allowed_origins() {
+            for origin in origins {
+                Self::validate_url("allowed_origins", origin, &mut problems);
+            }
+        }
+
+        match (&self.alchemy_api_key, &self.rpc_url) {
+            (None, None) if !gastimator.mode().is_local_only() || gastimator.fork() => {
+                problems.push(ConfigError::MissingRemote)
+            }
+            (Some(key), None) if key.trim().is_empty() => {
+                problems.push(ConfigError::EmptyAlchemyApiKey)
+            }
+            _ => {}
+        }
+        if let Some(rpc_url) = &self.rpc_url {
+            Self::validate_url("rpc_url", rpc_url, &mut problems);
+        }
+
+        let zero_duration = |field: &str| ConfigError::ZeroDuration {
+            field: field.to_owned(),
+        };
+        if self.remote_timeout.is_zero() {
+            problems.push(zero_duration("remote_timeout"));
+        }
+        if self.server.request_timeout().is_zero() {
+            problems.push(zero_duration("request_timeout"));
+        }
+        if gastimator
+            .total_budget()
+            .is_some_and(|budget| budget.is_zero())
+        {
+            problems.push(zero_duration("total_budget"));
+        }
+
+        let zero_count = |field: &str| ConfigError::ZeroCount {
+            field: field.to_owned(),
+        };
+        if gastimator.worker_pool_size() == 0 {
+            problems.push(zero_count("worker_pool_size"));
+        }
+        if self.max_concurrent_remote_requests == Some(0) {
+            problems.push(zero_count("max_concurrent_remote_requests"));
+        }
+        if gastimator.local_simulation_threads() == 0 {
+            problems.push(zero_count("local_simulation_threads"));
+        }
+        if self.server.max_body_bytes() == 0 {
+            problems.push(zero_count("max_body_bytes"));
+        }
+        if self.server.max_rlp_bytes() == 0 {
+            problems.push(zero_count("max_rlp_bytes"));
+        }
+
+        if let Some(rate_limit) = self.server.rate_limit() {
+            if !rate_limit.is_valid() {
+                problems.push(ConfigError::InvalidRateLimit {
+                    per_second: rate_limit.per_second(),
+                    burst: rate_limit.burst(),
+                });
+            }
+        }
+
+        let rate = gastimator.remote_sample_rate();
+        if !(0.0..=1.0).contains(&rate) {
+            problems.push(ConfigError::InvalidRemoteSampleRate { rate });
+        }
+        let weight = gastimator.remote_weight();
+        if !(0.0..=1.0).contains(&weight) {
+            problems.push(ConfigError::InvalidRemoteWeight { weight });
+        }
+        let margin = gastimator.safety_margin();
+        if margin.is_nan() || margin < 1.0 {
+            problems.push(ConfigError::InvalidSafetyMargin { margin });
+        }
+        let factor = gastimator.gas_limit_warning_factor();
+        if factor.is_nan() || factor < 1.0 {
+            problems.push(ConfigError::InvalidGasLimitWarningFactor { factor });
+        }
+        if let Some(gas_limit) = gastimator.max_allowed_gas_limit() {
+            if *gas_limit < 21_000 {
+                problems.push(ConfigError::MaxAllowedGasLimitTooLow { gas_limit });
+            }
+        }
+        if gastimator.fork_block().is_some() && !gastimator.fork() {
+            problems.push(ConfigError::ForkBlockWithoutFork);
+        }
+        if gastimator.mode().is_remote_only() && gastimator.fork() {
+            problems.push(ConfigError::ForkWithRemoteOnly);
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+}
+
+// ========================================
+// Private Implementation
+// ========================================
+impl Config {
+    /// Pushes a [`ConfigError::InvalidUrl`] to `problems` if `url` is not a
+    /// well formed `http` or `https` URL.
+    fn validate_url(field: &str, url: &str, problems: &mut Vec<ConfigError>) {
+        let reason = match reqwest::Url::parse(url) {
+            Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => return,
+            Ok(parsed) => format!("unsupported scheme `{}`", parsed.scheme()),
+            Err(e) => e.to_string(),
+        };
+        problems.push(ConfigError::InvalidUrl {
+            field: field.to_owned(),
+            url: url.to_owned(),
+            reason,
+        });
+    }
+}
+
+impl From<Vec<ConfigError>> for Error {
+    fn from(problems: Vec<ConfigError>) -> Self {
+        Error::InvalidConfig { problems }
+    }
 }
 
-/// Tries to read the Alchemy API key from the environment variable `ALCHEMY_API_KEY`,
+/// Tries to read the Alchemy API key from the file at the path in the
+/// environment variable `ALCHEMY_API_KEY_FILE`, e.g. a mounted Docker or
+/// Kubernetes secret, else from the environment variable `ALCHEMY_API_KEY`,
 /// as a String.
 ///
 /// # Throws
-/// Throws [`Error::NoAlchemyApiKey`] if the environment variable is not set.
+/// Throws [`Error::NoAlchemyApiKey`] if neither environment variable is set,
+/// see [`read_alchemy_api_key_file`] for errors reading the file.
 pub fn read_alchemy_api_key() -> Result<String> {
-    std::env::var("ALCHEMY_API_KEY").map_err(|_| Error::NoAlchemyApiKey)
+    read_optional_alchemy_api_key()?.ok_or(Error::NoAlchemyApiKey)
+}
+
+/// Like [`read_alchemy_api_key`], but `None` if neither environment
+/// variable is set.
+///
+/// # Throws
+/// See [`read_alchemy_api_key_file`].
+pub fn read_optional_alchemy_api_key() -> Result<Option<String>> {
+    if let Ok(path) = std::env::var("ALCHEMY_API_KEY_FILE") {
+        return read_alchemy_api_key_file(path).map(Some);
+    }
+    Ok(std::env::var("ALCHEMY_API_KEY").ok())
+}
+
+/// Reads the Alchemy API key from the file at `path`, trimming whitespace,
+/// e.g. the trailing newline.
+///
+/// # Throws
+/// Throws [`Error::ReadAlchemyApiKeyFile`] if the file cannot be read or
+/// is blank.
+pub fn read_alchemy_api_key_file(path: impl AsRef<std::path::Path>) -> Result<String> {
+    let path = path.as_ref();
+    let error = |underlying: String| Error::ReadAlchemyApiKeyFile {
+        path: path.display().to_string(),
+        underlying,
+    };
+    let key = std::fs::read_to_string(path).map_err(|e| error(e.to_string()))?;
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(error("file is empty".to_owned()));
+    }
+    Ok(key.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server_config() -> ServerConfigBuilder {
+        ServerConfigBuilder::default()
+            .address("127.0.0.1")
+            .port(0u16)
+            .clone()
+    }
+
+    #[test]
+    fn valid_config() {
+        let sut = ConfigBuilder::default()
+            .server(server_config().build().unwrap())
+            .alchemy_api_key("key".to_owned())
+            .build()
+            .unwrap();
+        assert_eq!(sut.validate(), Ok(()));
+    }
+
+    #[test]
+    fn local_only_does_not_need_remote() {
+        let sut = ConfigBuilder::default()
+            .server(server_config().build().unwrap())
+            .gastimator(
+                GastimatorConfigBuilder::default()
+                    .mode(GastimatorMode::LocalOnly)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        assert_eq!(sut.validate(), Ok(()));
+    }
+
+    #[test]
+    fn all_problems_are_reported() {
+        let sut = ConfigBuilder::default()
+            .server(
+                server_config()
+                    .address("")
+                    .otlp_endpoint("localhost:4318".to_owned())
+                    .allowed_origins(AllowedOrigins::new(["app.example.com"]))
+                    .build()
+                    .unwrap(),
+            )
+            .rpc_url("not a url".to_owned())
+            .remote_timeout(Duration::ZERO)
+            .max_concurrent_remote_requests(0usize)
+            .gastimator(
+                GastimatorConfigBuilder::default()
+                    .worker_pool_size(0usize)
+                    .remote_sample_rate(1.5)
+                    .remote_weight(-0.5)
+                    .safety_margin(0.9)
+                    .gas_limit_warning_factor(0.5)
+                    .fork_block(1u64)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+
+        let problems = sut.validate().unwrap_err();
+
+        assert_eq!(
+            problems,
+            vec![
+                ConfigError::EmptyAddress,
+                ConfigError::InvalidUrl {
+                    field: "otlp_endpoint".to_owned(),
+                    url: "localhost:4318".to_owned(),
+                    reason: "unsupported scheme `localhost`".to_owned(),
+                },
+                ConfigError::InvalidUrl {
+                    field: "allowed_origins".to_owned(),
+                    url: "app.example.com".to_owned(),
+                    reason: "relative URL without a base".to_owned(),
+                },
+                ConfigError::InvalidUrl {
+                    field: "rpc_url".to_owned(),
+                    url: "not a url".to_owned(),
+                    reason: "relative URL without a base".to_owned(),
+                },
+                ConfigError::ZeroDuration {
+                    field: "remote_timeout".to_owned()
+                },
+                ConfigError::ZeroCount {
+                    field: "worker_pool_size".to_owned()
+                },
+                ConfigError::ZeroCount {
+                    field: "max_concurrent_remote_requests".to_owned()
+                },
+                ConfigError::InvalidRemoteSampleRate { rate: 1.5 },
+                ConfigError::InvalidRemoteWeight { weight: -0.5 },
+                ConfigError::InvalidSafetyMargin { margin: 0.9 },
+                ConfigError::InvalidGasLimitWarningFactor { factor: 0.5 },
+                ConfigError::ForkBlockWithoutFork,
+            ]
+        );
+    }
+
+    #[test]
+    fn remote_only_excludes_fork() {
+        let sut = ConfigBuilder::default()
+            .server(server_config().build().unwrap())
+            .rpc_url("http://localhost:8545".to_owned())
+            .gastimator(
+                GastimatorConfigBuilder::default()
+                    .mode(GastimatorMode::RemoteOnly)
+                    .fork(true)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        assert_eq!(sut.validate(), Err(vec![ConfigError::ForkWithRemoteOnly]));
+    }
+
+    #[test]
+    fn missing_remote() {
+        let sut = ConfigBuilder::default()
+            .server(server_config().build().unwrap())
+            .build()
+            .unwrap();
+        assert_eq!(sut.validate(), Err(vec![ConfigError::MissingRemote]));
+    }
+
+    #[test]
+    fn invalid_config_error_lists_all_problems() {
+        let error = Error::from(vec![
+            ConfigError::EmptyAddress,
+            ConfigError::ForkBlockWithoutFork,
+        ]);
+        assert_eq!(
+            error.to_string(),
+            "Invalid config: Server address must not be empty, Fork block is set, but forking is disabled"
+        );
+    }
+
+    #[test]
+    fn alchemy_api_key_file_is_trimmed() {
+        let path = std::env::temp_dir().join(format!("alchemy-key-{}", rand::random::<u64>()));
+        std::fs::write(&path, "  secret\n").unwrap();
+        assert_eq!(read_alchemy_api_key_file(&path).unwrap(), "secret");
+
+        std::fs::write(&path, "\n").unwrap();
+        assert!(
+            read_alchemy_api_key_file(&path)
+                .unwrap_err()
+                .is_read_alchemy_api_key_file()
+        );
+    }
 }
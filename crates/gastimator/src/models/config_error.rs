@@ -0,0 +1,76 @@
+use crate::prelude::*;
+
+/// A problem with a value of a [`Config`], found by [`Config::validate`].
+#[derive(Debug, Clone, ThisError, PartialEq, Serialize)]
+pub enum ConfigError {
+    /// The address of the server is empty
+    #[error("Server address must not be empty")]
+    EmptyAddress,
+
+    /// Neither an Alchemy API key nor an RPC URL is configured, but a
+    /// remote is needed, i.e. not local only, or forking
+    #[error(
+        "Either an Alchemy API key or an RPC URL is required, unless local only and not forking"
+    )]
+    MissingRemote,
+
+    /// The Alchemy API key is set but empty
+    #[error("Alchemy API key must not be empty")]
+    EmptyAlchemyApiKey,
+
+    /// The admin token is set but empty, which would guard nothing
+    #[error("Admin token must not be empty")]
+    EmptyAdminToken,
+
+    /// A URL, e.g. the RPC URL, is not a well formed `http(s)` URL
+    #[error("Invalid `{field}` URL `{url}`: {reason}")]
+    InvalidUrl {
+        field: String,
+        url: String,
+        reason: String,
+    },
+
+    /// A duration which must be positive is zero
+    #[error("`{field}` must be positive")]
+    ZeroDuration { field: String },
+
+    /// A count which must be positive is zero
+    #[error("`{field}` must be positive")]
+    ZeroCount { field: String },
+
+    /// The remote sample rate is not between `0.0` and `1.0`
+    #[error("Remote sample rate must be between 0.0 and 1.0, got: {rate}")]
+    InvalidRemoteSampleRate { rate: f64 },
+
+    /// The gas limit warning factor is less than `1.0`, warning about gas
+    /// limits below the estimate
+    #[error("Gas limit warning factor must be at least 1.0, got: {factor}")]
+    InvalidGasLimitWarningFactor { factor: f64 },
+
+    /// The weight of the remote estimate is not between `0.0` and `1.0`
+    #[error("Remote weight must be between 0.0 and 1.0, got: {weight}")]
+    InvalidRemoteWeight { weight: f64 },
+
+    /// The safety margin is less than `1.0`, which would lower estimates
+    #[error("Safety margin must be at least 1.0, got: {margin}")]
+    InvalidSafetyMargin { margin: f64 },
+
+    /// The rate limit allows no requests, i.e. its rate is not positive or
+    /// its burst is zero
+    #[error("Rate limit must have a positive rate and burst, got: {per_second}/s, burst {burst}")]
+    InvalidRateLimit { per_second: f64, burst: u32 },
+
+    /// The max allowed gas limit is below the `21000` needed by any
+    /// transaction
+    #[error("Max allowed gas limit must be at least 21000, got: {gas_limit}")]
+    MaxAllowedGasLimitTooLow { gas_limit: Gas },
+
+    /// A block to fork at is set, but forking is disabled
+    #[error("Fork block is set, but forking is disabled")]
+    ForkBlockWithoutFork,
+
+    /// Forking is enabled, but remote only, so nothing is simulated
+    /// against the fork
+    #[error("Forking requires local simulation, but remote only is set")]
+    ForkWithRemoteOnly,
+}
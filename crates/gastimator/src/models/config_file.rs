@@ -0,0 +1,231 @@
+use std::path::Path;
+
+use crate::prelude::*;
+
+/// The contents of a JSON config file, e.g. passed with `--config`, each
+/// value is optional, missing values are taken from CLI flags or defaults.
+///
+/// ```json
+/// {
+///     "port": 8080,
+///     "network": "sepolia",
+///     "remote_timeout_ms": 5000,
+///     "cache_ttl_secs": 30
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Getters, CopyGetters)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    /// The address of the server, e.g. `"0.0.0.0"`.
+    #[getset(get = "pub")]
+    address: Option<String>,
+
+    /// The port of the server, e.g. `3000`.
+    #[getset(get_copy = "pub")]
+    port: Option<u16>,
+
+    /// The API key for the Alchemy API.
+    #[getset(get = "pub")]
+    alchemy_api_key: Option<String>,
+
+    /// The full URL of a JSON-RPC endpoint used instead of Alchemy.
+    #[getset(get = "pub")]
+    rpc_url: Option<String>,
+
+    /// A token sent as `Authorization: Bearer <token>` header to `rpc_url`.
+    #[getset(get = "pub")]
+    rpc_bearer_token: Option<String>,
+
+    /// The id of the chain, mutually exclusive with `network`.
+    #[getset(get_copy = "pub")]
+    chain_id: Option<u64>,
+
+    /// The network by name, e.g. `"sepolia"`, mutually exclusive with
+    /// `chain_id`.
+    #[getset(get_copy = "pub")]
+    network: Option<AlchemyNetwork>,
+
+    /// The timeout in milliseconds of requests to the remote gas estimator.
+    #[getset(get_copy = "pub")]
+    remote_timeout_ms: Option<u64>,
+
+    /// The timeout in milliseconds of handling a request to the server.
+    #[getset(get_copy = "pub")]
+    request_timeout_ms: Option<u64>,
+
+    /// The time to live in seconds of cached estimates.
+    #[getset(get_copy = "pub")]
+    cache_ttl_secs: Option<u64>,
+
+    /// The token required by admin endpoints.
+    #[getset(get = "pub")]
+    admin_token: Option<String>,
+
+    /// The origins browsers are allowed to call the server from, `"*"` for
+    /// any.
+    #[getset(get = "pub")]
+    allowed_origins: Option<Vec<String>>,
+}
+
+// ========================================
+// Public Implementation
+// ========================================
+impl ConfigFile {
+    /// Reads and parses the JSON config file at `path`.
+    ///
+    /// # Throws
+    /// Throws [`Error::ReadConfigFile`] if the file cannot be read and
+    /// [`Error::InvalidConfigFile`] if it is not valid JSON, has unknown
+    /// keys or sets both `chain_id` and `network`.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let invalid = |underlying: String| Error::InvalidConfigFile {
+            path: path.display().to_string(),
+            underlying,
+        };
+        let json = std::fs::read_to_string(path).map_err(|e| Error::ReadConfigFile {
+            path: path.display().to_string(),
+            underlying: e.to_string(),
+        })?;
+        let file = serde_json::from_str::<Self>(&json).map_err(|e| invalid(e.to_string()))?;
+        if file.chain_id.is_some() && file.network.is_some() {
+            return Err(invalid(
+                "`chain_id` and `network` are mutually exclusive".to_owned(),
+            ));
+        }
+        Ok(file)
+    }
+
+    /// The chain id, either `chain_id` or that of `network`, if any.
+    pub fn resolved_chain_id(&self) -> Option<u64> {
+        self.chain_id
+            .or_else(|| self.network.map(|network| network.chain_id()))
+    }
+}
+
+impl Config {
+    /// Reads the JSON config file at `path`, see [`ConfigFile`], using
+    /// defaults for missing values, e.g. port `3000`. The Alchemy API key is
+    /// read from the `ALCHEMY_API_KEY` environment variable if not in the file.
+    ///
+    /// # Throws
+    /// Throws [`Error::ReadConfigFile`] or [`Error::InvalidConfigFile`], see
+    /// [`ConfigFile::from_file`], or [`Error::ReadAlchemyApiKeyFile`], see
+    /// [`read_optional_alchemy_api_key`].
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        Self::try_from(ConfigFile::from_file(path)?)
+    }
+}
+
+impl TryFrom<ConfigFile> for Config {
+    type Error = Error;
+
+    fn try_from(file: ConfigFile) -> Result<Self> {
+        let mut server = ServerConfigBuilder::default();
+        server
+            .address(
+                file.address
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_ADDRESS.to_owned()),
+            )
+            .port(file.port.unwrap_or(DEFAULT_PORT))
+            .admin_token(file.admin_token.clone());
+        if let Some(timeout) = file.request_timeout_ms {
+            server.request_timeout(Duration::from_millis(timeout));
+        }
+        if let Some(origins) = file.allowed_origins.clone() {
+            server.allowed_origins(AllowedOrigins::new(origins));
+        }
+
+        let mut gastimator = GastimatorConfigBuilder::default();
+        if let Some(ttl) = file.cache_ttl_secs {
+            gastimator.cache_ttl(Duration::from_secs(ttl));
+        }
+
+        let mut config = ConfigBuilder::default();
+        config
+            .server(server.build().unwrap())
+            .alchemy_api_key(match file.alchemy_api_key.clone() {
+                Some(key) => Some(key),
+                None => read_optional_alchemy_api_key()?,
+            })
+            .rpc_url(file.rpc_url.clone())
+            .rpc_bearer_token(file.rpc_bearer_token.clone())
+            .gastimator(gastimator.build().unwrap());
+        if let Some(chain_id) = file.resolved_chain_id() {
+            config.chain_id(chain_id);
+        }
+        if let Some(timeout) = file.remote_timeout_ms {
+            config.remote_timeout(Duration::from_millis(timeout));
+        }
+        Ok(config.build().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = ConfigFile;
+
+    /// Writes `json` to a new file in the temp dir, returning its path.
+    fn write_temp(name: &str, json: &str) -> std::path::PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("gastimator-{name}-{}.json", rand::random::<u64>()));
+        std::fs::write(&path, json).unwrap();
+        path
+    }
+
+    #[test]
+    fn config_from_file() {
+        let path = write_temp(
+            "config",
+            r#"{
+                "port": 8080,
+                "alchemy_api_key": "key",
+                "network": "sepolia",
+                "remote_timeout_ms": 5000,
+                "request_timeout_ms": 7000,
+                "cache_ttl_secs": 30
+            }"#,
+        );
+
+        let config = Config::from_file(&path).unwrap();
+
+        assert_eq!(config.server().address(), DEFAULT_ADDRESS);
+        assert_eq!(config.server().port(), 8080);
+        assert_eq!(config.alchemy_api_key().as_deref(), Some("key"));
+        assert_eq!(config.chain_id(), 11155111);
+        assert_eq!(config.remote_timeout(), Duration::from_millis(5000));
+        assert_eq!(
+            config.server().request_timeout(),
+            Duration::from_millis(7000)
+        );
+        assert_eq!(config.gastimator().cache_ttl(), Duration::from_secs(30));
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn empty_file_is_default() {
+        let path = write_temp("empty", "{}");
+        assert_eq!(Sut::from_file(path).unwrap(), Sut::default());
+    }
+
+    #[test]
+    fn unknown_key_is_invalid() {
+        let path = write_temp("unknown", r#"{"prot": 8080}"#);
+        assert!(Sut::from_file(path).unwrap_err().is_invalid_config_file());
+    }
+
+    #[test]
+    fn chain_id_and_network_are_exclusive() {
+        let path = write_temp("exclusive", r#"{"chain_id": 1, "network": "base"}"#);
+        assert!(Sut::from_file(path).unwrap_err().is_invalid_config_file());
+    }
+
+    #[test]
+    fn missing_file() {
+        let res = Sut::from_file("/does/not/exist.json");
+        assert!(res.unwrap_err().is_read_config_file());
+    }
+}
@@ -0,0 +1,92 @@
+use crate::prelude::*;
+
+/// A contract deployment to estimate, the creation bytecode and the ABI
+/// encoded constructor arguments, e.g.
+/// `{"bytecode": "0x6080...", "constructor_args": "0x...", "value": "0x0"}`,
+/// built into a [`TxKind::Create`] transaction with `bytecode ++ args` as
+/// input.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Getters)]
+pub struct DeployRequest {
+    /// The creation (init) bytecode of the contract, e.g. as output by
+    /// `solc --bin`.
+    #[getset(get = "pub")]
+    bytecode: Bytes,
+
+    /// The ABI encoded arguments of the constructor, appended to `bytecode`,
+    /// empty if the constructor takes none.
+    #[serde(default)]
+    #[getset(get = "pub")]
+    constructor_args: Bytes,
+
+    /// The amount of ETH (in wei) sent to a payable constructor.
+    #[serde(default)]
+    #[getset(get = "pub")]
+    value: U256,
+}
+
+impl DeployRequest {
+    /// A deployment of `bytecode` with `constructor_args` sending `value`.
+    pub fn new(
+        bytecode: impl Into<Bytes>,
+        constructor_args: impl Into<Bytes>,
+        value: impl Into<U256>,
+    ) -> Self {
+        Self {
+            bytecode: bytecode.into(),
+            constructor_args: constructor_args.into(),
+            value: value.into(),
+        }
+    }
+}
+
+impl TryFrom<DeployRequest> for Transaction {
+    type Error = Error;
+
+    /// The contract creation of `request`, with `bytecode ++ constructor_args`
+    /// as input.
+    ///
+    /// # Throws
+    /// Throws [`Error::InvalidTransaction`] if `bytecode` is empty.
+    fn try_from(request: DeployRequest) -> Result<Self> {
+        if request.bytecode.is_empty() {
+            return Err(Error::InvalidTransaction {
+                reason: "Bytecode of deployment is empty".to_owned(),
+            });
+        }
+        let input = [request.bytecode, request.constructor_args].concat();
+        Ok(TransactionBuilder::default()
+            .to(TxKind::Create)
+            .input(Bytes::from(input))
+            .value(request.value)
+            .build()
+            .unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = DeployRequest;
+
+    #[test]
+    fn input_is_bytecode_and_args() {
+        let sut = Sut::new([0x60, 0x80], [0x2a], U256::from(7));
+
+        let tx = Transaction::try_from(sut).unwrap();
+
+        assert_eq!(tx.kind(), TransactionKind::ContractCreation);
+        assert_eq!(tx.input(), &Bytes::from([0x60, 0x80, 0x2a]));
+        assert_eq!(tx.value(), &U256::from(7));
+    }
+
+    #[test]
+    fn empty_bytecode_is_invalid() {
+        let sut = Sut::new(Bytes::new(), [0x2a], U256::ZERO);
+        assert!(
+            Transaction::try_from(sut)
+                .unwrap_err()
+                .is_invalid_transaction()
+        );
+    }
+}
@@ -1,11 +1,12 @@
-use axum::response::IntoResponse;
+use axum::{Json, http::StatusCode, response::IntoResponse};
 use derive_more::IsVariant;
+use serde_json::Value;
 
 use crate::prelude::*;
 
 /// All errors which can occur during the estimation
 /// of gas cost for an Ethereum transaction.
-#[derive(Debug, ThisError, IsVariant, PartialEq)]
+#[derive(Debug, Clone, ThisError, IsVariant, PartialEq, Serialize)]
 pub enum Error {
     /// Gas usage of transaction exceeds specifed gas limit
     #[error("Gas exceeds limit")]
@@ -18,10 +19,82 @@ pub enum Error {
     #[error("String not hex: {bad_value}")]
     StringNotHex { bad_value: String },
 
+    /// The signer of a signed transaction cannot be recovered from its
+    /// signature
+    #[error("Invalid signature, cannot recover the signer: {underlying}")]
+    InvalidSignature { underlying: String },
+
+    /// Unknown name of a strategy for ids of JSON-RPC requests
+    #[error("Unknown id strategy: {bad_value}")]
+    UnknownIdStrategy { bad_value: String },
+
+    /// Unknown name of a format of log lines
+    #[error("Unknown log format: {bad_value}")]
+    UnknownLogFormat { bad_value: String },
+
+    /// Unknown name of a policy for the recommended gas of a range
+    #[error("Unknown estimate policy: {bad_value}")]
+    UnknownEstimatePolicy { bad_value: String },
+
+    /// Unknown name of a network of the Alchemy API
+    #[error("Unknown Alchemy network: {bad_value}")]
+    UnknownAlchemyNetwork { bad_value: String },
+
+    /// Unknown name of an optional route of the REST server
+    #[error("Unknown route: {bad_value}")]
+    UnknownRoute { bad_value: String },
+
+    /// The config has one or more invalid values, see [`Config::validate`]
+    #[error("Invalid config: {}", problems.iter().map(ToString::to_string).collect::<Vec<_>>().join(", "))]
+    InvalidConfig { problems: Vec<ConfigError> },
+
+    /// Failed to read the Alchemy API key from a file
+    #[error("Failed to read Alchemy API key file '{path}': {underlying}")]
+    ReadAlchemyApiKeyFile { path: String, underlying: String },
+
+    /// Failed to read the config file
+    #[error("Failed to read config file '{path}': {underlying}")]
+    ReadConfigFile { path: String, underlying: String },
+
+    /// The config file is not valid, e.g. not JSON or with unknown keys
+    #[error("Invalid config file '{path}': {underlying}")]
+    InvalidConfigFile { path: String, underlying: String },
+
     /// Failed to parse CLI arguments from clap
     #[error("Failed to parse CLI arguments: {underlying}")]
     FailedParseCliArgs { underlying: String },
 
+    /// Transaction has no `to`, `input` nor `value`, so it cannot be
+    /// meaningfully estimated
+    #[error("Empty transaction, without `to`, `input` and `value`, cannot be estimated")]
+    EmptyTransaction,
+
+    /// The combination of fields of the transaction is impossible, e.g. a
+    /// contract creation without init code
+    #[error("Invalid transaction: {reason}")]
+    InvalidTransaction { reason: String },
+
+    /// A line of a newline-delimited JSON request body could not be read or
+    /// is not a valid transaction
+    #[error("Invalid line {line} of NDJSON body: {underlying}")]
+    InvalidNdjsonLine { line: usize, underlying: String },
+
+    /// A message received on a WebSocket is not a valid transaction
+    #[error("Invalid WebSocket message: {underlying}")]
+    InvalidWebSocketMessage { underlying: String },
+
+    /// The precomputed access list of a transaction is invalid
+    #[error("Invalid precomputed access list: {reason}")]
+    InvalidAccessList { reason: String },
+
+    /// The precomputed access list of a transaction has more entries than allowed
+    #[error("Access list too large, {entries} entries, max is {max}")]
+    AccessListTooLarge { entries: usize, max: usize },
+
+    /// The gas limit of a transaction is higher than allowed
+    #[error("Gas limit too high, {gas_limit}, max is {max}")]
+    GasLimitTooHigh { gas_limit: Gas, max: Gas },
+
     /// Unable to acquire cache lock
     #[error("Unable to acquire cache lock")]
     UnableToAcquireCacheLock,
@@ -42,24 +115,88 @@ pub enum Error {
     #[error("Local TX simulation failed: {0}")]
     LocalSimulationFailed(String),
 
+    /// Local simulation of the transaction reverted, e.g. with reason
+    /// `"insufficient allowance"`
+    #[error("Execution reverted: {reason}")]
+    SimulationReverted { reason: String, gas_used: Gas },
+
+    /// Local simulation of the transaction halted, e.g. out of gas,
+    /// consuming all gas
+    #[error("Execution halted: {reason}")]
+    SimulationHalted { reason: String, gas_used: Gas },
+
+    /// Local simulation of the transaction at `index` of a bundle failed
+    #[error("Transaction {index} of bundle failed: {underlying}")]
+    BundleTransactionFailed {
+        index: usize,
+        underlying: Box<Error>,
+    },
+
+    /// The `phase` of an estimate did not complete in time, e.g. the remote
+    /// did not respond within the remote timeout
+    #[error("The {phase} estimate timed out")]
+    Timeout { phase: EstimatePhase },
+
     /// Remote gas estimate failed
     #[error("Remote gas estimate failed: {0}")]
     RemoteGasEstimateFailed(String),
 
+    /// The total budget of an estimate was exhausted before any estimate
+    /// was available
+    #[error("Total budget of {budget_millis} ms exhausted before any estimate was available")]
+    BudgetExhausted { budget_millis: u128 },
+
+    /// The client sent more requests than allowed by the rate limit
+    #[error("Rate limit exceeded, retry later")]
+    RateLimited,
+
+    /// The chain has no transaction with the hash
+    #[error("Transaction not found: {hash}")]
+    TransactionNotFound { hash: B256 },
+
+    /// Transactions cannot be looked up by hash without a remote, i.e.
+    /// neither an Alchemy API key nor an RPC URL was configured
+    #[error("Looking up transactions by hash requires an Alchemy API key or RPC URL")]
+    NoTransactionSource,
+
+    /// The OTLP endpoint to export metrics to is not a valid URL
+    #[error("Invalid OTLP endpoint: {underlying}")]
+    InvalidOtlpEndpoint { underlying: String },
+
+    /// Failed to build the exporter of metrics to the OTLP endpoint
+    #[error("Failed to export metrics over OTLP: {underlying}")]
+    OtlpExportFailed { underlying: String },
+
     /// Unable to get address of bound socket
     #[error("Failed to get bound address: {0}")]
     UnableToGetBoundAddress(String),
 
+    /// Missing or wrong admin token for an admin endpoint
+    #[error("Unauthorized")]
+    Unauthorized,
+
+    /// An idempotency key was reused for another request to the same route
+    #[error("Idempotency key `{key}` was already used for another request")]
+    IdempotencyKeyReused { key: String },
+
     /// Failed to signal readiness
     #[error("Failed to signal readiness")]
     FailedToSignalReadiness,
 
     /// No Alchemy API key provided
     #[error(
-        "No Alchemy API Key provided, unable to start server. Set the `ALCHEMY_API_KEY` environment variable, e.g. `export ALCHEMY_API_KEY=your_key`, or export it in an `.envrc.secrets` file (`.envrc` already tries to load it with `direnv`)."
+        "No Alchemy API Key or RPC URL provided, unable to start server. Pass `--rpc-url` to use your own node, or set the `ALCHEMY_API_KEY` environment variable, e.g. `export ALCHEMY_API_KEY=your_key`, or export it in an `.envrc.secrets` file (`.envrc` already tries to load it with `direnv`)."
     )]
     NoAlchemyApiKey,
 
+    /// Alchemy does not support the chain
+    #[error("Alchemy does not support chain with id: {chain_id}")]
+    UnsupportedAlchemyChain { chain_id: u64 },
+
+    /// Failed to read state of the chain to fork for local simulation
+    #[error("Fork state unavailable, underlying error: `{underlying}`")]
+    ForkStateUnavailable { underlying: String },
+
     /// Failed to send Alchemy RPC request
     #[error("Failed to make alchemy request, method: `{method}`")]
     AlchemySendRequest { method: String },
@@ -68,18 +205,20 @@ pub enum Error {
     #[error("Failed to read Alchemy response Bytes, underlying error: `{underlying}`")]
     AlchemyReadBytesOfResponse { underlying: String },
 
+    /// The remote responded with a body which is not JSON, e.g. an HTML
+    /// error page during an outage, as opposed to a JSON-RPC error
+    #[error("Remote responded with status {status} and a non JSON body: `{body}`")]
+    RemoteNonJsonResponse { status: u16, body: String },
+
+    /// The remote responded with a JSON-RPC error object which is not mapped
+    /// to a more specific error, e.g. `-32602` for invalid params
+    #[error("Remote responded with JSON-RPC error {code}: `{message}`")]
+    RemoteRpcError { code: i64, message: String },
+
     /// Failed to parse Alchemy response to some generic type
     #[error("Failed to parse Alchemy response to type `{kind}`, underlying error: `{underlying}`")]
     AlchemyParseToResponseToType { kind: String, underlying: String },
 
-    /// Failed to parse Alchemy String response as u32
-    #[error("Failed to parse Alchemy String response as u32")]
-    AlchemyParseAsU32,
-
-    /// Failed to parse Alchemy String response as Bytes
-    #[error("Failed to parse Alchemy String response as Bytes")]
-    AlchemyParseAsBytes,
-
     /// Failed to cast a UIn256 to u64, would not fit
     #[error("UInt256 larger than u64")]
     UInt256LargerThanU64,
@@ -95,6 +234,24 @@ pub enum Error {
         "Failed to RLP decode bytes into a Signed EIP1559 transaction, underlying error: `{underlying}`"
     )]
     DecodeRlpFailedBytesIntoSignedEip1559Tx { underlying: String },
+
+    /// Failed to RLP decode bytes into EIP4844 transaction
+    #[error(
+        "Failed to RLP decode bytes into EIP4844 transaction, underlying error: `{underlying}`"
+    )]
+    DecodeRlpFailedBytesIntoEip4844Tx { underlying: String },
+
+    /// Failed to RLP decode bytes into a Signed EIP4844 transaction
+    #[error(
+        "Failed to RLP decode bytes into a Signed EIP4844 transaction, underlying error: `{underlying}`"
+    )]
+    DecodeRlpFailedBytesIntoSignedEip4844Tx { underlying: String },
+
+    /// The RLP encoded transaction is of an unsupported EIP-2718 type
+    #[error(
+        "Unsupported transaction type `{type_byte:#04x}`, only EIP-1559 (`0x02`) and EIP-4844 (`0x03`) transactions are supported, legacy transactions have type `0x00`"
+    )]
+    UnsupportedTransactionType { type_byte: u8 },
 }
 
 // ========================================
@@ -106,7 +263,13 @@ impl Error {
     }
 
     pub fn remote_gas_estimate_failed(e: impl std::fmt::Display) -> Self {
-        Error::LocalSimulationFailed(e.to_string())
+        Error::RemoteGasEstimateFailed(e.to_string())
+    }
+
+    pub fn fork_state_unavailable(e: impl std::fmt::Display) -> Self {
+        Error::ForkStateUnavailable {
+            underlying: e.to_string(),
+        }
     }
 
     pub fn alchemy_read_bytes_of_response(e: impl std::fmt::Display) -> Self {
@@ -115,6 +278,15 @@ impl Error {
         }
     }
 
+    /// A [`Error::RemoteNonJsonResponse`] with `status` and `body`, the body
+    /// truncated to at most 256 characters.
+    pub fn remote_non_json_response(status: u16, body: &str) -> Self {
+        Error::RemoteNonJsonResponse {
+            status,
+            body: body.chars().take(256).collect(),
+        }
+    }
+
     pub fn decode_rlp_decode_bytes_into_eip1559(e: impl std::fmt::Display) -> Self {
         Error::DecodeRlpFailedBytesIntoEip1559Tx {
             underlying: e.to_string(),
@@ -127,6 +299,18 @@ impl Error {
         }
     }
 
+    pub fn decode_rlp_decode_bytes_into_eip4844(e: impl std::fmt::Display) -> Self {
+        Error::DecodeRlpFailedBytesIntoEip4844Tx {
+            underlying: e.to_string(),
+        }
+    }
+
+    pub fn decode_rlp_decode_bytes_into_signed_eip4844(e: impl std::fmt::Display) -> Self {
+        Error::DecodeRlpFailedBytesIntoSignedEip4844Tx {
+            underlying: e.to_string(),
+        }
+    }
+
     pub fn start(e: std::io::Error) -> Self {
         Error::UnableToStartServer {
             underlying: e.to_string(),
@@ -142,13 +326,175 @@ impl Error {
     }
 }
 
+/// The JSON body of an error response of the REST server, e.g.
+/// `{"error": "GasExceedsLimit", "message": "Gas exceeds limit", "details": {"estimated_cost": 21000, "gas_limit": 10}}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Getters)]
+pub struct ErrorResponse {
+    /// The name of the [`Error`] variant, e.g. `"GasExceedsLimit"`.
+    #[getset(get = "pub")]
+    error: String,
+
+    /// Human readable description of the error.
+    #[getset(get = "pub")]
+    message: String,
+
+    /// The fields of the error, if any, else an empty object.
+    #[getset(get = "pub")]
+    details: Value,
+}
+
+impl From<&Error> for ErrorResponse {
+    fn from(error: &Error) -> Self {
+        // Externally tagged, i.e. `"Variant"` or `{"Variant": fields}`.
+        let (name, details) = match serde_json::to_value(error) {
+            Ok(Value::Object(tagged)) => tagged.into_iter().next().unwrap_or_default(),
+            Ok(Value::String(name)) => (name, Value::Null),
+            _ => Default::default(),
+        };
+        Self {
+            error: name,
+            message: error.to_string(),
+            // Fields of tuple variants are already part of the message.
+            details: if details.is_object() {
+                details
+            } else {
+                Value::Object(Default::default())
+            },
+        }
+    }
+}
+
+impl Error {
+    /// The HTTP status code of the error, `4xx` for invalid requests or if
+    /// the request timed out, `502`
+    /// if the remote failed, `504` if the budget was exhausted or a phase of
+    /// the estimate timed out, `501` if
+    /// transactions cannot be looked up by hash, else `500`.
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            Error::StringNotHex { .. }
+            | Error::EmptyTransaction
+            | Error::InvalidTransaction { .. }
+            | Error::InvalidNdjsonLine { .. }
+            | Error::InvalidWebSocketMessage { .. }
+            | Error::InvalidAccessList { .. }
+            | Error::AccessListTooLarge { .. }
+            | Error::GasLimitTooHigh { .. }
+            | Error::DecodeRlpFailedBytesIntoEip1559Tx { .. }
+            | Error::DecodeRlpFailedBytesIntoSignedEip1559Tx { .. }
+            | Error::DecodeRlpFailedBytesIntoEip4844Tx { .. }
+            | Error::DecodeRlpFailedBytesIntoSignedEip4844Tx { .. }
+            | Error::UnsupportedTransactionType { .. }
+            | Error::InvalidSignature { .. }
+            | Error::SimulationReverted { .. }
+            | Error::SimulationHalted { .. } => StatusCode::BAD_REQUEST,
+            Error::Unauthorized => StatusCode::UNAUTHORIZED,
+            Error::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            Error::TransactionNotFound { .. } => StatusCode::NOT_FOUND,
+            Error::NoTransactionSource => StatusCode::NOT_IMPLEMENTED,
+            Error::GasExceedsLimit { .. } | Error::IdempotencyKeyReused { .. } => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            Error::RemoteGasEstimateFailed(_)
+            | Error::RemoteNonJsonResponse { .. }
+            | Error::RemoteRpcError { .. }
+            | Error::ForkStateUnavailable { .. }
+            | Error::AlchemySendRequest { .. }
+            | Error::AlchemyReadBytesOfResponse { .. }
+            | Error::AlchemyParseToResponseToType { .. } => StatusCode::BAD_GATEWAY,
+            Error::BudgetExhausted { .. } | Error::Timeout { .. } => StatusCode::GATEWAY_TIMEOUT,
+            Error::BundleTransactionFailed { underlying, .. } => underlying.status_code(),
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
 impl IntoResponse for Error {
     fn into_response(self) -> axum::response::Response {
-        axum::response::Response::builder()
-            .status(500)
-            .body(format!("{:?}", self).into())
-            .unwrap()
+        (self.status_code(), Json(ErrorResponse::from(&self))).into_response()
     }
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    type Sut = ErrorResponse;
+
+    #[test]
+    fn struct_variant_has_fields_as_details() {
+        let sut = Sut::from(&Error::GasExceedsLimit {
+            estimated_cost: Some(Gas::from(21_000)),
+            gas_limit: Gas::from(10),
+        });
+        assert_eq!(
+            serde_json::to_value(&sut).unwrap(),
+            json!({
+                "error": "GasExceedsLimit",
+                "message": "Gas exceeds limit",
+                "details": { "estimated_cost": 21000, "gas_limit": 10 }
+            })
+        );
+    }
+
+    #[test]
+    fn unit_and_tuple_variants_have_empty_details() {
+        let sut = Sut::from(&Error::EmptyTransaction);
+        assert_eq!(sut.error(), "EmptyTransaction");
+        assert_eq!(sut.details(), &json!({}));
+
+        let sut = Sut::from(&Error::RemoteGasEstimateFailed("timeout".to_owned()));
+        assert_eq!(sut.error(), "RemoteGasEstimateFailed");
+        assert_eq!(sut.message(), "Remote gas estimate failed: timeout");
+        assert_eq!(sut.details(), &json!({}));
+    }
+
+    #[test]
+    fn remote_gas_estimate_failed_is_remote() {
+        let error = Error::remote_gas_estimate_failed("connection reset");
+        assert_eq!(
+            error,
+            Error::RemoteGasEstimateFailed("connection reset".to_owned())
+        );
+        assert_eq!(error.status_code(), StatusCode::BAD_GATEWAY);
+    }
+
+    #[test]
+    fn status_codes() {
+        let bad_hex = Error::StringNotHex {
+            bad_value: "zz".to_owned(),
+        };
+        assert_eq!(bad_hex.status_code(), StatusCode::BAD_REQUEST);
+        let exceeds = Error::GasExceedsLimit {
+            estimated_cost: None,
+            gas_limit: Gas::from(1),
+        };
+        assert_eq!(exceeds.status_code(), StatusCode::UNPROCESSABLE_ENTITY);
+        let remote = Error::RemoteGasEstimateFailed("timeout".to_owned());
+        assert_eq!(remote.status_code(), StatusCode::BAD_GATEWAY);
+        let timeout = Error::Timeout {
+            phase: EstimatePhase::Remote,
+        };
+        assert_eq!(timeout.status_code(), StatusCode::GATEWAY_TIMEOUT);
+        assert_eq!(timeout.to_string(), "The remote estimate timed out");
+        assert_eq!(
+            Error::FailedToCalculateGasEstimate.status_code(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+
+    #[tokio::test]
+    async fn into_response_is_json() {
+        let response = Error::EmptyTransaction.into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let sut: Sut = serde_json::from_slice(&body).unwrap();
+        assert_eq!(sut.error(), "EmptyTransaction");
+    }
+}
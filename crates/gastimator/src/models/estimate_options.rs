@@ -0,0 +1,169 @@
+use derive_more::IsVariant;
+
+use crate::prelude::*;
+
+/// Per request options for a gas estimate, typically passed as query
+/// parameters, e.g. `/tx?raw_remote=true`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Builder, CopyGetters)]
+#[builder(setter(into), default)]
+#[serde(default)]
+pub struct EstimateOptions {
+    /// If `true` the raw JSON-RPC response of the remote `eth_estimateGas`
+    /// call is included in the response, unmodified. Useful for debugging
+    /// divergences between local and remote estimates.
+    #[getset(get_copy = "pub")]
+    raw_remote: bool,
+
+    /// If set, an estimate with a range is collapsed into a single estimate,
+    /// using the point of the range specified, e.g. `/tx?collapse=high`.
+    #[getset(get_copy = "pub")]
+    collapse: Option<RangeCollapse>,
+
+    /// The max fee per gas (in wei) of an [EIP-1559][eip] transaction, if
+    /// set the estimated fee is included in the response, e.g.
+    /// `/tx?max_fee_per_gas=30000000000`.
+    ///
+    /// [eip]: https://eips.ethereum.org/EIPS/eip-1559
+    #[getset(get_copy = "pub")]
+    max_fee_per_gas: Option<U256>,
+
+    /// The max priority fee per gas (in wei) of an [EIP-1559][eip]
+    /// transaction, used for the estimated fee if `max_fee_per_gas` is not
+    /// set, e.g. on chains with a negligible base fee.
+    ///
+    /// [eip]: https://eips.ethereum.org/EIPS/eip-1559
+    #[getset(get_copy = "pub")]
+    max_priority_fee_per_gas: Option<U256>,
+
+    /// Which estimators are used, e.g. `/tx?mode=local` to skip the remote
+    /// call for latency sensitive clients.
+    #[getset(get_copy = "pub")]
+    mode: EstimateMode,
+
+    /// If `true` the transaction decoded from the RLP is included in the
+    /// response, e.g. `/rlp?decoded=true`, to verify the right bytes were
+    /// sent. Ignored by endpoints not taking RLP.
+    #[getset(get_copy = "pub")]
+    decoded: bool,
+}
+
+// ========================================
+// Public Implementation
+// ========================================
+impl EstimateOptions {
+    /// The fee per gas (in wei) used to compute the estimated fee, the
+    /// `max_fee_per_gas` if set, else the `max_priority_fee_per_gas`, if set.
+    pub fn fee_per_gas(&self) -> Option<U256> {
+        self.max_fee_per_gas.or(self.max_priority_fee_per_gas)
+    }
+
+    /// These options with the fees replaced by `max_fee_per_gas` and
+    /// `max_priority_fee_per_gas`.
+    pub fn with_fees(
+        &self,
+        max_fee_per_gas: Option<U256>,
+        max_priority_fee_per_gas: Option<U256>,
+    ) -> Self {
+        Self {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            ..self.clone()
+        }
+    }
+}
+
+/// Which point of an estimated range to collapse it into, see
+/// [`GasUsage::collapsed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RangeCollapse {
+    /// The low bound of the range.
+    Low,
+    /// The midpoint of the range, rounded down.
+    Midpoint,
+    /// The high bound of the range.
+    High,
+    /// The recommended gas of the range, a weighted blend of the local and
+    /// remote estimates.
+    Recommended,
+}
+
+/// Which estimators are used for an estimate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, IsVariant, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EstimateMode {
+    /// Both the local simulation and the remote gas estimator, in parallel,
+    /// yielding a range if both succeed.
+    #[default]
+    LocalAndRemote,
+    /// Only the local simulation, without calling the remote gas estimator.
+    Local,
+}
+
+/// Options of the `/simulate` endpoint, passed as query parameters, e.g.
+/// `/simulate?state_diff=true`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Builder, CopyGetters)]
+#[builder(setter(into), default)]
+#[serde(default)]
+pub struct SimulateOptions {
+    /// If `true` the changes to the state (balances, nonces and storage)
+    /// made by the transaction are included in the response.
+    #[getset(get_copy = "pub")]
+    state_diff: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    type Sut = EstimateOptions;
+
+    #[test]
+    fn deserialize_empty_is_default() {
+        let sut: Sut = serde_json::from_value(json!({})).unwrap();
+        assert_eq!(sut, Sut::default());
+    }
+
+    #[test]
+    fn deserialize_raw_remote() {
+        let sut: Sut = serde_json::from_value(json!({"raw_remote": true})).unwrap();
+        assert!(sut.raw_remote());
+    }
+
+    #[test]
+    fn deserialize_collapse() {
+        let sut: Sut = serde_json::from_value(json!({"collapse": "midpoint"})).unwrap();
+        assert_eq!(sut.collapse(), Some(RangeCollapse::Midpoint));
+    }
+
+    #[test]
+    fn deserialize_mode() {
+        let sut: Sut = serde_json::from_value(json!({"mode": "local"})).unwrap();
+        assert_eq!(sut.mode(), EstimateMode::Local);
+        assert_eq!(Sut::default().mode(), EstimateMode::LocalAndRemote);
+    }
+
+    #[test]
+    fn deserialize_decoded() {
+        let sut: Sut = serde_json::from_value(json!({"decoded": true})).unwrap();
+        assert!(sut.decoded());
+        assert!(!Sut::default().decoded());
+    }
+
+    #[test]
+    fn fee_per_gas_prefers_max_fee() {
+        let sut: Sut = serde_json::from_value(json!({
+            "max_fee_per_gas": "30000000000",
+            "max_priority_fee_per_gas": "0x3b9aca00"
+        }))
+        .unwrap();
+        assert_eq!(sut.fee_per_gas(), Some(U256::from(30_000_000_000u64)));
+
+        let sut: Sut =
+            serde_json::from_value(json!({"max_priority_fee_per_gas": "0x3b9aca00"})).unwrap();
+        assert_eq!(sut.fee_per_gas(), Some(U256::from(1_000_000_000u64)));
+        assert_eq!(Sut::default().fee_per_gas(), None);
+    }
+}
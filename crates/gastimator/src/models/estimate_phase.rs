@@ -0,0 +1,15 @@
+use crate::prelude::*;
+
+/// A phase of computing an estimate, e.g. the one which timed out, see
+/// [`Error::Timeout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, derive_more::Display)]
+#[serde(rename_all = "snake_case")]
+pub enum EstimatePhase {
+    /// The local simulation.
+    #[display("local")]
+    Local,
+
+    /// The call to the remote gas estimator.
+    #[display("remote")]
+    Remote,
+}
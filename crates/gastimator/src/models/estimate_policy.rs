@@ -0,0 +1,102 @@
+use crate::prelude::*;
+
+/// How the single `recommended` value of a range is picked from the local
+/// and remote estimates when both succeed, the range itself is always kept.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EstimatePolicy {
+    /// A blend of the two, where the remote has the weight
+    /// [`GastimatorConfig::remote_weight`].
+    #[default]
+    Weighted,
+
+    /// The local estimate.
+    PreferLocal,
+
+    /// The remote estimate, e.g. since local simulation without a fork runs
+    /// against empty state.
+    PreferRemote,
+
+    /// The lower of the two.
+    Min,
+
+    /// The higher of the two.
+    Max,
+
+    /// The average of the two, rounded.
+    Average,
+}
+
+// ========================================
+// Public Implementation
+// ========================================
+impl EstimatePolicy {
+    /// The recommended gas of the `local` and `remote` estimates, where
+    /// `remote_weight` is only used by [`EstimatePolicy::Weighted`].
+    pub fn recommended(&self, local: Gas, remote: Gas, remote_weight: f64) -> Gas {
+        let blend = |weight: f64| {
+            let blend = *local as f64 * (1.0 - weight) + *remote as f64 * weight;
+            Gas::from(blend.round() as u64)
+        };
+        match self {
+            Self::Weighted => blend(remote_weight),
+            Self::PreferLocal => local,
+            Self::PreferRemote => remote,
+            Self::Min => min(local, remote),
+            Self::Max => max(local, remote),
+            Self::Average => blend(0.5),
+        }
+    }
+}
+
+// ========================================
+// FromStr Implementation
+// ========================================
+impl std::str::FromStr for EstimatePolicy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "weighted" => Ok(Self::Weighted),
+            "prefer_local" => Ok(Self::PreferLocal),
+            "prefer_remote" => Ok(Self::PreferRemote),
+            "min" => Ok(Self::Min),
+            "max" => Ok(Self::Max),
+            "average" => Ok(Self::Average),
+            _ => Err(Error::UnknownEstimatePolicy {
+                bad_value: s.to_owned(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = EstimatePolicy;
+
+    #[test]
+    fn recommended() {
+        let (local, remote) = (Gas::from(40_000), Gas::from(50_000));
+        let recommended = |policy: Sut| *policy.recommended(local, remote, 0.75);
+        assert_eq!(recommended(Sut::Weighted), 47_500);
+        assert_eq!(recommended(Sut::PreferLocal), 40_000);
+        assert_eq!(recommended(Sut::PreferRemote), 50_000);
+        assert_eq!(recommended(Sut::Min), 40_000);
+        assert_eq!(recommended(Sut::Max), 50_000);
+        assert_eq!(recommended(Sut::Average), 45_000);
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!("prefer_remote".parse::<Sut>(), Ok(Sut::PreferRemote));
+        assert_eq!("average".parse::<Sut>(), Ok(Sut::Average));
+        assert_eq!(
+            "median".parse::<Sut>(),
+            Err(Error::UnknownEstimatePolicy {
+                bad_value: "median".to_owned()
+            })
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use crate::prelude::*;
+
+/// How the estimate of a [`GasEstimateResponse`] was obtained, telling a
+/// reliable estimate from a degraded fallback.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EstimateSource {
+    /// Only the local simulation succeeded, e.g. because the remote gas
+    /// estimator failed or was not called.
+    LocalOnly,
+    /// Only the remote gas estimator succeeded, e.g. because the local
+    /// simulation failed.
+    RemoteOnly,
+    /// Both the local simulation and the remote gas estimator succeeded,
+    /// yielding a range. The default, e.g. for responses lacking a source.
+    #[default]
+    Both,
+    /// A cached estimate was used, see [`Cache`].
+    Cached,
+    /// The gas is known exactly without estimating, e.g. for a native token
+    /// transfer or a call to a precompile.
+    ExactRule,
+}
+
+impl EstimateSource {
+    /// The label of the source, same as its serialized form, e.g. `"both"`.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::LocalOnly => "local_only",
+            Self::RemoteOnly => "remote_only",
+            Self::Both => "both",
+            Self::Cached => "cached",
+            Self::ExactRule => "exact_rule",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    type Sut = EstimateSource;
+
+    #[test]
+    fn serde() {
+        assert_eq!(
+            serde_json::to_value(Sut::LocalOnly).unwrap(),
+            json!("local_only")
+        );
+        assert_eq!(
+            serde_json::from_value::<Sut>(json!("exact_rule")).unwrap(),
+            Sut::ExactRule
+        );
+    }
+
+    #[test]
+    fn label_is_serialized_form() {
+        for sut in [
+            Sut::LocalOnly,
+            Sut::RemoteOnly,
+            Sut::Both,
+            Sut::Cached,
+            Sut::ExactRule,
+        ] {
+            assert_eq!(serde_json::to_value(sut).unwrap(), json!(sut.label()));
+        }
+    }
+}
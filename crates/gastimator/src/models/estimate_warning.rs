@@ -0,0 +1,76 @@
+use crate::prelude::*;
+
+/// A warning about a transaction which was estimated successfully, but which
+/// the client probably wants to flag, e.g. a gas limit far above the estimate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EstimateWarning {
+    /// The gas limit of the transaction exceeds the estimate by more than
+    /// `factor`, which often indicates a client bug or a risk of overpaying
+    /// fees.
+    GasLimitSuspiciouslyHigh {
+        /// The gas limit of the transaction.
+        gas_limit: Gas,
+        /// The estimated gas usage, the high bound of a range.
+        estimate: Gas,
+        /// The configured factor which the gas limit exceeds the estimate by.
+        factor: f64,
+    },
+}
+
+// ========================================
+// Public Implementation
+// ========================================
+impl EstimateWarning {
+    /// A [`EstimateWarning::GasLimitSuspiciouslyHigh`] if `gas_limit` is
+    /// more than `factor` times `estimate`, else `None`.
+    pub fn gas_limit_suspiciously_high(
+        gas_limit: Option<Gas>,
+        estimate: Gas,
+        factor: f64,
+    ) -> Option<Self> {
+        let gas_limit = gas_limit?;
+        (*gas_limit as f64 > *estimate as f64 * factor).then_some(Self::GasLimitSuspiciouslyHigh {
+            gas_limit,
+            estimate,
+            factor,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    type Sut = EstimateWarning;
+
+    #[test]
+    fn gas_limit_suspiciously_high() {
+        let estimate = Gas::from(21_000);
+        assert!(
+            Sut::gas_limit_suspiciously_high(Some(Gas::from(210_001)), estimate, 10.0).is_some()
+        );
+        assert!(
+            Sut::gas_limit_suspiciously_high(Some(Gas::from(210_000)), estimate, 10.0).is_none()
+        );
+        assert!(Sut::gas_limit_suspiciously_high(None, estimate, 10.0).is_none());
+    }
+
+    #[test]
+    fn serialize() {
+        let sut =
+            Sut::gas_limit_suspiciously_high(Some(Gas::from(300_000)), Gas::from(21_000), 10.0)
+                .unwrap();
+        assert_eq!(
+            serde_json::to_value(&sut).unwrap(),
+            json!({
+                "kind": "gas_limit_suspiciously_high",
+                "gas_limit": 300000,
+                "estimate": 21000,
+                "factor": 10.0
+            })
+        );
+    }
+}
@@ -1,9 +1,23 @@
+use alloy::eips::eip2930::AccessList;
+
 use crate::prelude::*;
 
 /// EIP-2028 cost per zero byte
 const CONTRACT_CALL_COST_PER_BYTE_ZERO: u64 = 4;
 /// EIP-2028 cost per non zero byte
 const CONTRACT_CALL_COST_PER_BYTE_NONZERO: u64 = 16;
+/// EIP-4844 blob gas per blob
+const BLOB_GAS_PER_BLOB: u64 = 131_072;
+/// EIP-3860 cost per word of initcode
+const INITCODE_COST_PER_WORD: u64 = 2;
+/// Cost per byte of code deposited by a contract creation
+const CODE_DEPOSIT_COST_PER_BYTE: u64 = 200;
+/// EIP-170 maximum size in bytes of the code of a contract
+const MAX_CODE_SIZE: usize = 24_576;
+/// EIP-2930 cost per address of an access list
+const ACCESS_LIST_COST_PER_ADDRESS: u64 = 2_400;
+/// EIP-2930 cost per storage key of an access list
+const ACCESS_LIST_COST_PER_STORAGE_KEY: u64 = 1_900;
 
 /// Amount of gas used by a transaction.
 #[derive(
@@ -24,6 +38,20 @@ const CONTRACT_CALL_COST_PER_BYTE_NONZERO: u64 = 16;
 #[serde(transparent)]
 pub struct Gas(u64);
 
+impl TryFrom<U256> for Gas {
+    type Error = Error;
+
+    /// Converts a `U256` into gas, which is always a `u64`.
+    ///
+    /// # Throws
+    /// Throws [`Error::UInt256LargerThanU64`] if `value` does not fit in a `u64`.
+    fn try_from(value: U256) -> Result<Self> {
+        u64::try_from(value)
+            .map(Self)
+            .map_err(|_| Error::UInt256LargerThanU64)
+    }
+}
+
 // ========================================
 // Public Implementation
 // ========================================
@@ -40,6 +68,38 @@ impl Gas {
         Self(32_000)
     }
 
+    /// Heuristic minimum gas usage for a contract creation with `init_code`,
+    /// i.e. [`Gas::min_contract_creation`] plus the cost of depositing the
+    /// code and the calldata cost of `init_code`, excluding the `21000` base.
+    ///
+    /// Not a bound, the deployed code is not known without executing
+    /// `init_code`, its size is approximated by that of `init_code`, bounded
+    /// by the [EIP-170][eip] limit of `24576` bytes, so it overestimates
+    /// creations whose constructor is large.
+    ///
+    /// [eip]: https://eips.ethereum.org/EIPS/eip-170
+    pub fn min_contract_creation_with_code(init_code: &Bytes) -> Self {
+        let code_deposit = min(init_code.len(), MAX_CODE_SIZE) as u64 * CODE_DEPOSIT_COST_PER_BYTE;
+        Self(
+            *Self::min_contract_creation()
+                + code_deposit
+                + Self::contract_call_cost_of_input(init_code),
+        )
+    }
+
+    /// Fixed gas usage for creating a contract without any init code
+    pub fn exact_empty_contract_creation() -> Self {
+        Self(*Self::exact_native_token_transfer() + *Self::min_contract_creation())
+    }
+
+    /// Blob gas used by `blob_count` blobs, [EIP-4844][eip]. Blob gas is a
+    /// separate dimension, priced separately from execution gas.
+    ///
+    /// [eip]: https://eips.ethereum.org/EIPS/eip-4844
+    pub fn blob_gas(blob_count: usize) -> Self {
+        Self(blob_count as u64 * BLOB_GAS_PER_BLOB)
+    }
+
     /// EIP-150 sets the gas cost of CALL and CALLCODE to 700 gas
     /// https://eips.ethereum.org/EIPS/eip-150
     fn base_contract_call_cost() -> Self {
@@ -64,6 +124,36 @@ impl Gas {
         Self(base.0 + Self::contract_call_cost_of_input(input))
     }
 
+    /// The [EIP-2028][eip] cost of calldata `input`.
+    ///
+    /// [eip]: https://eips.ethereum.org/EIPS/eip-2028
+    pub fn calldata_cost(input: &Bytes) -> Self {
+        Self(Self::contract_call_cost_of_input(input))
+    }
+
+    /// The [EIP-3860][eip] cost of the initcode `input` of a contract
+    /// creation.
+    ///
+    /// [eip]: https://eips.ethereum.org/EIPS/eip-3860
+    pub fn initcode_cost(input: &Bytes) -> Self {
+        Self(input.len().div_ceil(32) as u64 * INITCODE_COST_PER_WORD)
+    }
+
+    /// The [EIP-2930][eip] cost of `access_list`.
+    ///
+    /// [eip]: https://eips.ethereum.org/EIPS/eip-2930
+    pub fn access_list_cost(access_list: &AccessList) -> Self {
+        Self(
+            access_list
+                .iter()
+                .map(|item| {
+                    ACCESS_LIST_COST_PER_ADDRESS
+                        + item.storage_keys.len() as u64 * ACCESS_LIST_COST_PER_STORAGE_KEY
+                })
+                .sum(),
+        )
+    }
+
     fn contract_call_cost_of_input(input: &Bytes) -> u64 {
         input
             .iter()
@@ -93,4 +183,70 @@ mod tests {
             2 * CONTRACT_CALL_COST_PER_BYTE_ZERO + 4 * CONTRACT_CALL_COST_PER_BYTE_NONZERO
         );
     }
+
+    #[test]
+    fn min_contract_creation_with_code() {
+        let init_code = Bytes::from([0xab; 10]);
+        assert_eq!(
+            Gas::min_contract_creation_with_code(&init_code),
+            Gas::from(
+                32_000 + 10 * CODE_DEPOSIT_COST_PER_BYTE + 10 * CONTRACT_CALL_COST_PER_BYTE_NONZERO
+            )
+        );
+        let too_large = Bytes::from(vec![0x00; MAX_CODE_SIZE + 1]);
+        assert_eq!(
+            Gas::min_contract_creation_with_code(&too_large),
+            Gas::from(
+                32_000
+                    + MAX_CODE_SIZE as u64 * CODE_DEPOSIT_COST_PER_BYTE
+                    + (MAX_CODE_SIZE as u64 + 1) * CONTRACT_CALL_COST_PER_BYTE_ZERO
+            )
+        );
+    }
+
+    #[test]
+    fn initcode_cost_rounds_up_to_words() {
+        let input = Bytes::from([0xab; 33]);
+        assert_eq!(
+            Gas::initcode_cost(&input),
+            Gas::from(2 * INITCODE_COST_PER_WORD)
+        );
+    }
+
+    #[test]
+    fn access_list_cost() {
+        use alloy::eips::eip2930::AccessListItem;
+        let access_list = AccessList(vec![AccessListItem {
+            address: Address::ZERO,
+            storage_keys: vec![B256::ZERO, B256::repeat_byte(1)],
+        }]);
+        assert_eq!(
+            Gas::access_list_cost(&access_list),
+            Gas::from(ACCESS_LIST_COST_PER_ADDRESS + 2 * ACCESS_LIST_COST_PER_STORAGE_KEY)
+        );
+    }
+
+    #[test]
+    fn blob_gas() {
+        assert_eq!(Gas::blob_gas(0), Gas::from(0));
+        assert_eq!(Gas::blob_gas(2), Gas::from(2 * BLOB_GAS_PER_BLOB));
+    }
+
+    #[test]
+    fn try_from_u256() {
+        assert_eq!(Gas::try_from(U256::from(237u64)), Ok(Gas::from(237)));
+    }
+
+    #[test]
+    fn try_from_u256_max_u64() {
+        assert_eq!(Gas::try_from(U256::from(u64::MAX)), Ok(Gas::MAX));
+    }
+
+    #[test]
+    fn try_from_u256_larger_than_u64() {
+        assert_eq!(
+            Gas::try_from(U256::from(u64::MAX) + U256::from(1)),
+            Err(Error::UInt256LargerThanU64)
+        );
+    }
 }
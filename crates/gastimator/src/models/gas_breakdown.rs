@@ -0,0 +1,105 @@
+use crate::prelude::*;
+
+/// Breakdown of the gas used by a transaction, the parts sum up to the
+/// total gas used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Builder, Getters)]
+#[builder(setter(into))]
+pub struct GasBreakdown {
+    /// The intrinsic gas, excluding calldata, charged before execution,
+    /// e.g. the `21000` base, contract creation and access list costs.
+    #[getset(get = "pub")]
+    intrinsic: Gas,
+
+    /// The cost of the calldata (`input`), [EIP-2028][eip2028] priced. If
+    /// the calldata floor of [EIP-7623][eip7623] applies, the transaction is
+    /// charged the floor, which is then attributed to calldata.
+    ///
+    /// [eip2028]: https://eips.ethereum.org/EIPS/eip-2028
+    /// [eip7623]: https://eips.ethereum.org/EIPS/eip-7623
+    #[getset(get = "pub")]
+    calldata: Gas,
+
+    /// The gas used by executing the transaction, after refunds.
+    #[getset(get = "pub")]
+    execution: Gas,
+}
+
+// ========================================
+// Public Implementation
+// ========================================
+impl GasBreakdown {
+    /// The total gas used, i.e. the sum of all parts.
+    pub fn total(&self) -> Gas {
+        Gas::from(*self.intrinsic + *self.calldata + *self.execution)
+    }
+
+    /// Breaks down `gas_used` of a transaction with calldata `input`, given
+    /// the `initial_gas` (intrinsic gas including calldata) and `floor_gas`
+    /// (EIP-7623 calldata floor, `0` if not applicable) of it.
+    pub fn new(input: &Bytes, gas_used: Gas, initial_gas: Gas, floor_gas: Gas) -> Self {
+        let calldata = Gas::calldata_cost(input);
+        let intrinsic = Gas::from(initial_gas.saturating_sub(*calldata));
+        let (calldata, execution) = if gas_used <= floor_gas {
+            // Charged the calldata floor, execution is covered by it.
+            (Gas::from(gas_used.saturating_sub(*intrinsic)), Gas::from(0))
+        } else {
+            (calldata, Gas::from(gas_used.saturating_sub(*initial_gas)))
+        };
+        Self {
+            intrinsic,
+            calldata,
+            execution,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::hex::FromHex;
+
+    use super::*;
+
+    type Sut = GasBreakdown;
+
+    #[test]
+    fn parts_sum_to_gas_used() {
+        let input = Bytes::from_hex("de00ad00beef").unwrap();
+        let sut = Sut::new(
+            &input,
+            Gas::from(50_000),
+            Gas::from(21_072),
+            Gas::from(21_160),
+        );
+        assert_eq!(*sut.intrinsic(), Gas::from(21_000));
+        assert_eq!(*sut.calldata(), Gas::from(72));
+        assert_eq!(*sut.execution(), Gas::from(50_000 - 21_072));
+        assert_eq!(sut.total(), Gas::from(50_000));
+    }
+
+    #[test]
+    fn calldata_floor_is_attributed_to_calldata() {
+        let input = Bytes::from_hex("dead").unwrap();
+        let sut = Sut::new(
+            &input,
+            Gas::from(21_080),
+            Gas::from(21_032),
+            Gas::from(21_080),
+        );
+        assert_eq!(*sut.intrinsic(), Gas::from(21_000));
+        assert_eq!(*sut.calldata(), Gas::from(80));
+        assert_eq!(*sut.execution(), Gas::from(0));
+        assert_eq!(sut.total(), Gas::from(21_080));
+    }
+
+    #[test]
+    fn json_snapshot() {
+        insta::assert_json_snapshot!(
+            &GasBreakdownBuilder::default()
+                .intrinsic(Gas::from(21_000))
+                .calldata(Gas::from(72))
+                .execution(Gas::from(12_345))
+                .build()
+                .unwrap()
+        )
+    }
+}
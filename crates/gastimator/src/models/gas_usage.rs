@@ -42,6 +42,10 @@ pub enum GasUsage {
         low: Gas,
         /// High bound estimate
         high: Gas,
+        /// A single value within the range, to use as gas limit, picked
+        /// from the local and remote estimates by
+        /// [`GastimatorConfig::estimate_policy`]
+        recommended: Gas,
     },
 }
 
@@ -57,6 +61,103 @@ impl GasUsage {
             Self::Exact { kind, .. } => kind,
         }
     }
+
+    /// The highest gas the transaction is estimated to use, i.e. the high
+    /// bound of a range.
+    pub fn max_gas(&self) -> Gas {
+        match self {
+            Self::Exact { gas, .. } | Self::Estimate { gas, .. } => *gas,
+            Self::EstimateWithRange { high, .. } => *high,
+        }
+    }
+
+    /// The lowest gas the transaction is estimated to use, i.e. the low
+    /// bound of a range.
+    pub fn min_gas(&self) -> Gas {
+        match self {
+            Self::Exact { gas, .. } | Self::Estimate { gas, .. } => *gas,
+            Self::EstimateWithRange { low, .. } => *low,
+        }
+    }
+
+    /// Caps an estimate at `cap`, e.g. the gas limit of the transaction, of a
+    /// range `high` and `recommended` are capped, `low` is not. Exact gas
+    /// usages are returned unchanged.
+    pub fn capped(self, cap: Gas) -> Self {
+        match self {
+            Self::Exact { .. } => self,
+            Self::Estimate { kind, gas } => Self::Estimate {
+                kind,
+                gas: min(gas, cap),
+            },
+            Self::EstimateWithRange {
+                kind,
+                low,
+                high,
+                recommended,
+            } => Self::EstimateWithRange {
+                kind,
+                low,
+                high: min(high, cap),
+                recommended: min(recommended, cap),
+            },
+        }
+    }
+
+    /// Pads an estimate by multiplying it with `margin`, e.g. `1.1` for +10%,
+    /// rounding up, but not above `cap` (unless already above it). Of a range `high` and `recommended`
+    /// are padded, `low` is not. Exact gas usages are returned unchanged.
+    pub fn padded(self, margin: f64, cap: Gas) -> Self {
+        // In millionths, so that e.g. `55_000 * 1.1` is exactly `60_500`
+        let margin_ppm = (margin * 1_000_000.0).round() as u128;
+        let pad = |gas: Gas| {
+            let padded = (*gas as u128 * margin_ppm).div_ceil(1_000_000);
+            min(
+                Gas::from(padded.min(u64::MAX as u128) as u64),
+                max(gas, cap),
+            )
+        };
+        match self {
+            Self::Exact { .. } => self,
+            Self::Estimate { kind, gas } => Self::Estimate {
+                kind,
+                gas: pad(gas),
+            },
+            Self::EstimateWithRange {
+                kind,
+                low,
+                high,
+                recommended,
+            } => Self::EstimateWithRange {
+                kind,
+                low,
+                high: pad(high),
+                recommended: pad(recommended),
+            },
+        }
+    }
+
+    /// Collapses an estimate with a range into a single estimate using the
+    /// point of the range specified by `collapse`, other gas usages are
+    /// returned unchanged.
+    pub fn collapsed(self, collapse: RangeCollapse) -> Self {
+        let Self::EstimateWithRange {
+            kind,
+            low,
+            high,
+            recommended,
+        } = self
+        else {
+            return self;
+        };
+        let gas = match collapse {
+            RangeCollapse::Low => low,
+            RangeCollapse::Midpoint => Gas::from(*low + (*high - *low) / 2),
+            RangeCollapse::High => high,
+            RangeCollapse::Recommended => recommended,
+        };
+        Self::Estimate { kind, gas }
+    }
 }
 
 #[cfg(test)]
@@ -87,6 +188,116 @@ mod tests {
             kind: TransactionKind::ContractCreation,
             low: Gas::from(43210),
             high: Gas::from(54321),
+            recommended: Gas::from(51543),
         })
     }
+
+    fn range() -> Sut {
+        Sut::EstimateWithRange {
+            kind: TransactionKind::ContractCreation,
+            low: Gas::from(30_000),
+            high: Gas::from(147_649),
+            recommended: Gas::from(118_237),
+        }
+    }
+
+    #[test]
+    fn collapsed_low() {
+        assert_eq!(
+            range().collapsed(RangeCollapse::Low),
+            Sut::Estimate {
+                kind: TransactionKind::ContractCreation,
+                gas: Gas::from(30_000),
+            }
+        );
+    }
+
+    #[test]
+    fn collapsed_midpoint() {
+        assert_eq!(
+            range().collapsed(RangeCollapse::Midpoint),
+            Sut::Estimate {
+                kind: TransactionKind::ContractCreation,
+                gas: Gas::from(88_824),
+            }
+        );
+    }
+
+    #[test]
+    fn collapsed_high() {
+        assert_eq!(
+            range().collapsed(RangeCollapse::High),
+            Sut::Estimate {
+                kind: TransactionKind::ContractCreation,
+                gas: Gas::from(147_649),
+            }
+        );
+    }
+
+    #[test]
+    fn collapsed_recommended() {
+        assert_eq!(
+            range().collapsed(RangeCollapse::Recommended),
+            Sut::Estimate {
+                kind: TransactionKind::ContractCreation,
+                gas: Gas::from(118_237),
+            }
+        );
+    }
+
+    #[test]
+    fn padded_range() {
+        assert_eq!(
+            range().padded(1.1, Gas::MAX),
+            Sut::EstimateWithRange {
+                kind: TransactionKind::ContractCreation,
+                low: Gas::from(30_000),
+                high: Gas::from(162_414),
+                recommended: Gas::from(130_061),
+            }
+        );
+    }
+
+    #[test]
+    fn padded_is_capped() {
+        let sut = Sut::Estimate {
+            kind: TransactionKind::ContractCreation,
+            gas: Gas::from(100_000),
+        };
+        assert_eq!(
+            sut.padded(1.5, Gas::from(120_000)),
+            Sut::Estimate {
+                kind: TransactionKind::ContractCreation,
+                gas: Gas::from(120_000),
+            }
+        );
+        let exact = Sut::Exact {
+            kind: TransactionKind::NativeTokenTransfer,
+            gas: Gas::exact_native_token_transfer(),
+        };
+        assert_eq!(exact.clone().padded(1.5, Gas::MAX), exact);
+    }
+
+    #[test]
+    fn capped_range() {
+        assert_eq!(
+            range().capped(Gas::from(120_000)),
+            Sut::EstimateWithRange {
+                kind: TransactionKind::ContractCreation,
+                low: Gas::from(30_000),
+                high: Gas::from(120_000),
+                recommended: Gas::from(118_237),
+            }
+        );
+        assert_eq!(range().min_gas(), Gas::from(30_000));
+    }
+
+    #[test]
+    fn collapsed_leaves_non_range_unchanged() {
+        let sut = Sut::Estimate {
+            kind: TransactionKind::ContractCreation,
+            gas: Gas::from(54321),
+        };
+        assert_eq!(sut.clone().collapsed(RangeCollapse::High), sut);
+    }
 }
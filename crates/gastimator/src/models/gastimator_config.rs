@@ -0,0 +1,235 @@
+use derive_more::IsVariant;
+
+use crate::prelude::*;
+
+/// Default number of workers in the [`WorkQueue`] of the [`Gastimator`].
+pub const DEFAULT_WORKER_POOL_SIZE: usize = 32;
+
+/// Default number of local simulations which are allowed to run
+/// concurrently, the number of CPUs, or `4` if unknown.
+pub fn default_local_simulation_threads() -> usize {
+    std::thread::available_parallelism().map_or(4, usize::from)
+}
+
+/// Default factor by which the gas limit of a transaction may exceed its
+/// estimate before the response carries a warning.
+pub const DEFAULT_GAS_LIMIT_WARNING_FACTOR: f64 = 10.0;
+
+/// Default weight of the remote estimate in the recommended gas of a range.
+pub const DEFAULT_REMOTE_WEIGHT: f64 = 0.75;
+
+/// Default safety margin estimates are padded with, i.e. no padding.
+pub const DEFAULT_SAFETY_MARGIN: f64 = 1.0;
+
+/// Default maximum number of entries of the access list of a transaction.
+pub const DEFAULT_MAX_ACCESS_LIST_ENTRIES: usize = 1024;
+
+/// How the [`Gastimator`] handles empty transactions, i.e. transactions
+/// without `to`, `input` and `value`, which cannot be meaningfully estimated,
+/// see [`Transaction::is_empty`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum EmptyTransactionPolicy {
+    /// Fail with [`Error::EmptyTransaction`].
+    #[default]
+    Reject,
+
+    /// Return the exact gas usage of creating an empty contract, without
+    /// simulating it.
+    MinimalEstimate,
+}
+
+/// Which estimators the [`Gastimator`] uses, both the local simulation and
+/// the remote gas estimator, or either only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, IsVariant)]
+pub enum GastimatorMode {
+    /// Estimates by local simulation and by the remote gas estimator.
+    #[default]
+    Both,
+
+    /// No remote gas estimator is used, estimates are computed by local
+    /// simulation only, and the [`Gastimator`] is ready immediately.
+    LocalOnly,
+
+    /// Transactions are not locally simulated, estimates are computed by the
+    /// remote gas estimator only, e.g. where running the EVM is not wanted.
+    /// Excludes `fork`.
+    RemoteOnly,
+}
+
+/// Configuration of the [`Gastimator`], i.e. how estimates are orchestrated.
+#[derive(Debug, Clone, PartialEq, Builder, CopyGetters)]
+#[builder(setter(into), default)]
+pub struct GastimatorConfig {
+    /// Number of estimates which are allowed to be computed concurrently,
+    /// estimates exceeding this wait in a priority queue.
+    /// E.g. `32`
+    #[getset(get_copy = "pub")]
+    worker_pool_size: usize,
+
+    /// Number of local simulations which are allowed to run concurrently,
+    /// each on a blocking thread, bounding the CPU used by simulations so
+    /// that the async runtime stays responsive under bursts.
+    /// E.g. `8`
+    #[getset(get_copy = "pub")]
+    local_simulation_threads: usize,
+
+    /// The excess blob gas of the simulated block, from which the blob base
+    /// fee used to price blobs of EIP-4844 transactions is derived.
+    /// E.g. `0`, yielding the minimum blob base fee of 1 wei.
+    #[getset(get_copy = "pub")]
+    excess_blob_gas: u64,
+
+    /// The EVM spec (hardfork) used for local simulation, if `None` the spec
+    /// of the configured chain is used.
+    /// E.g. `Some(SpecId::CANCUN)`
+    #[getset(get_copy = "pub")]
+    spec: Option<SpecId>,
+
+    /// If `true` transactions are locally simulated against a fork of the
+    /// state of the chain, read over RPC at the latest block, instead of
+    /// against empty state.
+    /// E.g. `false`
+    #[getset(get_copy = "pub")]
+    fork: bool,
+
+    /// The number of the block to fork at when `fork` is `true`, if `None`
+    /// each simulation forks at the latest block.
+    /// E.g. `Some(22_000_000)`
+    #[getset(get_copy = "pub")]
+    fork_block: Option<u64>,
+
+    /// How to handle empty transactions, see [`EmptyTransactionPolicy`].
+    /// E.g. `EmptyTransactionPolicy::Reject`
+    #[getset(get_copy = "pub")]
+    empty_transaction_policy: EmptyTransactionPolicy,
+
+    /// Which estimators are used, see [`GastimatorMode`].
+    /// E.g. `GastimatorMode::Both`
+    #[getset(get_copy = "pub")]
+    mode: GastimatorMode,
+
+    /// The fraction, between `0.0` and `1.0`, of cacheable transactions for
+    /// which the remote gas estimator is called, the rest are estimated by
+    /// local simulation only, trading remote quota for accuracy. Transactions
+    /// which are not cacheable, or all if remote only, always call the
+    /// remote.
+    /// E.g. `0.1` calls the remote for every tenth cacheable transaction.
+    #[getset(get_copy = "pub")]
+    remote_sample_rate: f64,
+
+    /// The time to live of cached estimates, after which they are expired
+    /// and recomputed, so that estimates refresh periodically.
+    /// E.g. `Duration::from_secs(60)`
+    #[getset(get_copy = "pub")]
+    cache_ttl: Duration,
+
+    /// How long responses are stored by the `Idempotency-Key` header of
+    /// their request, a request with the same key within it is answered with
+    /// the stored response instead of being estimated again. Zero to not
+    /// store responses.
+    /// E.g. `Duration::from_secs(60)`
+    #[getset(get_copy = "pub")]
+    idempotency_ttl: Duration,
+
+    /// If `true` local estimates are also cached by the fields relevant for
+    /// local simulation only, see [`KindStableKey`], so that transactions
+    /// without `nonce` or `from` skip local simulation on a hit. Less precise
+    /// than the cache of estimates, which is keyed by the entire transaction.
+    /// E.g. `false`
+    #[getset(get_copy = "pub")]
+    kind_stable_cache: bool,
+
+    /// The maximum number of entries (addresses plus storage keys) of the
+    /// access list of a transaction, transactions exceeding it are rejected
+    /// with [`Error::AccessListTooLarge`], bounding the cost of handling it.
+    /// E.g. `1024`
+    #[getset(get_copy = "pub")]
+    max_access_list_entries: usize,
+
+    /// The end-to-end budget of an estimate, shared by the cache lookup,
+    /// waiting for a worker, the local simulation and the remote estimate,
+    /// which gets whatever remains after the local simulation. When the
+    /// budget is exhausted the local estimate is returned, if available,
+    /// else the estimate fails with [`Error::BudgetExhausted`]. `None` for
+    /// no budget.
+    /// E.g. `Some(Duration::from_millis(500))`
+    #[getset(get_copy = "pub")]
+    total_budget: Option<Duration>,
+
+    /// The maximum gas limit of a transaction, transactions with a higher
+    /// gas limit are rejected with [`Error::GasLimitTooHigh`] before being
+    /// simulated, typically the gas limit of a block. `None` for no cap.
+    /// E.g. `Some(Gas::from(30_000_000))`
+    #[getset(get_copy = "pub")]
+    max_allowed_gas_limit: Option<Gas>,
+
+    /// The factor by which the gas limit of a transaction may exceed its
+    /// estimate, above it the response carries an
+    /// [`EstimateWarning::GasLimitSuspiciouslyHigh`], `f64::INFINITY` to
+    /// never warn.
+    /// E.g. `10.0`
+    #[getset(get_copy = "pub")]
+    gas_limit_warning_factor: f64,
+
+    /// The weight, between `0.0` and `1.0`, of the remote estimate in the
+    /// `recommended` gas of a range, the local estimate has the remaining
+    /// weight. Favors the remote by default, since local simulation without
+    /// a fork runs against empty state.
+    /// E.g. `0.75`
+    #[getset(get_copy = "pub")]
+    remote_weight: f64,
+
+    /// How the `recommended` gas of a range is picked from the local and
+    /// remote estimates, by default weighted by `remote_weight`.
+    /// E.g. `EstimatePolicy::PreferRemote`
+    #[getset(get_copy = "pub")]
+    estimate_policy: EstimatePolicy,
+
+    /// The factor estimates are padded with, e.g. `1.1` for +10%, so that
+    /// transactions using the estimate as gas limit do not run out of gas.
+    /// Capped by the gas limit of the transaction, exact gas usages are not
+    /// padded. `1.0` for no padding.
+    /// E.g. `1.1`
+    #[getset(get_copy = "pub")]
+    safety_margin: f64,
+}
+
+// ========================================
+// Public Implementation
+// ========================================
+impl GastimatorConfig {
+    /// This config with `mode` replaced, e.g. for a [`Gastimator`] built
+    /// without a remote gas estimator.
+    pub fn with_mode(&self, mode: GastimatorMode) -> Self {
+        Self {
+            mode,
+            ..self.clone()
+        }
+    }
+}
+
+impl Default for GastimatorConfig {
+    fn default() -> Self {
+        Self {
+            worker_pool_size: DEFAULT_WORKER_POOL_SIZE,
+            local_simulation_threads: default_local_simulation_threads(),
+            excess_blob_gas: 0,
+            spec: None,
+            fork: false,
+            fork_block: None,
+            empty_transaction_policy: EmptyTransactionPolicy::default(),
+            mode: GastimatorMode::default(),
+            remote_sample_rate: 1.0,
+            cache_ttl: DEFAULT_CACHE_TTL,
+            idempotency_ttl: DEFAULT_IDEMPOTENCY_TTL,
+            kind_stable_cache: false,
+            max_access_list_entries: DEFAULT_MAX_ACCESS_LIST_ENTRIES,
+            total_budget: None,
+            max_allowed_gas_limit: None,
+            gas_limit_warning_factor: DEFAULT_GAS_LIMIT_WARNING_FACTOR,
+            remote_weight: DEFAULT_REMOTE_WEIGHT,
+            estimate_policy: EstimatePolicy::default(),
+            safety_margin: DEFAULT_SAFETY_MARGIN,
+        }
+    }
+}
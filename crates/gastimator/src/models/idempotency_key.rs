@@ -0,0 +1,60 @@
+use alloy_primitives::keccak256;
+
+use crate::prelude::*;
+
+/// Key of a response stored for an idempotent request, the value of its
+/// `Idempotency-Key` header scoped by the route, with the hash of the
+/// request, so that reusing the value for another request is detected
+/// instead of replaying the response of the first.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Getters)]
+pub struct IdempotencyKey {
+    /// The route of the request, e.g. `"/tx"`.
+    #[getset(get = "pub")]
+    route: String,
+
+    /// The value of the `Idempotency-Key` header, e.g. a UUID.
+    #[getset(get = "pub")]
+    key: String,
+}
+
+// ========================================
+// Public Implementation
+// ========================================
+impl IdempotencyKey {
+    /// The `key` sent to `route`.
+    pub fn new(route: impl Into<String>, key: impl Into<String>) -> Self {
+        Self {
+            route: route.into(),
+            key: key.into(),
+        }
+    }
+
+    /// The hash of `request`, e.g. its body and query, of its JSON.
+    pub fn request_hash(request: &impl Serialize) -> B256 {
+        keccak256(serde_json::to_vec(request).unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = IdempotencyKey;
+
+    #[test]
+    fn scoped_by_route() {
+        assert_ne!(Sut::new("/tx", "retry-1"), Sut::new("/rlp", "retry-1"));
+        assert_eq!(Sut::new("/tx", "retry-1"), Sut::new("/tx", "retry-1"));
+    }
+
+    #[test]
+    fn request_hash_distinguishes_requests() {
+        let transfer = Transaction::sample_native_token_transfer();
+        let creation = Transaction::sample_contract_creation();
+        assert_eq!(
+            Sut::request_hash(&transfer),
+            Sut::request_hash(&transfer.clone())
+        );
+        assert_ne!(Sut::request_hash(&transfer), Sut::request_hash(&creation));
+    }
+}
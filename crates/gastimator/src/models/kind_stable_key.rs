@@ -0,0 +1,93 @@
+use alloy::eips::eip2930::AccessList;
+
+use crate::prelude::*;
+
+/// Key of the kind-stable cache of local estimates, the fields of a
+/// transaction which determine its local simulation against empty state:
+/// `to`, `value`, `input` and the access list. Unlike the key of the
+/// [`Cache`] of estimates, the entire transaction, it does not need `nonce`
+/// and `from`, so that e.g. wallets estimating before filling in the nonce
+/// benefit from caching.
+///
+/// Less precise than the entire transaction, e.g. simulations depending on
+/// the sender or, when forking, on the chain state are not distinguished.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KindStableKey {
+    to: TxKind,
+    value: U256,
+    input: Bytes,
+    access_list: Option<AccessList>,
+}
+
+// ========================================
+// Public Implementation
+// ========================================
+impl KindStableKey {
+    /// The key of `tx`, `None` if `tx` overrides state or the block, e.g.
+    /// its base fee or timestamp, or targets a specific block, all of which
+    /// change the local simulation.
+    pub fn of(tx: &Transaction) -> Option<Self> {
+        if tx.state_override().is_some() || tx.block_number().is_some() || tx.overrides_block() {
+            return None;
+        }
+        Some(Self {
+            to: *tx.to(),
+            value: *tx.value(),
+            input: tx.input().clone(),
+            access_list: tx
+                .precomputed_access_list()
+                .as_ref()
+                .map(|precomputed| precomputed.access_list().clone()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = KindStableKey;
+
+    #[test]
+    fn ignores_nonce_from_and_gas_limit() {
+        let tx = Transaction::sample_native_token_transfer_cachable();
+        let mut other = TransactionBuilder::default()
+            .value(*tx.value())
+            .to(*tx.to())
+            .build()
+            .unwrap();
+        other.set_gas_limit(Some(Gas::from(30_000)));
+        assert_eq!(Sut::of(&tx), Sut::of(&other));
+    }
+
+    #[test]
+    fn differs_by_input() {
+        let tx = Transaction::sample_contract_creation();
+        let other = TransactionBuilder::default()
+            .to(TxKind::Create)
+            .input(Bytes::from([0x5b; 401]))
+            .build()
+            .unwrap();
+        assert_ne!(Sut::of(&tx), Sut::of(&other));
+    }
+
+    #[test]
+    fn none_with_block_number() {
+        let tx = TransactionBuilder::default()
+            .to(Address::repeat_byte(0xcc))
+            .block_number(alloy::eips::BlockNumberOrTag::Latest)
+            .build()
+            .unwrap();
+        assert_eq!(Sut::of(&tx), None);
+    }
+
+    #[test]
+    fn none_with_base_fee() {
+        let tx = TransactionBuilder::default()
+            .to(Address::repeat_byte(0xcc))
+            .base_fee(7u64)
+            .build()
+            .unwrap();
+        assert_eq!(Sut::of(&tx), None);
+    }
+}
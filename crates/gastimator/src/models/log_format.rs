@@ -0,0 +1,51 @@
+use crate::prelude::*;
+
+/// The format of the log lines of the server.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogFormat {
+    /// Human readable, colored lines, e.g. for local development.
+    #[default]
+    Text,
+
+    /// One JSON object per line, with fields such as the kind of the
+    /// transaction and the elapsed time of a span as keys, e.g. for log
+    /// aggregation.
+    Json,
+}
+
+// ========================================
+// FromStr Implementation
+// ========================================
+impl std::str::FromStr for LogFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(Error::UnknownLogFormat {
+                bad_value: s.to_owned(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = LogFormat;
+
+    #[test]
+    fn from_str() {
+        assert_eq!("text".parse::<Sut>(), Ok(Sut::Text));
+        assert_eq!("json".parse::<Sut>(), Ok(Sut::Json));
+        assert_eq!(
+            "yaml".parse::<Sut>(),
+            Err(Error::UnknownLogFormat {
+                bad_value: "yaml".to_owned()
+            })
+        );
+    }
+}
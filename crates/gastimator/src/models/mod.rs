@@ -1,17 +1,75 @@
+mod accuracy_response;
+mod allowed_origins;
+mod block_env_override;
+mod cache_key;
+mod call_output;
 mod config;
+mod config_error;
+mod config_file;
+mod deploy_request;
 mod error;
+mod estimate_options;
+mod estimate_phase;
+mod estimate_policy;
+mod estimate_source;
+mod estimate_warning;
 mod gas;
+mod gas_breakdown;
 mod gas_usage;
+mod gastimator_config;
+mod idempotency_key;
+mod kind_stable_key;
+mod log_format;
+mod optional_route;
+mod precompile;
+mod precomputed_access_list;
+mod rate_limit;
 mod raw_transaction;
+mod replacement;
+mod request_id;
+mod rlp_verify_response;
 mod server_config;
+mod service_status;
+mod state_diff;
+mod state_override;
 mod transaction;
+mod transaction_hash_input;
 mod transaction_kind;
 
+pub use accuracy_response::*;
+pub use allowed_origins::*;
+pub use block_env_override::*;
+pub use cache_key::*;
+pub use call_output::*;
 pub use config::*;
+pub use config_error::*;
+pub use config_file::*;
+pub use deploy_request::*;
 pub use error::*;
+pub use estimate_options::*;
+pub use estimate_phase::*;
+pub use estimate_policy::*;
+pub use estimate_source::*;
+pub use estimate_warning::*;
 pub use gas::*;
+pub use gas_breakdown::*;
 pub use gas_usage::*;
+pub use gastimator_config::*;
+pub use idempotency_key::*;
+pub use kind_stable_key::*;
+pub use log_format::*;
+pub use optional_route::*;
+pub use precompile::*;
+pub use precomputed_access_list::*;
+pub use rate_limit::*;
 pub use raw_transaction::*;
+pub use replacement::*;
+pub use request_id::*;
+pub use rlp_verify_response::*;
 pub use server_config::*;
+pub use service_status::*;
+pub use state_diff::*;
+pub use state_override::*;
 pub use transaction::*;
+pub use transaction_hash_input::*;
 pub use transaction_kind::*;
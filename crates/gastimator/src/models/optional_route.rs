@@ -0,0 +1,172 @@
+use std::collections::HashSet;
+
+use crate::prelude::*;
+
+/// Routes of the REST server which can be disabled, e.g. in a locked down
+/// deployment. The core routes `/tx` and `/rlp` are always mounted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OptionalRoute {
+    /// `POST /tx/batch`
+    Batch,
+    /// `GET /health`
+    Health,
+    /// `GET /ready`
+    Ready,
+    /// `GET /cache/stats`
+    CacheStats,
+    /// `POST /simulate`
+    Simulate,
+    /// `POST /cache/clear`, guarded by the admin token, if configured
+    CacheClear,
+    /// `POST /replace`
+    Replace,
+    /// `GET /metrics`
+    Metrics,
+    /// `POST /classify`
+    Classify,
+    /// `POST /call`
+    Call,
+    /// `POST /tx/stream`
+    Stream,
+    /// `GET /ws`, upgraded to a WebSocket
+    WebSocket,
+    /// `POST /rlp/raw`, the RLP as plain hex body
+    RlpRaw,
+    /// `POST /tx/by-hash`, a mined transaction fetched by hash
+    ByHash,
+    /// `POST /tx/accuracy`, the estimate of a mined transaction compared
+    /// against the gas it used
+    Accuracy,
+    /// `POST /rlp/verify`, the decoded transaction and recovered signer of
+    /// an RLP, without estimating it
+    RlpVerify,
+    /// `POST /bundle`, the gas used by transactions simulated in order
+    /// against shared state
+    Bundle,
+    /// `GET /stats`, counters of successes and failures of the local and
+    /// remote estimators as JSON
+    Stats,
+    /// `POST /deploy`, the estimate of deploying contract bytecode
+    Deploy,
+}
+
+// ========================================
+// Public Implementation
+// ========================================
+impl OptionalRoute {
+    /// All optional routes.
+    pub const ALL: [Self; 19] = [
+        Self::Batch,
+        Self::Health,
+        Self::Ready,
+        Self::CacheStats,
+        Self::Simulate,
+        Self::CacheClear,
+        Self::Replace,
+        Self::Metrics,
+        Self::Classify,
+        Self::Call,
+        Self::Stream,
+        Self::WebSocket,
+        Self::RlpRaw,
+        Self::ByHash,
+        Self::Accuracy,
+        Self::RlpVerify,
+        Self::Bundle,
+        Self::Stats,
+        Self::Deploy,
+    ];
+
+    /// All optional routes, as a set, i.e. all enabled.
+    pub fn all() -> HashSet<Self> {
+        Self::ALL.into_iter().collect()
+    }
+
+    /// The name of the route, e.g. `"batch"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Batch => "batch",
+            Self::Health => "health",
+            Self::Ready => "ready",
+            Self::CacheStats => "cache_stats",
+            Self::Simulate => "simulate",
+            Self::CacheClear => "cache_clear",
+            Self::Replace => "replace",
+            Self::Metrics => "metrics",
+            Self::Classify => "classify",
+            Self::Call => "call",
+            Self::Stream => "stream",
+            Self::WebSocket => "ws",
+            Self::RlpRaw => "rlp_raw",
+            Self::ByHash => "by_hash",
+            Self::Accuracy => "accuracy",
+            Self::RlpVerify => "rlp_verify",
+            Self::Bundle => "bundle",
+            Self::Stats => "stats",
+            Self::Deploy => "deploy",
+        }
+    }
+
+    /// The path of the route, e.g. `"/tx/batch"`.
+    pub fn path(&self) -> &'static str {
+        match self {
+            Self::Batch => "/tx/batch",
+            Self::Health => "/health",
+            Self::Ready => "/ready",
+            Self::CacheStats => "/cache/stats",
+            Self::Simulate => "/simulate",
+            Self::CacheClear => "/cache/clear",
+            Self::Replace => "/replace",
+            Self::Metrics => "/metrics",
+            Self::Classify => "/classify",
+            Self::Call => "/call",
+            Self::Stream => "/tx/stream",
+            Self::WebSocket => "/ws",
+            Self::RlpRaw => "/rlp/raw",
+            Self::ByHash => "/tx/by-hash",
+            Self::Accuracy => "/tx/accuracy",
+            Self::RlpVerify => "/rlp/verify",
+            Self::Bundle => "/bundle",
+            Self::Stats => "/stats",
+            Self::Deploy => "/deploy",
+        }
+    }
+}
+
+// ========================================
+// FromStr Implementation
+// ========================================
+impl std::str::FromStr for OptionalRoute {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|route| route.name() == s)
+            .ok_or_else(|| Error::UnknownRoute {
+                bad_value: s.to_owned(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    type Sut = OptionalRoute;
+
+    #[test]
+    fn from_str_roundtrip() {
+        for route in Sut::ALL {
+            assert_eq!(Sut::from_str(route.name()).unwrap(), route);
+        }
+    }
+
+    #[test]
+    fn from_str_unknown() {
+        assert!(Sut::from_str("tx").unwrap_err().is_unknown_route());
+    }
+}
@@ -0,0 +1,112 @@
+use revm::interpreter::gas::{InitialAndFloorGas, calculate_initial_tx_gas};
+
+use crate::prelude::*;
+
+/// Ethereum precompiles with a gas cost given by a formula of the size of
+/// the input, which never fail, so the gas used by a transaction calling
+/// them is known exactly without simulating it.
+///
+/// Other precompiles (e.g. `modexp` or the `bn254` curve operations) are
+/// not recognized, either their cost depends on the contents of the input,
+/// or they fail on malformed input, consuming all gas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Precompile {
+    /// `ecrecover` at `0x01`, a fixed cost of `3000`.
+    EcRecover,
+    /// `sha256` at `0x02`, `60 + 12` per word of input.
+    Sha256,
+    /// `ripemd160` at `0x03`, `600 + 120` per word of input.
+    Ripemd160,
+    /// `identity` at `0x04`, `15 + 3` per word of input.
+    Identity,
+}
+
+// ========================================
+// Public Implementation
+// ========================================
+impl Precompile {
+    /// The recognized precompile at `address`, if any.
+    pub fn at(address: &Address) -> Option<Self> {
+        let (prefix, last) = address.0.split_at(19);
+        if prefix.iter().any(|byte| *byte != 0) {
+            return None;
+        }
+        match last[0] {
+            0x01 => Some(Self::EcRecover),
+            0x02 => Some(Self::Sha256),
+            0x03 => Some(Self::Ripemd160),
+            0x04 => Some(Self::Identity),
+            _ => None,
+        }
+    }
+
+    /// The gas used by executing the precompile with `input`, excluding
+    /// the intrinsic gas of the transaction.
+    pub fn execution_gas(&self, input: &Bytes) -> Gas {
+        let words = input.len().div_ceil(32) as u64;
+        let (base, per_word) = match self {
+            Self::EcRecover => (3_000, 0),
+            Self::Sha256 => (60, 12),
+            Self::Ripemd160 => (600, 120),
+            Self::Identity => (15, 3),
+        };
+        Gas::from(base + per_word * words)
+    }
+
+    /// The exact gas used by a transaction calling the precompile with
+    /// `input` and no access list, simulated with `spec`, i.e. the intrinsic
+    /// gas plus the execution gas, or the [EIP-7623][eip] calldata floor if
+    /// higher.
+    ///
+    /// [eip]: https://eips.ethereum.org/EIPS/eip-7623
+    pub fn gas_of_call(&self, input: &Bytes, spec: SpecId) -> Gas {
+        let InitialAndFloorGas {
+            initial_gas,
+            floor_gas,
+        } = calculate_initial_tx_gas(spec, input, false, 0, 0, 0);
+        Gas::from(max(initial_gas + *self.execution_gas(input), floor_gas))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = Precompile;
+
+    #[test]
+    fn at_recognized_addresses() {
+        assert_eq!(
+            Sut::at(&Address::with_last_byte(0x01)),
+            Some(Sut::EcRecover)
+        );
+        assert_eq!(Sut::at(&Address::with_last_byte(0x04)), Some(Sut::Identity));
+        // modexp is not recognized
+        assert_eq!(Sut::at(&Address::with_last_byte(0x05)), None);
+        assert_eq!(Sut::at(&Address::ZERO), None);
+        assert_eq!(Sut::at(&Address::from([0x04; 20])), None);
+    }
+
+    #[test]
+    fn execution_gas_rounds_up_to_words() {
+        let input = Bytes::from([0xab; 33]);
+        assert_eq!(Sut::Identity.execution_gas(&input), Gas::from(15 + 3 * 2));
+        assert_eq!(Sut::Sha256.execution_gas(&input), Gas::from(60 + 12 * 2));
+        assert_eq!(
+            Sut::Ripemd160.execution_gas(&input),
+            Gas::from(600 + 120 * 2)
+        );
+        assert_eq!(Sut::EcRecover.execution_gas(&input), Gas::from(3_000));
+    }
+
+    #[test]
+    fn gas_of_call_includes_intrinsic_gas() {
+        let input = Bytes::from([0xab; 64]);
+        // 21_000 base + 64 non zero bytes of calldata
+        let intrinsic = 21_000 + 64 * 16;
+        assert_eq!(
+            Sut::Identity.gas_of_call(&input, SpecId::CANCUN),
+            Gas::from(intrinsic + 15 + 3 * 2)
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::HashSet;
+
+use alloy::eips::eip2930::{AccessList, AccessListResult};
+
+use crate::prelude::*;
+
+/// An access list and the gas used with it, precomputed by the client
+/// using `eth_createAccessList`, i.e. an [`AccessListResult`].
+///
+/// When present on a transaction, the access list is used to compute the
+/// intrinsic cost in the local simulation, and the gas used is trusted in
+/// place of a remote estimate, skipping the remote call.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Deref)]
+#[serde(transparent)]
+pub struct PrecomputedAccessList(AccessListResult);
+
+// ========================================
+// Public Implementation
+// ========================================
+impl PrecomputedAccessList {
+    /// Creates a new precomputed access list from the result of
+    /// `eth_createAccessList`.
+    pub fn new(result: AccessListResult) -> Self {
+        Self(result)
+    }
+
+    /// The access list.
+    pub fn access_list(&self) -> &AccessList {
+        &self.0.access_list
+    }
+
+    /// The number of entries of the access list, i.e. the number of
+    /// addresses plus the number of storage keys of all addresses.
+    pub fn entries(&self) -> usize {
+        self.access_list()
+            .iter()
+            .map(|item| 1 + item.storage_keys.len())
+            .sum()
+    }
+
+    /// The gas used by the transaction with the access list, as computed by
+    /// the client.
+    ///
+    /// # Throws
+    /// Throws [`Error::InvalidAccessList`] if the gas used does not fit in a `u64`.
+    pub fn gas_used(&self) -> Result<Gas> {
+        Gas::try_from(self.0.gas_used).map_err(|_| Error::InvalidAccessList {
+            reason: format!("gas used {} does not fit in a u64", self.0.gas_used),
+        })
+    }
+
+    /// Validates that the access list was successfully created, that the gas
+    /// used is non zero and fits in a `u64`, and that no address nor storage
+    /// key of an address is duplicated.
+    ///
+    /// # Throws
+    /// Throws [`Error::InvalidAccessList`] if any of the above does not hold.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason: String| Err(Error::InvalidAccessList { reason });
+        if let Some(error) = &self.0.error {
+            return invalid(format!("access list creation failed: {error}"));
+        }
+        if self.gas_used()? == Gas::from(0) {
+            return invalid("gas used is zero".to_owned());
+        }
+        let mut addresses = HashSet::new();
+        for item in self.access_list().iter() {
+            if !addresses.insert(item.address) {
+                return invalid(format!("duplicate address {}", item.address));
+            }
+            let mut storage_keys = HashSet::new();
+            for key in &item.storage_keys {
+                if !storage_keys.insert(key) {
+                    return invalid(format!(
+                        "duplicate storage key {key} of address {}",
+                        item.address
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// ========================================
+// Hash Implementation
+// ========================================
+impl std::hash::Hash for PrecomputedAccessList {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.access_list.hash(state);
+        self.0.gas_used.hash(state);
+        self.0.error.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::eips::eip2930::AccessListItem;
+    use serde_json::json;
+
+    use super::*;
+
+    type Sut = PrecomputedAccessList;
+
+    fn sut(items: Vec<AccessListItem>, gas_used: u64, error: Option<&str>) -> Sut {
+        Sut::new(AccessListResult {
+            access_list: AccessList(items),
+            gas_used: U256::from(gas_used),
+            error: error.map(ToOwned::to_owned),
+        })
+    }
+
+    fn item(address: u8, keys: &[u8]) -> AccessListItem {
+        AccessListItem {
+            address: Address::from([address; 20]),
+            storage_keys: keys.iter().map(|k| B256::repeat_byte(*k)).collect(),
+        }
+    }
+
+    #[test]
+    fn valid() {
+        assert!(
+            sut(vec![item(1, &[1, 2]), item(2, &[1])], 30_000, None)
+                .validate()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn entries_counts_addresses_and_storage_keys() {
+        assert_eq!(
+            sut(vec![item(1, &[1, 2]), item(2, &[])], 30_000, None).entries(),
+            2 + 2
+        );
+    }
+
+    #[test]
+    fn invalid_if_creation_failed() {
+        assert!(
+            sut(vec![], 30_000, Some("execution reverted"))
+                .validate()
+                .unwrap_err()
+                .is_invalid_access_list()
+        );
+    }
+
+    #[test]
+    fn invalid_if_gas_used_is_zero() {
+        assert!(
+            sut(vec![], 0, None)
+                .validate()
+                .unwrap_err()
+                .is_invalid_access_list()
+        );
+    }
+
+    #[test]
+    fn invalid_if_duplicate_address() {
+        assert!(
+            sut(vec![item(1, &[1]), item(1, &[2])], 30_000, None)
+                .validate()
+                .unwrap_err()
+                .is_invalid_access_list()
+        );
+    }
+
+    #[test]
+    fn invalid_if_duplicate_storage_key() {
+        assert!(
+            sut(vec![item(1, &[1, 1])], 30_000, None)
+                .validate()
+                .unwrap_err()
+                .is_invalid_access_list()
+        );
+    }
+
+    #[test]
+    fn deserialize_from_create_access_list_result() {
+        let json = json!({
+            "accessList": [
+                {
+                    "address": "0x0101010101010101010101010101010101010101",
+                    "storageKeys": [
+                        "0x0101010101010101010101010101010101010101010101010101010101010101"
+                    ]
+                }
+            ],
+            "gasUsed": "0x7530"
+        });
+        let sut = serde_json::from_value::<Sut>(json).unwrap();
+        assert_eq!(sut, self::sut(vec![item(1, &[1])], 30_000, None));
+    }
+}
@@ -0,0 +1,40 @@
+use crate::prelude::*;
+
+/// A limit of the rate of requests of a single client, as a token bucket
+/// which refills with `per_second` tokens per second up to `burst` tokens,
+/// each request taking one token.
+#[derive(Debug, Clone, Copy, PartialEq, CopyGetters)]
+pub struct RateLimit {
+    /// The sustained number of requests per second allowed.
+    /// E.g. `10.0`
+    #[getset(get_copy = "pub")]
+    per_second: f64,
+
+    /// The number of requests allowed at once, after being idle.
+    /// E.g. `20`
+    #[getset(get_copy = "pub")]
+    burst: u32,
+}
+
+// ========================================
+// Public Implementation
+// ========================================
+impl RateLimit {
+    /// A limit of `per_second` requests per second, allowing bursts of
+    /// `burst` requests.
+    pub fn new(per_second: f64, burst: u32) -> Self {
+        Self { per_second, burst }
+    }
+
+    /// A limit of `per_second` requests per second, allowing bursts of one
+    /// second worth of requests, but at least one.
+    pub fn with_one_second_burst(per_second: f64) -> Self {
+        Self::new(per_second, per_second.ceil().max(1.0) as u32)
+    }
+
+    /// Returns `true` if the rate is positive and finite and the burst is at
+    /// least one, else no request would ever be allowed.
+    pub fn is_valid(&self) -> bool {
+        self.per_second.is_finite() && self.per_second > 0.0 && self.burst >= 1
+    }
+}
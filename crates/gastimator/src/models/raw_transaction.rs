@@ -10,14 +10,70 @@ pub struct RawTransaction {
     pub rlp: Bytes,
 }
 
+/// Either a [`RawTransaction`] or a [`Transaction`], e.g. `{"rlp": "0x..."}`
+/// or `{"to": "0x...", "input": "0x..."}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TransactionOrRaw {
+    Raw(RawTransaction),
+    Transaction(Transaction),
+}
+
+// ========================================
+// Public Implementation
+// ========================================
+impl RawTransaction {
+    /// Decodes the RLP into a transaction, unless longer than `max_len`
+    /// bytes, so that oversized payloads are not decoded.
+    ///
+    /// # Throws
+    /// Throws [`Error::InvalidTransaction`] if the RLP is longer than
+    /// `max_len`, else fails as [`decode_transaction`].
+    pub fn decode(self, max_len: usize) -> Result<Transaction> {
+        let len = self.rlp.len();
+        if len > max_len {
+            return Err(Error::InvalidTransaction {
+                reason: format!("RLP of {len} bytes exceeds the max of {max_len} bytes"),
+            });
+        }
+        Transaction::try_from(self)
+    }
+}
+
+impl TransactionOrRaw {
+    /// The transaction, decoding a raw transaction unless its RLP is longer
+    /// than `max_rlp_len` bytes, see [`RawTransaction::decode`].
+    pub fn decode(self, max_rlp_len: usize) -> Result<Transaction> {
+        match self {
+            Self::Raw(raw) => raw.decode(max_rlp_len),
+            Self::Transaction(tx) => Ok(tx),
+        }
+    }
+}
+
+impl TryFrom<TransactionOrRaw> for Transaction {
+    type Error = crate::Error;
+
+    fn try_from(value: TransactionOrRaw) -> Result<Self, Self::Error> {
+        match value {
+            TransactionOrRaw::Raw(raw) => Self::try_from(raw),
+            TransactionOrRaw::Transaction(tx) => Ok(tx),
+        }
+    }
+}
+
 impl std::str::FromStr for RawTransaction {
     type Err = crate::Error;
 
-    /// Tries to decode the string `s` into a `BagOfBytes`. Will fail
-    /// if the string is not valid hex or if the decoded bytes does
-    /// not have length 32.
+    /// Decodes the hex string `s` into the RLP, with or without a `0x` (or
+    /// `0X`) prefix, like `{"rlp": "0x..."}` is accepted with or without.
+    /// Fails with [`Error::StringNotHex`] if `s` is not valid hex.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        hex::decode(s)
+        let hex = s
+            .strip_prefix("0x")
+            .or_else(|| s.strip_prefix("0X"))
+            .unwrap_or(s);
+        hex::decode(hex)
             .map_err(|_| crate::Error::StringNotHex {
                 bad_value: s.to_owned(),
             })
@@ -32,6 +88,31 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn deserialize_transaction_or_raw() {
+        let raw: TransactionOrRaw = serde_json::from_value(json!({"rlp": "dead"})).unwrap();
+        assert!(matches!(raw, TransactionOrRaw::Raw(_)));
+        let tx: TransactionOrRaw = serde_json::from_value(
+            json!({"to": "0x11a9893cc07d91d95644aedd05d03f95e1dbaccd", "input": "0x1234"}),
+        )
+        .unwrap();
+        assert!(matches!(tx, TransactionOrRaw::Transaction(_)));
+    }
+
+    #[test]
+    fn decode_rejects_rlp_exceeding_max_len() {
+        let raw = RawTransaction {
+            rlp: Transaction::sample_native_token_transfer_cachable().to_rlp(),
+        };
+        let len = raw.rlp.len();
+
+        assert!(raw.clone().decode(len).is_ok());
+        assert!(matches!(
+            raw.decode(len - 1),
+            Err(Error::InvalidTransaction { .. })
+        ));
+    }
+
     #[test]
     fn deserialize() {
         let json = json!({"rlp": "dead"});
@@ -43,4 +124,24 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn from_str_with_and_without_prefix() {
+        let expected = RawTransaction {
+            rlp: hex_literal::hex!("dead").into(),
+        };
+        for s in ["dead", "0xdead", "0Xdead"] {
+            assert_eq!(s.parse::<RawTransaction>().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn from_str_not_hex() {
+        assert_eq!(
+            "0xnothex".parse::<RawTransaction>(),
+            Err(Error::StringNotHex {
+                bad_value: "0xnothex".to_owned()
+            })
+        );
+    }
 }
@@ -0,0 +1,85 @@
+use crate::prelude::*;
+
+/// A transaction together with the fees it is sent with, fees are not part
+/// of [`Transaction`] since they do not affect its gas usage.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Builder, Getters, CopyGetters)]
+#[builder(setter(into))]
+pub struct PricedTransaction {
+    /// The transaction.
+    #[getset(get = "pub")]
+    tx: Transaction,
+
+    /// The max fee per gas (in wei) of an [EIP-1559][eip] transaction.
+    ///
+    /// [eip]: https://eips.ethereum.org/EIPS/eip-1559
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get_copy = "pub")]
+    max_fee_per_gas: Option<U256>,
+
+    /// The max priority fee per gas (in wei) of an [EIP-1559][eip]
+    /// transaction.
+    ///
+    /// [eip]: https://eips.ethereum.org/EIPS/eip-1559
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get_copy = "pub")]
+    max_priority_fee_per_gas: Option<U256>,
+}
+
+/// A request to estimate a replacement of a pending transaction, i.e. a
+/// transaction with the same nonce sent with higher fees to replace one
+/// which is stuck, see [`Gastimator::estimate_replacement`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Builder, Getters)]
+#[builder(setter(into))]
+pub struct ReplacementRequest {
+    /// The pending transaction being replaced.
+    #[getset(get = "pub")]
+    original: PricedTransaction,
+
+    /// The transaction replacing `original`.
+    #[getset(get = "pub")]
+    replacement: PricedTransaction,
+}
+
+// ========================================
+// Public Implementation
+// ========================================
+impl PricedTransaction {
+    /// The per request `options` with the fees of this transaction.
+    pub fn options(&self, options: &EstimateOptions) -> EstimateOptions {
+        options.with_fees(self.max_fee_per_gas, self.max_priority_fee_per_gas)
+    }
+}
+
+impl ReplacementRequest {
+    /// Splits the request into the original and the replacement.
+    pub fn into_parts(self) -> (PricedTransaction, PricedTransaction) {
+        (self.original, self.replacement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    type Sut = ReplacementRequest;
+
+    #[test]
+    fn deserialize_fees_are_optional() {
+        let tx = Transaction::sample_native_token_transfer();
+        let sut: Sut = serde_json::from_value(json!({
+            "original": { "tx": tx },
+            "replacement": { "tx": tx, "max_fee_per_gas": "0x3b9aca00" }
+        }))
+        .unwrap();
+        assert_eq!(sut.original().max_fee_per_gas(), None);
+        assert_eq!(
+            sut.replacement().max_fee_per_gas(),
+            Some(U256::from(1_000_000_000u64))
+        );
+        assert_eq!(sut.replacement().tx(), &tx);
+    }
+}
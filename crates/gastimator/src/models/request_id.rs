@@ -0,0 +1,77 @@
+use crate::prelude::*;
+
+/// The id of a request to the server, either passed by the client in the
+/// `X-Request-Id` header or generated, a random UUID, used to correlate the
+/// log lines of the request and echoed in its response.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, derive_more::Display)]
+#[serde(transparent)]
+pub struct RequestId(String);
+
+// ========================================
+// Public Implementation
+// ========================================
+impl RequestId {
+    /// The maximum length of an id passed by a client.
+    pub const MAX_LEN: usize = 128;
+
+    /// A new random id, a version 4 UUID, e.g.
+    /// `"9b2e6c1a-4f0d-4c8e-a7d2-3e5f1b8c0a94"`.
+    pub fn generate() -> Self {
+        // Version 4 and variant 1 (RFC 4122), the remaining bits random.
+        let bits = rand::random::<u128>() & !(0xf << 76) & !(0x3 << 62) | (0x4 << 76) | (0x2 << 62);
+        let hex = format!("{bits:032x}");
+        Self(format!(
+            "{}-{}-{}-{}-{}",
+            &hex[0..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..32]
+        ))
+    }
+
+    /// The id passed by a client, e.g. the value of an `X-Request-Id`
+    /// header, `None` if it is empty, longer than [`Self::MAX_LEN`] or
+    /// contains anything but visible ASCII, so that it is safe to log.
+    pub fn new(id: impl AsRef<str>) -> Option<Self> {
+        let id = id.as_ref();
+        let valid =
+            !id.is_empty() && id.len() <= Self::MAX_LEN && id.chars().all(|c| c.is_ascii_graphic());
+        valid.then(|| Self(id.to_owned()))
+    }
+
+    /// The id as string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = RequestId;
+
+    #[test]
+    fn generated_is_unique_uuid_v4() {
+        let sut = Sut::generate();
+        let id = sut.as_str();
+        assert_eq!(id.len(), 36);
+        assert_eq!(
+            id.split('-').map(str::len).collect::<Vec<_>>(),
+            [8, 4, 4, 4, 12]
+        );
+        assert_eq!(&id[14..15], "4");
+        assert!(matches!(&id[19..20], "8" | "9" | "a" | "b"));
+        assert_ne!(Sut::generate(), sut);
+    }
+
+    #[test]
+    fn new_rejects_unsafe_ids() {
+        assert_eq!(Sut::new("req-1").unwrap().as_str(), "req-1");
+        assert_eq!(Sut::new(""), None);
+        assert_eq!(Sut::new("with space"), None);
+        assert_eq!(Sut::new("line\nbreak"), None);
+        assert_eq!(Sut::new("x".repeat(Sut::MAX_LEN + 1)), None);
+    }
+}
@@ -0,0 +1,50 @@
+use crate::prelude::*;
+
+/// The transaction decoded from an RLP and its signer, recovered from the
+/// signature, to check a signed transaction before submitting it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Getters, CopyGetters)] // deserialize for tests
+pub struct RlpVerifyResponse {
+    /// The decoded transaction.
+    #[getset(get = "pub")]
+    transaction: Transaction,
+
+    /// The signer recovered from the signature, `None` if the transaction is
+    /// unsigned.
+    #[getset(get_copy = "pub")]
+    signer: Option<Address>,
+}
+
+impl RlpVerifyResponse {
+    /// Decodes `raw` and recovers its signer, see [`recover_signer`].
+    ///
+    /// # Throws
+    /// Throws [`Error::InvalidSignature`] if signed but the signer cannot be
+    /// recovered, else fails as [`RawTransaction::decode`].
+    pub fn verify(raw: RawTransaction, max_rlp_len: usize) -> Result<Self> {
+        let rlp = raw.rlp.clone();
+        let transaction = raw.decode(max_rlp_len)?;
+        let signer = recover_signer(rlp)?;
+        Ok(Self {
+            transaction,
+            signer,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = RlpVerifyResponse;
+
+    #[test]
+    fn unsigned_has_no_signer() {
+        let tx = Transaction::sample_native_token_transfer_cachable();
+        let raw = RawTransaction { rlp: tx.to_rlp() };
+
+        let sut = Sut::verify(raw, DEFAULT_MAX_RLP_BYTES).unwrap();
+
+        assert_eq!(sut.signer(), None);
+        assert_eq!(*sut.transaction().to(), *tx.to());
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use crate::prelude::*;
 
 /// Run configuration for the server
@@ -13,8 +15,90 @@ pub struct ServerConfig {
     /// E.g. `3000`
     #[getset(get_copy = "pub")]
     port: u16,
+
+    /// The optional routes which are mounted, the core routes `/tx` and
+    /// `/rlp` are always mounted.
+    /// E.g. all of [`OptionalRoute::ALL`]
+    #[builder(default = "OptionalRoute::all()")]
+    #[getset(get = "pub")]
+    enabled_routes: HashSet<OptionalRoute>,
+
+    /// An optional token required as `Authorization: Bearer <token>` header
+    /// by admin endpoints, e.g. `POST /cache/clear`. If `None` admin
+    /// endpoints are not mounted.
+    #[builder(default)]
+    #[getset(get = "pub")]
+    admin_token: Option<String>,
+
+    /// An optional OpenTelemetry collector, e.g. `"http://localhost:4318"`,
+    /// metrics are periodically pushed to over OTLP. Requires the `otel`
+    /// feature, ignored without it.
+    #[builder(default)]
+    #[getset(get = "pub")]
+    otlp_endpoint: Option<String>,
+
+    /// The maximum duration of handling a request, after which `504` is
+    /// returned.
+    /// E.g. `Duration::from_secs(30)`
+    #[builder(default = "DEFAULT_REQUEST_TIMEOUT")]
+    #[getset(get_copy = "pub")]
+    request_timeout: Duration,
+
+    /// The origins browsers are allowed to call the server from, i.e.
+    /// answered with CORS headers, including preflight requests.
+    /// E.g. `AllowedOrigins::new(["https://app.example.com"])`
+    #[builder(default)]
+    #[getset(get = "pub")]
+    allowed_origins: AllowedOrigins,
+
+    /// The maximum size in bytes of a request body, of any route, larger
+    /// requests are rejected with `413 Payload Too Large`. Of bodies
+    /// streamed to `/tx/stream` each line is limited too.
+    /// E.g. `262_144`
+    #[builder(default = "DEFAULT_MAX_BODY_BYTES")]
+    #[getset(get_copy = "pub")]
+    max_body_bytes: usize,
+
+    /// The maximum length in bytes of the RLP of a raw transaction, longer
+    /// ones are rejected with [`Error::InvalidTransaction`] without being
+    /// decoded.
+    /// E.g. `131_072`
+    #[builder(default = "DEFAULT_MAX_RLP_BYTES")]
+    #[getset(get_copy = "pub")]
+    max_rlp_bytes: usize,
+
+    /// An optional limit of the rate of requests per client IP, requests
+    /// exceeding it are rejected with [`Error::RateLimited`]. If `None`
+    /// requests are not limited.
+    /// E.g. `Some(RateLimit::new(10.0, 20))`
+    #[builder(default)]
+    #[getset(get_copy = "pub")]
+    rate_limit: Option<RateLimit>,
+
+    /// The format of the log lines of the server, human readable text or
+    /// JSON for log aggregation.
+    /// E.g. `LogFormat::Json`
+    #[builder(default)]
+    #[getset(get_copy = "pub")]
+    log_format: LogFormat,
 }
 
+/// Default address of the server, all interfaces.
+pub const DEFAULT_ADDRESS: &str = "0.0.0.0";
+
+/// Default port of the server.
+pub const DEFAULT_PORT: u16 = 3000;
+
+/// Default maximum size in bytes of a request body, 256 KiB.
+pub const DEFAULT_MAX_BODY_BYTES: usize = 256 * 1024;
+
+/// Default maximum length in bytes of the RLP of a raw transaction, 128 KiB,
+/// the maximum size of a transaction accepted by the mempool of Geth.
+pub const DEFAULT_MAX_RLP_BYTES: usize = 128 * 1024;
+
+/// Default maximum duration of handling a request.
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
 // ========================================
 // Public Implementation
 // ========================================
@@ -23,4 +107,79 @@ impl ServerConfig {
     pub fn address_with_port(&self) -> String {
         format!("{}:{}", self.address, self.port)
     }
+
+    /// Returns `true` if the optional `route` is enabled, admin routes, e.g.
+    /// [`OptionalRoute::CacheClear`], only if an admin token is configured.
+    pub fn is_route_enabled(&self, route: OptionalRoute) -> bool {
+        let guarded = route != OptionalRoute::CacheClear || self.admin_token.is_some();
+        guarded && self.enabled_routes.contains(&route)
+    }
+
+    /// Checks that `bearer_token` is the admin token, comparing in constant
+    /// time.
+    ///
+    /// # Throws
+    /// Throws [`Error::Unauthorized`] if no admin token is configured or
+    /// `bearer_token` is missing or another token.
+    pub fn authorize_admin(&self, bearer_token: Option<&str>) -> Result<()> {
+        match (&self.admin_token, bearer_token) {
+            (Some(admin_token), Some(bearer_token))
+                if constant_time_eq(admin_token.as_bytes(), bearer_token.as_bytes()) =>
+            {
+                Ok(())
+            }
+            _ => Err(Error::Unauthorized),
+        }
+    }
+}
+
+/// Returns `true` if `a` equals `b`, in time independent of the position of
+/// the first differing byte, only the length is leaked.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = ServerConfig;
+
+    fn sut(admin_token: Option<&str>) -> Sut {
+        ServerConfigBuilder::default()
+            .address("127.0.0.1")
+            .port(3000u16)
+            .admin_token(admin_token.map(str::to_owned))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn cache_clear_requires_admin_token() {
+        assert!(!sut(None).is_route_enabled(OptionalRoute::CacheClear));
+        assert!(sut(Some("secret")).is_route_enabled(OptionalRoute::CacheClear));
+        assert!(sut(None).is_route_enabled(OptionalRoute::CacheStats));
+    }
+
+    #[test]
+    fn authorize_admin() {
+        let sut_with_token = sut(Some("secret"));
+        assert_eq!(sut_with_token.authorize_admin(Some("secret")), Ok(()));
+        assert_eq!(
+            sut_with_token.authorize_admin(Some("secreT")),
+            Err(Error::Unauthorized)
+        );
+        assert_eq!(
+            sut_with_token.authorize_admin(Some("secret2")),
+            Err(Error::Unauthorized)
+        );
+        assert_eq!(
+            sut_with_token.authorize_admin(None),
+            Err(Error::Unauthorized)
+        );
+        assert_eq!(
+            sut(None).authorize_admin(Some("secret")),
+            Err(Error::Unauthorized)
+        );
+    }
 }
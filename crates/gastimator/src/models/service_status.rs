@@ -0,0 +1,53 @@
+use crate::prelude::*;
+
+/// Status of the service, returned by the `/health` and `/ready` endpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceStatus {
+    /// The service is up.
+    Ok,
+    /// The service is ready to serve estimates.
+    Ready,
+    /// The service is up but not yet ready to serve estimates.
+    NotReady,
+}
+
+/// Response body of the `/health` and `/ready` endpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Getters)]
+pub struct ServiceStatusResponse {
+    #[getset(get = "pub")]
+    status: ServiceStatus,
+}
+
+impl From<ServiceStatus> for ServiceStatusResponse {
+    fn from(status: ServiceStatus) -> Self {
+        Self { status }
+    }
+}
+
+/// Options of the `/ready` endpoint, passed as query parameters, e.g.
+/// `/ready?ping=true`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Builder, CopyGetters)]
+#[builder(setter(into), default)]
+#[serde(default)]
+pub struct ReadinessOptions {
+    /// If `true` and not yet ready, the remote gas estimator is pinged, and
+    /// if it responds successfully the service becomes ready.
+    #[getset(get_copy = "pub")]
+    ping: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn serialize_not_ready() {
+        assert_eq!(
+            serde_json::to_value(ServiceStatusResponse::from(ServiceStatus::NotReady)).unwrap(),
+            json!({ "status": "not_ready" })
+        );
+    }
+}
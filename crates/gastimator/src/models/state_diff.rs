@@ -0,0 +1,121 @@
+use std::collections::BTreeMap;
+
+use crate::prelude::*;
+
+/// The changes to the state of all accounts changed by simulating a
+/// transaction, keyed by address. Accounts which are only read are omitted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Deref)]
+#[serde(transparent)]
+pub struct StateDiff(BTreeMap<Address, AccountDiff>);
+
+/// The changes to the balance, nonce and storage of one account, unchanged
+/// parts are `None` or omitted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Builder, Getters)]
+#[builder(setter(into), default)]
+pub struct AccountDiff {
+    /// The change of the balance in wei, if changed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub")]
+    balance: Option<Change<U256>>,
+
+    /// The change of the nonce, if changed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub")]
+    nonce: Option<Change<u64>>,
+
+    /// The changes of storage, keyed by slot, unchanged slots are omitted.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    #[getset(get = "pub")]
+    storage: BTreeMap<U256, Change<U256>>,
+}
+
+/// A change of a value from `from` (before the transaction) to `to` (after
+/// the transaction).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Getters)]
+pub struct Change<T> {
+    #[getset(get = "pub")]
+    from: T,
+    #[getset(get = "pub")]
+    to: T,
+}
+
+// ========================================
+// Public Implementation
+// ========================================
+impl StateDiff {
+    /// Creates a new state diff from `accounts`, omitting accounts without
+    /// any changes.
+    pub fn new(accounts: impl IntoIterator<Item = (Address, AccountDiff)>) -> Self {
+        Self(
+            accounts
+                .into_iter()
+                .filter(|(_, diff)| !diff.is_empty())
+                .collect(),
+        )
+    }
+
+    /// The diff of the account at `address`, if it was changed.
+    pub fn account(&self, address: &Address) -> Option<&AccountDiff> {
+        self.0.get(address)
+    }
+}
+
+impl AccountDiff {
+    /// Whether the account is unchanged.
+    pub fn is_empty(&self) -> bool {
+        self.balance.is_none() && self.nonce.is_none() && self.storage.is_empty()
+    }
+}
+
+impl<T: PartialEq> Change<T> {
+    /// A change from `from` to `to`, `None` if they are equal.
+    pub fn new(from: T, to: T) -> Option<Self> {
+        (from != to).then_some(Self { from, to })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = StateDiff;
+
+    #[test]
+    fn unchanged_accounts_are_omitted() {
+        let changed = Address::new([0xaa; 20]);
+        let unchanged = Address::new([0xbb; 20]);
+        let sut = Sut::new([
+            (
+                changed,
+                AccountDiffBuilder::default()
+                    .nonce(Change::new(0, 1))
+                    .build()
+                    .unwrap(),
+            ),
+            (unchanged, AccountDiff::default()),
+        ]);
+        assert_eq!(sut.len(), 1);
+        assert!(sut.account(&unchanged).is_none());
+    }
+
+    #[test]
+    fn change_of_equal_values_is_none() {
+        assert_eq!(Change::new(U256::from(1), U256::from(1)), None);
+    }
+
+    #[test]
+    fn json_snapshot() {
+        let sut = Sut::new([(
+            Address::new([0xaa; 20]),
+            AccountDiffBuilder::default()
+                .balance(Change::new(U256::from(1000), U256::from(900)))
+                .storage(BTreeMap::from([(
+                    U256::from(1),
+                    Change::new(U256::ZERO, U256::from(42)).unwrap(),
+                )]))
+                .build()
+                .unwrap(),
+        )]);
+        insta::assert_json_snapshot!(&sut)
+    }
+}
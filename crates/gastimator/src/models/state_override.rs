@@ -0,0 +1,109 @@
+use std::collections::BTreeMap;
+
+use crate::prelude::*;
+
+/// Overrides of the state of accounts, keyed by address, written into the
+/// in-memory database before a transaction is simulated locally, e.g. to
+/// seed the bytecode of a token contract and the balance slot of the
+/// sender, so that calls reading state not available locally do not revert.
+///
+/// Overrides are only applied to the local simulation, the remote gas
+/// estimator is not passed them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Deref)]
+#[serde(transparent)]
+pub struct StateOverride(BTreeMap<Address, AccountOverride>);
+
+/// Overrides of the balance, nonce, code and storage of one account, parts
+/// which are `None` or omitted are read from the database as usual.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Builder, Getters)]
+#[builder(setter(into), default)]
+pub struct AccountOverride {
+    /// The balance in wei, if overridden.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub")]
+    balance: Option<U256>,
+
+    /// The nonce, if overridden.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub")]
+    nonce: Option<u64>,
+
+    /// The runtime bytecode, if overridden.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub")]
+    code: Option<Bytes>,
+
+    /// Values of storage slots, keyed by slot, other slots are not
+    /// overridden.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[getset(get = "pub")]
+    storage: BTreeMap<U256, U256>,
+}
+
+// ========================================
+// Public Implementation
+// ========================================
+impl StateOverride {
+    /// Creates a new state override of `accounts`.
+    pub fn new(accounts: impl IntoIterator<Item = (Address, AccountOverride)>) -> Self {
+        Self(accounts.into_iter().collect())
+    }
+
+    /// Whether the code of the account at `address` is overridden.
+    pub fn overrides_code_at(&self, address: &Address) -> bool {
+        self.0
+            .get(address)
+            .is_some_and(|account| account.code.is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = StateOverride;
+
+    #[test]
+    fn overrides_code_at() {
+        let token = Address::new([0xaa; 20]);
+        let holder = Address::new([0xbb; 20]);
+        let sut = Sut::new([
+            (
+                token,
+                AccountOverrideBuilder::default()
+                    .code(Bytes::from([0x00]))
+                    .build()
+                    .unwrap(),
+            ),
+            (
+                holder,
+                AccountOverrideBuilder::default()
+                    .balance(U256::from(1))
+                    .build()
+                    .unwrap(),
+            ),
+        ]);
+        assert!(sut.overrides_code_at(&token));
+        assert!(!sut.overrides_code_at(&holder));
+        assert!(!sut.overrides_code_at(&Address::ZERO));
+    }
+
+    #[test]
+    fn json_roundtrip() {
+        let json = r#"{
+            "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa": {
+                "balance": "0x3e8",
+                "code": "0x6000",
+                "storage": { "0x1": "0x2a" }
+            }
+        }"#;
+        let sut: Sut = serde_json::from_str(json).unwrap();
+        let account = sut.get(&Address::new([0xaa; 20])).unwrap();
+        assert_eq!(*account.balance(), Some(U256::from(1000)));
+        assert_eq!(*account.nonce(), None);
+        assert_eq!(account.storage().get(&U256::from(1)), Some(&U256::from(42)));
+        let roundtripped: Sut =
+            serde_json::from_value(serde_json::to_value(&sut).unwrap()).unwrap();
+        assert_eq!(roundtripped, sut);
+    }
+}
@@ -1,3 +1,5 @@
+use alloy_consensus::transaction::RlpEcdsaEncodableTx;
+
 use crate::prelude::*;
 
 /// Transaction is a struct that represents a transaction in the Ethereum network.
@@ -25,7 +27,7 @@ pub struct Transaction {
     /// if a transaction is cacheable. If two transactions are identical but lack
     /// a nonce or a from address, they are not cacheable.
     #[serde(skip_serializing_if = "Option::is_none")]
-    #[getset(get = "pub")]
+    #[getset(get = "pub", set = "pub")]
     from: Option<Address>,
 
     /// The receiver of the transaction, either a contract or an EOA.
@@ -46,6 +48,58 @@ pub struct Transaction {
     #[serde(default)]
     #[getset(get = "pub")]
     input: Bytes,
+
+    /// Versioned hashes of the blobs carried by an [EIP-4844][eip] transaction,
+    /// empty for all other transactions.
+    ///
+    /// [eip]: https://eips.ethereum.org/EIPS/eip-4844
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[getset(get = "pub")]
+    blob_versioned_hashes: Vec<B256>,
+
+    /// Max fee per blob gas (in wei) of an [EIP-4844][eip] transaction.
+    ///
+    /// [eip]: https://eips.ethereum.org/EIPS/eip-4844
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub", set = "pub")]
+    max_fee_per_blob_gas: Option<u128>,
+
+    /// An access list and gas used precomputed by the client with
+    /// `eth_createAccessList`, if present the access list is used for the
+    /// intrinsic cost and the gas used is trusted instead of calling the
+    /// remote gas estimator, see [`PrecomputedAccessList`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub")]
+    precomputed_access_list: Option<PrecomputedAccessList>,
+
+    /// Overrides of the state of accounts, applied before simulating the
+    /// transaction locally, see [`StateOverride`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub")]
+    state_override: Option<StateOverride>,
+
+    /// The block to simulate the transaction at, e.g. `"0x14fb180"`,
+    /// `"latest"` or `"pending"`, used for state and block environment
+    /// (number, timestamp, base fee and gas limit) when locally simulating
+    /// against a fork of the chain, ignored otherwise. If `None` the block
+    /// configured for forking is used, by default the latest block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub")]
+    block_number: Option<BlockNumberOrTag>,
+
+    /// Overrides the base fee (in wei) of the block the transaction is
+    /// locally simulated in, i.e. `block.basefee`, for contracts branching on
+    /// it. If `None` the base fee of the simulated block is used, which is
+    /// zero unless forking.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub")]
+    base_fee: Option<u64>,
+
+    /// Overrides of the environment of the block the transaction is locally
+    /// simulated in, e.g. its timestamp or coinbase, see [`BlockEnvOverride`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[getset(get = "pub")]
+    block_env: Option<BlockEnvOverride>,
 }
 
 // ========================================
@@ -58,6 +112,12 @@ impl Transaction {
         self.gas_limit().map(|gas| gas).unwrap_or(Gas::MAX)
     }
 
+    /// Returns `true` if the environment of the block the transaction is
+    /// simulated in is overridden, e.g. its base fee or timestamp.
+    pub fn overrides_block(&self) -> bool {
+        self.base_fee.is_some() || self.block_env.is_some()
+    }
+
     /// We should only try to read from the cache if we have a nonce and from address
     /// otherwise we are not certain that the transaction was
     /// cacheable. If the nonce is the same as the last nonce from
@@ -66,9 +126,88 @@ impl Transaction {
         self.nonce().is_some() && self.from().is_some()
     }
 
+    /// Returns `true` if this transaction is degenerate, without `to`,
+    /// `input` and `value` (and blobs), i.e. a no-op which cannot be
+    /// meaningfully estimated.
+    pub fn is_empty(&self) -> bool {
+        self.to.is_create()
+            && self.input.is_empty()
+            && self.value.is_zero()
+            && !self.is_blob_carrying()
+    }
+
+    /// Checks that the combination of `to`, `value`, `input` and blob fields
+    /// is possible, so that nonsensical transactions are rejected instead of
+    /// estimated. Empty transactions are not checked, they are handled by
+    /// [`EmptyTransactionPolicy`].
+    ///
+    /// Whether `to` is a contract is not known without state, so calls
+    /// without `value` and `input` are not rejected.
+    ///
+    /// # Throws
+    /// Throws [`Error::InvalidTransaction`] if a contract creation has no
+    /// init code, a blob carrying transaction creates a contract, or a max
+    /// fee per blob gas is set without blobs.
+    pub fn validate(&self) -> Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let invalid = |reason: &str| {
+            Err(Error::InvalidTransaction {
+                reason: reason.to_owned(),
+            })
+        };
+        if self.to.is_create() && self.is_blob_carrying() {
+            return invalid("blob carrying transactions cannot create contracts");
+        }
+        if self.to.is_create() && self.input.is_empty() {
+            return invalid("contract creation without init code");
+        }
+        if self.max_fee_per_blob_gas.is_some() && !self.is_blob_carrying() {
+            return invalid("max fee per blob gas without blobs");
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if this is an EIP-4844 transaction carrying blobs.
+    pub fn is_blob_carrying(&self) -> bool {
+        !self.blob_versioned_hashes.is_empty()
+    }
+
+    /// The blob gas used by this transaction, `None` if it carries no blobs.
+    pub fn blob_gas(&self) -> Option<Gas> {
+        self.is_blob_carrying()
+            .then(|| Gas::blob_gas(self.blob_versioned_hashes.len()))
+    }
+
+    /// The intrinsic gas of this transaction, i.e. the gas charged before
+    /// execution: the `21000` base, the cost of the calldata, of the access
+    /// list (if precomputed) and for contract creations the creation cost
+    /// and the cost of the initcode. Any estimate is at least this.
+    ///
+    /// Computed without simulation, it does not include the [EIP-7623][eip]
+    /// calldata floor, which applies to the gas used, not the gas charged
+    /// before execution.
+    ///
+    /// [eip]: https://eips.ethereum.org/EIPS/eip-7623
+    pub fn intrinsic_gas(&self) -> Gas {
+        let mut gas = *Gas::exact_native_token_transfer() + *Gas::calldata_cost(&self.input);
+        if let Some(precomputed) = &self.precomputed_access_list {
+            gas += *Gas::access_list_cost(precomputed.access_list());
+        }
+        if self.to.is_create() {
+            gas += *Gas::min_contract_creation() + *Gas::initcode_cost(&self.input);
+        }
+        Gas::from(gas)
+    }
+
     /// Classifies this transaction into a kind, either a pure ETH transfer,
-    /// contract creation, contract call or unknown.
+    /// contract creation, contract call, blob carrying or unknown.
     pub fn kind(&self) -> TransactionKind {
+        if self.is_blob_carrying() {
+            return TransactionKind::BlobCarrying;
+        }
+
         let to_is_none = self.to.to().is_none(); // Contract creation check
         let is_call = self.to.is_call();
         let is_create = self.to.is_create();
@@ -109,6 +248,68 @@ impl Transaction {
             .build()
             .unwrap()
     }
+
+    /// Converts this transaction into an unsigned EIP-1559 (alloy)
+    /// transaction on chain `chain_id` with the given fees, the inverse of
+    /// [`Transaction::from_eip1559`].
+    ///
+    /// A missing `nonce` becomes `0` and a missing `gas_limit` becomes `0`,
+    /// which [`Transaction::from_eip1559`] maps back to `None`. The access
+    /// list of a precomputed access list is kept, `from` and blob fields are
+    /// dropped, since EIP-1559 transactions cannot express them.
+    pub fn to_eip1559(
+        &self,
+        chain_id: u64,
+        max_fee_per_gas: u128,
+        max_priority_fee_per_gas: u128,
+    ) -> TxEip1559 {
+        TxEip1559 {
+            chain_id,
+            nonce: self.nonce.unwrap_or_default(),
+            gas_limit: self.gas_limit.map(|gas| *gas).unwrap_or_default(),
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            to: self.to,
+            value: self.value,
+            access_list: self
+                .precomputed_access_list
+                .as_ref()
+                .map(|precomputed| precomputed.access_list().clone())
+                .unwrap_or_default(),
+            input: self.input.clone(),
+        }
+    }
+
+    /// The unsigned RLP encoding, without type byte, of this transaction as
+    /// an EIP-1559 transaction with the default chain and fees of
+    /// `From<Transaction> for TxEip1559`, which can be decoded by
+    /// [`decode_transaction`], e.g. for use with the `/rlp` endpoint.
+    pub fn to_rlp(&self) -> Bytes {
+        let tx = TxEip1559::from(self.clone());
+        let mut out = Vec::with_capacity(tx.rlp_encoded_length());
+        tx.rlp_encode(&mut out);
+        Bytes::from(out)
+    }
+
+    /// Creates a new transaction from an EIP-4844 (alloy) transaction.
+    pub fn from_eip4844(value: TxEip4844) -> Self {
+        let gas_limit = if value.gas_limit == 0 {
+            None
+        } else {
+            Some(Gas::from(value.gas_limit))
+        };
+
+        TransactionBuilder::default()
+            .nonce(value.nonce)
+            .gas_limit(gas_limit)
+            .to(TxKind::Call(value.to))
+            .value(value.value)
+            .input(value.input)
+            .blob_versioned_hashes(value.blob_versioned_hashes)
+            .max_fee_per_blob_gas(value.max_fee_per_blob_gas)
+            .build()
+            .unwrap()
+    }
 }
 
 // ========================================
@@ -119,8 +320,7 @@ impl TryFrom<RawTransaction> for Transaction {
     type Error = crate::Error;
 
     fn try_from(value: RawTransaction) -> Result<Self> {
-        let tx = decode_eip1559_transaction(value.rlp.as_ref())?;
-        Ok(tx.into())
+        decode_transaction(value.rlp)
     }
 }
 
@@ -130,6 +330,20 @@ impl From<TxEip1559> for Transaction {
     }
 }
 
+impl From<Transaction> for TxEip1559 {
+    /// Mainnet (chain id `1`) without any fees, see
+    /// [`Transaction::to_eip1559`].
+    fn from(value: Transaction) -> Self {
+        value.to_eip1559(1, 0, 0)
+    }
+}
+
+impl From<TxEip4844> for Transaction {
+    fn from(value: TxEip4844) -> Self {
+        Self::from_eip4844(value)
+    }
+}
+
 // ========================================
 // Sample Values (test helpers)
 // ========================================
@@ -179,7 +393,8 @@ impl Transaction {
     pub fn sample_contract_creation_gas_limit(limit: impl Into<Option<Gas>>) -> Self {
         TransactionBuilder::default()
             .to(TxKind::Create)
-            .input(Bytes::from([0xab; 400])) // ERC20 contracts are often around 400 bytes
+            // Valid initcode (`JUMPDEST`s) of the size of an ERC20 contract, often around 400 bytes
+            .input(Bytes::from([0x5b; 400]))
             .gas_limit(limit)
             .build()
             .unwrap()
@@ -192,6 +407,40 @@ impl Transaction {
     pub fn sample_contract_creation() -> Self {
         Self::sample_contract_creation_gas_limit(None)
     }
+
+    /// A sample value for a contract creation transaction, which has a nonce
+    /// and from address.
+    ///
+    /// This is used for testing purposes only.
+    pub fn sample_contract_creation_cachable() -> Self {
+        TransactionBuilder::default()
+            .nonce(1)
+            .from(Address::from([0x12; 20]))
+            .to(TxKind::Create)
+            .input(Bytes::from([0x5b; 400]))
+            .build()
+            .unwrap()
+    }
+
+    /// A sample value for a blob carrying (EIP-4844) transaction, with
+    /// two blobs.
+    ///
+    /// This is used for testing purposes only.
+    pub fn sample_blob_carrying() -> Self {
+        // KZG versioned hashes have version byte `0x01`
+        let versioned_hash = |last: u8| {
+            let mut bytes = [0u8; 32];
+            bytes[0] = 0x01;
+            bytes[31] = last;
+            B256::from(bytes)
+        };
+        TransactionBuilder::default()
+            .to(Address::from([0x12; 20]))
+            .input(Bytes::from_static(&[0xde, 0xad]))
+            .blob_versioned_hashes(vec![versioned_hash(0x01), versioned_hash(0x02)])
+            .build()
+            .unwrap()
+    }
 }
 
 #[cfg(test)]
@@ -205,4 +454,121 @@ mod tests {
         assert!(Sut::sample_native_token_transfer_cachable().is_cacheable());
         assert!(!Sut::sample_native_token_transfer().is_cacheable());
     }
+
+    #[test]
+    fn blob_carrying() {
+        let sut = Sut::sample_blob_carrying();
+        assert_eq!(sut.kind(), TransactionKind::BlobCarrying);
+        assert_eq!(sut.blob_gas(), Some(Gas::blob_gas(2)));
+        assert_eq!(Sut::sample_native_token_transfer().blob_gas(), None);
+    }
+
+    #[test]
+    fn is_empty() {
+        let sut = TransactionBuilder::default()
+            .to(TxKind::Create)
+            .build()
+            .unwrap();
+        assert!(sut.is_empty());
+        assert_eq!(sut.kind(), TransactionKind::Unknown);
+        assert!(!Sut::sample_contract_creation().is_empty());
+        assert!(!Sut::sample_native_token_transfer().is_empty());
+    }
+
+    #[test]
+    fn validate() {
+        let invalid = |sut: Sut| sut.validate().unwrap_err().is_invalid_transaction();
+        assert!(invalid(
+            TransactionBuilder::default()
+                .to(TxKind::Create)
+                .value(U256::from(1))
+                .build()
+                .unwrap()
+        ));
+        assert!(invalid(
+            TransactionBuilder::default()
+                .to(TxKind::Create)
+                .input(Bytes::from_static(&[0x60]))
+                .blob_versioned_hashes(vec![B256::ZERO])
+                .build()
+                .unwrap()
+        ));
+        let mut fee_without_blobs = Sut::sample_native_token_transfer();
+        fee_without_blobs.set_max_fee_per_blob_gas(Some(1));
+        assert!(invalid(fee_without_blobs));
+
+        assert!(Sut::sample_contract_creation().validate().is_ok());
+        assert!(Sut::sample_blob_carrying().validate().is_ok());
+        assert!(Sut::default().validate().is_ok());
+    }
+
+    #[test]
+    fn intrinsic_gas_of_native_token_transfer() {
+        assert_eq!(
+            Sut::sample_native_token_transfer().intrinsic_gas(),
+            Gas::from(21_000)
+        );
+    }
+
+    #[test]
+    fn intrinsic_gas_of_contract_call() {
+        let sut = TransactionBuilder::default()
+            .to(Address::new([0xcc; 20]))
+            .input(Bytes::from([0x00, 0xab]))
+            .build()
+            .unwrap();
+        // base + one zero and one non zero byte of calldata
+        assert_eq!(sut.intrinsic_gas(), Gas::from(21_000 + 4 + 16));
+    }
+
+    #[test]
+    fn intrinsic_gas_of_contract_creation() {
+        let sut = TransactionBuilder::default()
+            .to(TxKind::Create)
+            .input(Bytes::from([0xab; 33]))
+            .build()
+            .unwrap();
+        // base + creation + calldata + initcode of 2 words
+        assert_eq!(
+            sut.intrinsic_gas(),
+            Gas::from(21_000 + 32_000 + 33 * 16 + 2 * 2)
+        );
+    }
+
+    #[test]
+    fn rlp_round_trip_of_contract_call() {
+        let sut = TransactionBuilder::default()
+            .nonce(7)
+            .to(Address::new([0xcc; 20]))
+            .value(U256::from(1))
+            .gas_limit(Gas::from(60_000))
+            .input(Bytes::from([0x00, 0xab]))
+            .build()
+            .unwrap();
+        assert_eq!(decode_transaction(sut.to_rlp()).unwrap(), sut);
+    }
+
+    #[test]
+    fn rlp_round_trip_of_contract_creation() {
+        let mut sut = Sut::sample_contract_creation_gas_limit(Gas::from(100_000));
+        sut.nonce = Some(3);
+        let decoded = decode_transaction(sut.to_rlp()).unwrap();
+        assert!(decoded.to().is_create());
+        assert_eq!(decoded, sut);
+    }
+
+    #[test]
+    fn to_eip1559_fills_missing_nonce_and_gas_limit() {
+        let sut = TransactionBuilder::default()
+            .to(Address::new([0xcc; 20]))
+            .build()
+            .unwrap();
+        let tx = sut.to_eip1559(10, 2, 1);
+        assert_eq!((tx.chain_id, tx.nonce, tx.gas_limit), (10, 0, 0));
+        assert_eq!((tx.max_fee_per_gas, tx.max_priority_fee_per_gas), (2, 1));
+
+        let decoded = decode_transaction(sut.to_rlp()).unwrap();
+        assert_eq!(*decoded.nonce(), Some(0));
+        assert_eq!(*decoded.gas_limit(), None);
+    }
 }
@@ -0,0 +1,16 @@
+use crate::prelude::*;
+
+/// The hash of a mined transaction to estimate, e.g.
+/// `{"hash": "0x..."}`, see [`Gastimator::transaction_by_hash`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, CopyGetters)]
+pub struct TransactionHashInput {
+    /// The hash of the transaction.
+    #[getset(get_copy = "pub")]
+    hash: B256,
+}
+
+impl From<B256> for TransactionHashInput {
+    fn from(hash: B256) -> Self {
+        Self { hash }
+    }
+}
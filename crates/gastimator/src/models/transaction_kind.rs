@@ -19,7 +19,8 @@ pub enum TransactionKind {
     ///
     /// Further gas usage depends on the contract code size
     /// 200 gas per byte of code. And EIP-170 introduced a
-    /// cap of 24_576 gas for the contract creation.
+    /// cap of 24_576 bytes of code, see
+    /// [`Gas::min_contract_creation_with_code`].
     ///
     /// If the contract has a constructor, the gas usage
     /// will be higher, depending on the cost of executing
@@ -37,6 +38,13 @@ pub enum TransactionKind {
     /// and the complexity of the function being called.
     ContractCall { with_native_token_transfer: bool },
 
+    /// An EIP-4844 transaction carrying blobs, e.g. rollup batches.
+    ///
+    /// Uses execution gas like any other call, but also **blob gas**,
+    /// 131_072 gas per blob, which is a separate dimension priced by the
+    /// blob base fee.
+    BlobCarrying,
+
     /// Unknown transaction type, not a pure ETH transfer,
     /// might an internal transfer, possibly a self destruct?
     Unknown,
@@ -0,0 +1,207 @@
+use opentelemetry::{KeyValue, metrics::MeterProvider as _};
+use opentelemetry_otlp::{MetricExporter, WithExportConfig};
+use opentelemetry_sdk::{
+    Resource,
+    metrics::{PeriodicReader, SdkMeterProvider},
+};
+
+use crate::prelude::*;
+
+/// Default interval between two exports of metrics to the OTLP endpoint.
+pub const DEFAULT_OTLP_EXPORT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Pushes the metrics of a [`Gastimator`] to an OpenTelemetry collector
+/// using the OpenTelemetry SDK, over OTLP/HTTP with protobuf encoding, i.e.
+/// `POST <endpoint>/v1/metrics`.
+///
+/// The counters of the cache (hits, misses and uncacheable transactions) and
+/// the outcomes of estimates and of the local and remote estimators, by
+/// `outcome` attribute, are exported as cumulative sums, and the size of the
+/// cache as a gauge, the same values as returned by `GET /cache/stats` and
+/// `GET /stats`.
+#[derive(Debug, Clone, Getters)]
+pub struct OtlpMetricsExporter {
+    /// The URL metrics are posted to, e.g. `http://localhost:4318/v1/metrics`.
+    #[getset(get = "pub")]
+    metrics_url: String,
+
+    /// The interval between two exports, e.g. `Duration::from_secs(10)`.
+    #[getset(get = "pub")]
+    interval: Duration,
+}
+
+// ========================================
+// Public Implementation
+// ========================================
+impl OtlpMetricsExporter {
+    /// Creates a new exporter to the OTLP collector at `endpoint`, e.g.
+    /// `"http://localhost:4318"`, exporting every `interval`.
+    ///
+    /// # Throws
+    /// Throws [`Error::InvalidOtlpEndpoint`] if `endpoint` is not a valid URL.
+    pub fn new(endpoint: impl AsRef<str>, interval: Duration) -> Result<Self> {
+        let endpoint =
+            reqwest::Url::parse(endpoint.as_ref()).map_err(|e| Error::InvalidOtlpEndpoint {
+                underlying: e.to_string(),
+            })?;
+        let metrics_url = format!("{}/v1/metrics", endpoint.as_str().trim_end_matches('/'));
+        Ok(Self {
+            metrics_url,
+            interval,
+        })
+    }
+
+    /// Starts exporting the metrics of `gastimator` every `interval` in the
+    /// background, failures are logged by the SDK, not returned. Exporting
+    /// stops when the returned provider is shut down or dropped.
+    ///
+    /// # Throws
+    /// Throws [`Error::OtlpExportFailed`] if the exporter cannot be built.
+    pub fn start(&self, gastimator: Arc<Gastimator>) -> Result<SdkMeterProvider> {
+        info!("Exporting metrics to: {}", self.metrics_url);
+        let exporter = MetricExporter::builder()
+            .with_http()
+            .with_endpoint(&self.metrics_url)
+            .build()
+            .map_err(|e| Error::OtlpExportFailed {
+                underlying: e.to_string(),
+            })?;
+        let provider = SdkMeterProvider::builder()
+            .with_reader(
+                PeriodicReader::builder(exporter)
+                    .with_interval(self.interval)
+                    .build(),
+            )
+            .with_resource(Resource::builder().with_service_name("gastimator").build())
+            .build();
+        Self::register_instruments(&provider, gastimator);
+        Ok(provider)
+    }
+}
+
+// ========================================
+// Private Implementation
+// ========================================
+impl OtlpMetricsExporter {
+    /// Registers the instruments of the metrics of `gastimator`, observed
+    /// when exported.
+    fn register_instruments(provider: &SdkMeterProvider, gastimator: Arc<Gastimator>) {
+        let meter = provider.meter("gastimator");
+        let cache_counter = |name: &'static str,
+                             unit: &'static str,
+                             description: &'static str,
+                             value: fn(&CacheStats) -> u64| {
+            let gastimator = gastimator.clone();
+            meter
+                .u64_observable_counter(name)
+                .with_unit(unit)
+                .with_description(description)
+                .with_callback(move |observer| {
+                    observer.observe(value(&gastimator.cache_stats()), &[])
+                })
+                .build();
+        };
+        cache_counter(
+            "gastimator.cache.hits",
+            "{lookup}",
+            "Number of lookups with a fresh entry",
+            |stats| stats.hits(),
+        );
+        cache_counter(
+            "gastimator.cache.misses",
+            "{lookup}",
+            "Number of lookups without a fresh entry",
+            |stats| stats.misses(),
+        );
+        cache_counter(
+            "gastimator.cache.uncacheable",
+            "{transaction}",
+            "Number of transactions which were not cacheable",
+            |stats| stats.uncacheable(),
+        );
+        meter
+            .u64_observable_gauge("gastimator.cache.size")
+            .with_unit("{entry}")
+            .with_description("Number of entries, including expired ones")
+            .with_callback({
+                let gastimator = gastimator.clone();
+                move |observer| observer.observe(gastimator.cache_stats().size() as u64, &[])
+            })
+            .build();
+
+        let outcome_counter =
+            |name: &'static str, description: &'static str, counts: fn(&Metrics) -> (u64, u64)| {
+                let gastimator = gastimator.clone();
+                meter
+                    .u64_observable_counter(name)
+                    .with_unit("{estimate}")
+                    .with_description(description)
+                    .with_callback(move |observer| {
+                        let (ok, error) = counts(gastimator.metrics());
+                        observer.observe(ok, &[KeyValue::new("outcome", "ok")]);
+                        observer.observe(error, &[KeyValue::new("outcome", "error")]);
+                    })
+                    .build();
+            };
+        outcome_counter(
+            "gastimator.estimates",
+            "Number of estimates, by outcome",
+            Metrics::estimate_counts,
+        );
+        outcome_counter(
+            "gastimator.local.estimates",
+            "Number of local simulations, by outcome",
+            Metrics::local_counts,
+        );
+        outcome_counter(
+            "gastimator.remote.estimates",
+            "Number of remote estimates, by outcome",
+            Metrics::remote_counts,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader};
+
+    use super::*;
+
+    type Sut = OtlpMetricsExporter;
+
+    #[test]
+    fn new_with_configured_endpoint() {
+        let sut = Sut::new("http://localhost:4318/", DEFAULT_OTLP_EXPORT_INTERVAL).unwrap();
+        assert_eq!(sut.metrics_url(), "http://localhost:4318/v1/metrics");
+    }
+
+    #[test]
+    fn new_with_invalid_endpoint_fails() {
+        assert!(matches!(
+            Sut::new("not a url", DEFAULT_OTLP_EXPORT_INTERVAL),
+            Err(Error::InvalidOtlpEndpoint { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn flush_posts_metrics_to_collector() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let request_line = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut request_line = String::new();
+            BufReader::new(&stream)
+                .read_line(&mut request_line)
+                .unwrap();
+            request_line
+        });
+        let gastimator = Arc::new(Gastimator::new("api-key".to_owned()));
+        let sut = Sut::new(endpoint, DEFAULT_OTLP_EXPORT_INTERVAL).unwrap();
+
+        let provider = sut.start(gastimator).unwrap();
+        // The collector does not respond, the export fails after it is received.
+        let _ = tokio::task::spawn_blocking(move || provider.force_flush()).await;
+
+        assert!(request_line.join().unwrap().starts_with("POST /v1/metrics"));
+    }
+}
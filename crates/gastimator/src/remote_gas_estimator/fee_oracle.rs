@@ -0,0 +1,13 @@
+use crate::prelude::*;
+
+/// A source of current fees of the chain, e.g. a JSON-RPC node, used to
+/// compute the fee of an estimate.
+#[async_trait::async_trait]
+pub trait FeeOracle {
+    /// The current gas price (in wei), i.e. `eth_gasPrice`.
+    async fn gas_price(&self) -> Result<U256>;
+
+    /// The current max priority fee per gas (in wei), i.e.
+    /// `eth_maxPriorityFeePerGas`.
+    async fn max_priority_fee_per_gas(&self) -> Result<U256>;
+}
@@ -1,4 +1,10 @@
+mod fee_oracle;
+mod no_remote_gas_estimator;
 #[allow(clippy::module_inception)]
 mod remote_gas_estimator;
+mod transaction_source;
 
+pub use fee_oracle::*;
+pub use no_remote_gas_estimator::*;
 pub use remote_gas_estimator::*;
+pub use transaction_source::*;
@@ -0,0 +1,18 @@
+use crate::prelude::*;
+
+/// A remote gas estimator which always fails, used when configured to be
+/// local only, i.e. estimates are computed by local simulation only.
+pub struct NoRemoteGasEstimator;
+
+// ========================================
+// RemoteGasEstimator Implementation
+// ========================================
+
+#[async_trait::async_trait]
+impl RemoteGasEstimator for NoRemoteGasEstimator {
+    async fn estimate_gas(&self, _: &Transaction) -> Result<Gas> {
+        Err(Error::RemoteGasEstimateFailed(
+            "Configured to be local only".to_owned(),
+        ))
+    }
+}
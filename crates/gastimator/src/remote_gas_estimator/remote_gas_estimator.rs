@@ -3,6 +3,18 @@ use crate::prelude::*;
 #[async_trait::async_trait]
 pub trait RemoteGasEstimator {
     async fn estimate_gas(&self, tx: &Transaction) -> Result<Gas>;
+
+    /// Estimates the gas usage of `tx` and also returns the raw response of
+    /// the remote, if available, unmodified.
+    ///
+    /// Default implementation does not have access to any raw response and
+    /// returns `None` for it.
+    async fn estimate_gas_with_raw_response(
+        &self,
+        tx: &Transaction,
+    ) -> Result<(Gas, Option<String>)> {
+        self.estimate_gas(tx).await.map(|gas| (gas, None))
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,21 @@
+use crate::prelude::*;
+
+/// A source of mined transactions of the chain, e.g. a JSON-RPC node, used
+/// to estimate a transaction by its hash.
+#[async_trait::async_trait]
+pub trait TransactionSource {
+    /// The transaction with `hash`, i.e. `eth_getTransactionByHash`.
+    ///
+    /// # Throws
+    /// Throws [`Error::TransactionNotFound`] if the chain has no transaction
+    /// with `hash`.
+    async fn transaction_by_hash(&self, hash: B256) -> Result<Transaction>;
+
+    /// The gas used by the mined transaction with `hash`, from its receipt,
+    /// i.e. `eth_getTransactionReceipt`.
+    ///
+    /// # Throws
+    /// Throws [`Error::TransactionNotFound`] if the chain has no mined
+    /// transaction with `hash`.
+    async fn gas_used_by(&self, hash: B256) -> Result<Gas>;
+}
@@ -0,0 +1,220 @@
+use crate::prelude::*;
+
+use std::collections::BinaryHeap;
+use std::sync::Mutex;
+use tokio::sync::oneshot;
+
+/// Priority of work submitted to the [`WorkQueue`], higher priority work
+/// is always dequeued before lower priority work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum WorkPriority {
+    /// E.g. items of a batch request.
+    Low,
+    /// E.g. single interactive requests.
+    #[default]
+    High,
+}
+
+/// A bounded work queue with priorities, backed by a fixed amount of
+/// "workers" (permits). Work which cannot get a worker immediately waits
+/// in a queue, ordered by [`WorkPriority`] and then by arrival (FIFO).
+///
+/// This prevents e.g. a large batch of low priority work from starving
+/// interactive high priority work.
+#[derive(derive_more::Debug)]
+#[debug("WorkQueue(size: {size})")]
+pub struct WorkQueue {
+    size: usize,
+    state: Mutex<WorkQueueState>,
+}
+
+/// A worker acquired from the [`WorkQueue`], the worker is handed over to
+/// the next waiter in line when this is dropped.
+#[derive(Debug)]
+pub struct WorkPermit<'a> {
+    queue: &'a WorkQueue,
+}
+
+// ========================================
+// Public Implementation
+// ========================================
+impl WorkQueue {
+    /// Creates a new queue with `size` workers, `size` is clamped to at
+    /// least one worker.
+    pub fn new(size: usize) -> Self {
+        let size = size.max(1);
+        Self {
+            size,
+            state: Mutex::new(WorkQueueState {
+                available: size,
+                next_sequence: 0,
+                waiters: BinaryHeap::new(),
+            }),
+        }
+    }
+
+    /// The number of workers of this queue.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Waits for a worker to become available, with respect to `priority`.
+    pub async fn acquire(&self, priority: WorkPriority) -> WorkPermit<'_> {
+        let mut pending = {
+            let mut state = self.state.lock().expect("WorkQueue lock poisoned");
+            if state.available > 0 && state.waiters.is_empty() {
+                state.available -= 1;
+                return WorkPermit { queue: self };
+            }
+            let (sender, receiver) = oneshot::channel();
+            let sequence = state.next_sequence;
+            state.next_sequence += 1;
+            state.waiters.push(Waiter {
+                priority,
+                sequence,
+                sender,
+            });
+            PendingWorker {
+                queue: self,
+                receiver,
+            }
+        };
+        (&mut pending.receiver)
+            .await
+            .expect("WorkQueue never drops a waiter without handing over a worker");
+        WorkPermit { queue: self }
+    }
+}
+
+// ========================================
+// Private Implementation
+// ========================================
+
+struct WorkQueueState {
+    /// Number of idle workers.
+    available: usize,
+    /// Monotonic counter used to preserve FIFO order within a priority.
+    next_sequence: u64,
+    waiters: BinaryHeap<Waiter>,
+}
+
+struct Waiter {
+    priority: WorkPriority,
+    sequence: u64,
+    sender: oneshot::Sender<()>,
+}
+
+impl PartialEq for Waiter {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority && self.sequence == other.sequence
+    }
+}
+impl Eq for Waiter {}
+impl PartialOrd for Waiter {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Waiter {
+    /// `BinaryHeap` is a max-heap: highest priority first, then lowest
+    /// sequence (earliest arrival) first.
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+impl WorkQueue {
+    /// Hands over a worker to the next waiter, or marks it as idle if
+    /// there are no (live) waiters.
+    fn release(&self) {
+        let mut state = self.state.lock().expect("WorkQueue lock poisoned");
+        while let Some(waiter) = state.waiters.pop() {
+            if waiter.sender.send(()).is_ok() {
+                return;
+            }
+            // Waiter was cancelled (its future dropped), try the next one.
+        }
+        state.available += 1;
+    }
+}
+
+/// A waiter's end of the hand over, if the waiting future is dropped
+/// right after a worker was handed over to it, the worker is released again.
+struct PendingWorker<'a> {
+    queue: &'a WorkQueue,
+    receiver: oneshot::Receiver<()>,
+}
+
+impl Drop for PendingWorker<'_> {
+    fn drop(&mut self) {
+        if self.receiver.try_recv().is_ok() {
+            self.queue.release();
+        }
+    }
+}
+
+impl Drop for WorkPermit<'_> {
+    fn drop(&mut self) {
+        self.queue.release();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    type Sut = WorkQueue;
+
+    #[tokio::test]
+    async fn high_priority_is_dequeued_before_low() {
+        let sut = Arc::new(Sut::new(1));
+        let order = Arc::new(Mutex::new(Vec::new()));
+
+        let busy = sut.acquire(WorkPriority::High).await;
+
+        let mut handles = Vec::new();
+        for (label, priority) in [
+            ("low0", WorkPriority::Low),
+            ("low1", WorkPriority::Low),
+            ("high", WorkPriority::High),
+        ] {
+            let sut = sut.clone();
+            let order = order.clone();
+            handles.push(tokio::spawn(async move {
+                let _permit = sut.acquire(priority).await;
+                order.lock().unwrap().push(label);
+            }));
+            // ensure deterministic arrival order
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        drop(busy);
+        for handle in handles {
+            handle.await.unwrap();
+        }
+
+        assert_eq!(*order.lock().unwrap(), vec!["high", "low0", "low1"]);
+    }
+
+    #[tokio::test]
+    async fn cancelled_waiter_does_not_leak_worker() {
+        let sut = Sut::new(1);
+        let busy = sut.acquire(WorkPriority::High).await;
+        let cancelled =
+            tokio::time::timeout(Duration::from_millis(5), sut.acquire(WorkPriority::High)).await;
+        assert!(cancelled.is_err());
+        drop(busy);
+
+        let acquired =
+            tokio::time::timeout(Duration::from_millis(50), sut.acquire(WorkPriority::Low)).await;
+        assert!(acquired.is_ok());
+    }
+
+    #[test]
+    fn size_is_at_least_one() {
+        assert_eq!(Sut::new(0).size(), 1);
+    }
+}
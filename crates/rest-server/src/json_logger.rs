@@ -0,0 +1,130 @@
+use std::io::Write;
+
+use log::kv::{Key, Value, VisitSource};
+
+use crate::prelude::*;
+
+/// A [`log::Log`] writing one JSON object per line to stderr, with the
+/// `timestamp_ms`, `level`, `target` and `message` of the record, the
+/// `request_id` of the current request, if any, see [`current_request_id`],
+/// and its key-values, e.g. the fields of a span logged by [`SpanLogger`], as
+/// keys. Filtered by `RUST_LOG`, like the text logs.
+pub(crate) struct JsonLogger {
+    filter: env_logger::filter::Filter,
+}
+
+impl JsonLogger {
+    /// A logger filtered by the `RUST_LOG` environment variable.
+    pub(crate) fn from_env() -> Self {
+        Self {
+            filter: env_logger::filter::Builder::from_env("RUST_LOG").build(),
+        }
+    }
+
+    /// Installs this logger as the logger of `log`.
+    pub(crate) fn init(self) -> Result<(), log::SetLoggerError> {
+        log::set_max_level(self.filter.filter());
+        log::set_boxed_logger(Box::new(self))
+    }
+
+    /// The JSON object of `record`.
+    fn json(record: &log::Record<'_>) -> serde_json::Value {
+        let timestamp_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |since| since.as_millis() as u64);
+        let mut json = serde_json::Map::new();
+        json.insert("timestamp_ms".to_owned(), timestamp_ms.into());
+        json.insert("level".to_owned(), record.level().as_str().into());
+        json.insert("target".to_owned(), record.target().into());
+        json.insert("message".to_owned(), record.args().to_string().into());
+        if let Some(request_id) = current_request_id() {
+            json.insert("request_id".to_owned(), request_id.as_str().into());
+        }
+        let _ = record.key_values().visit(&mut KeyValues(&mut json));
+        serde_json::Value::Object(json)
+    }
+}
+
+/// Inserts visited key-values into a JSON object, numbers and booleans as
+/// such, anything else as string.
+struct KeyValues<'a>(&'a mut serde_json::Map<String, serde_json::Value>);
+
+impl<'kvs> VisitSource<'kvs> for KeyValues<'_> {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), log::kv::Error> {
+        let value = if let Some(bool) = value.to_bool() {
+            bool.into()
+        } else if let Some(u64) = value.to_u64() {
+            u64.into()
+        } else if let Some(i64) = value.to_i64() {
+            i64.into()
+        } else if let Some(f64) = value.to_f64() {
+            f64.into()
+        } else {
+            value.to_string().into()
+        };
+        self.0.insert(key.as_str().to_owned(), value);
+        Ok(())
+    }
+}
+
+impl log::Log for JsonLogger {
+    fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+        self.filter.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        if !self.filter.matches(record) {
+            return;
+        }
+        let line = Self::json(record).to_string();
+        let _ = writeln!(std::io::stderr().lock(), "{line}");
+    }
+
+    fn flush(&self) {
+        let _ = std::io::stderr().flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = JsonLogger;
+
+    #[test]
+    fn record_with_key_values() {
+        let key_values: &[(&str, Value<'_>)] = &[
+            ("tx_kind", Value::from("contract_creation")),
+            ("cacheable", Value::from(true)),
+            ("elapsed_ms", Value::from(1.5)),
+        ];
+        let json = Sut::json(
+            &log::Record::builder()
+                .level(log::Level::Debug)
+                .target("gastimator")
+                .args(format_args!("estimate took 1.500 ms"))
+                .key_values(&key_values)
+                .build(),
+        );
+
+        assert_eq!(json["level"], "DEBUG");
+        assert_eq!(json["target"], "gastimator");
+        assert_eq!(json["message"], "estimate took 1.500 ms");
+        assert_eq!(json["tx_kind"], "contract_creation");
+        assert_eq!(json["cacheable"], true);
+        assert_eq!(json["elapsed_ms"], 1.5);
+        assert!(json["timestamp_ms"].is_u64());
+        assert!(json.get("request_id").is_none());
+    }
+
+    #[tokio::test]
+    async fn record_of_request_has_request_id() {
+        let request_id = RequestId::new("req-1").unwrap();
+        let json = with_request_id(request_id, async {
+            Sut::json(&log::Record::builder().args(format_args!("hi")).build())
+        })
+        .await;
+
+        assert_eq!(json["request_id"], "req-1");
+    }
+}
@@ -1,15 +1,35 @@
+mod json_logger;
+mod ndjson;
+mod rate_limiter;
+mod request_id;
 mod server;
+mod span_logger;
+mod web_socket;
 
 pub mod prelude {
 
     // INTERNAL MODULES
+    pub(crate) use crate::json_logger::*;
+    pub(crate) use crate::ndjson::*;
+    pub(crate) use crate::rate_limiter::*;
+    pub(crate) use crate::request_id::*;
     pub use crate::server::*;
+    pub(crate) use crate::span_logger::*;
+    pub(crate) use crate::web_socket::*;
 
     // INTERNAL CRATES
     pub use gastimator::prelude::*;
 
     // EXTERNAL CRATES
-    pub use axum::{Json, Router, response::IntoResponse, routing::post};
+    pub use axum::{
+        Extension, Json, Router,
+        extract::{Query, Request, State},
+        http::{HeaderMap, HeaderValue, Method, StatusCode, header},
+        middleware::{self, Next},
+        response::{IntoResponse, Response},
+        routing::{get, post},
+    };
+    pub use std::collections::HashSet;
     pub use tokio::sync::oneshot;
 }
 
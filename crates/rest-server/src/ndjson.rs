@@ -0,0 +1,198 @@
+use crate::prelude::*;
+
+use axum::body::{Body, Bytes};
+use futures_util::{Stream, StreamExt, stream};
+
+/// The state of [`lines`], the chunks yet to be read, the bytes read of the
+/// current line, whether all chunks are read and whether the rest of the
+/// current line is skipped, since it is too long.
+struct LinesState {
+    chunks: futures_util::stream::BoxStream<'static, Result<Bytes, String>>,
+    buffer: Vec<u8>,
+    done: bool,
+    skipping: bool,
+}
+
+/// The lines of the stream of `chunks`, without the trailing newline, read
+/// lazily so that only the current line is buffered. A failure to read a
+/// chunk is yielded and ends the stream. A line longer than
+/// `max_line_bytes` is yielded as a failure without being buffered, the
+/// stream continues with the next line.
+fn lines<E: std::fmt::Display + 'static>(
+    chunks: impl Stream<Item = Result<Bytes, E>> + Send + 'static,
+    max_line_bytes: usize,
+) -> impl Stream<Item = Result<Vec<u8>, String>> + Send + 'static {
+    let too_long = move || format!("Line longer than {max_line_bytes} bytes");
+    let state = LinesState {
+        chunks: chunks.map(|chunk| chunk.map_err(|e| e.to_string())).boxed(),
+        buffer: Vec::new(),
+        done: false,
+        skipping: false,
+    };
+    stream::unfold(state, move |mut state| async move {
+        loop {
+            if let Some(newline) = state.buffer.iter().position(|b| *b == b'\n') {
+                let mut line = state.buffer.drain(..=newline).collect::<Vec<_>>();
+                if std::mem::take(&mut state.skipping) {
+                    continue;
+                }
+                line.pop();
+                if line.len() > max_line_bytes {
+                    return Some((Err(too_long()), state));
+                }
+                return Some((Ok(line), state));
+            }
+            if state.skipping {
+                state.buffer.clear();
+            } else if state.buffer.len() > max_line_bytes {
+                state.buffer.clear();
+                state.skipping = true;
+                return Some((Err(too_long()), state));
+            }
+            if state.done {
+                if state.buffer.is_empty() {
+                    return None;
+                }
+                let line = std::mem::take(&mut state.buffer);
+                return Some((Ok(line), state));
+            }
+            match state.chunks.next().await {
+                Some(Ok(chunk)) => state.buffer.extend_from_slice(&chunk),
+                Some(Err(e)) => {
+                    state.buffer.clear();
+                    state.done = true;
+                    return Some((Err(e), state));
+                }
+                None => state.done = true,
+            }
+        }
+    })
+}
+
+/// The transactions of the newline-delimited JSON `chunks`, one per line,
+/// blank lines are skipped. Lines which cannot be read or parsed, or are
+/// longer than `max_line_bytes`, are yielded as [`Error::InvalidNdjsonLine`],
+/// with the 1-based number of the line.
+fn transactions<E: std::fmt::Display + 'static>(
+    chunks: impl Stream<Item = Result<Bytes, E>> + Send + 'static,
+    max_line_bytes: usize,
+) -> impl Stream<Item = Result<Transaction>> + Send + 'static {
+    lines(chunks, max_line_bytes)
+        .enumerate()
+        .filter_map(|(index, line)| async move {
+            let invalid = |underlying: String| Error::InvalidNdjsonLine {
+                line: index + 1,
+                underlying,
+            };
+            match line {
+                Ok(line) if line.trim_ascii().is_empty() => None,
+                Ok(line) => Some(serde_json::from_slice(&line).map_err(|e| invalid(e.to_string()))),
+                Err(e) => Some(Err(invalid(e))),
+            }
+        })
+}
+
+/// The transactions of the newline-delimited JSON `body`, see
+/// [`transactions`].
+pub(crate) fn ndjson_transactions(
+    body: Body,
+    max_line_bytes: usize,
+) -> impl Stream<Item = Result<Transaction>> + Send + 'static {
+    transactions(body.into_data_stream(), max_line_bytes)
+}
+
+/// The newline-delimited JSON body of the stream of `items`, one per line.
+pub(crate) fn ndjson_body(items: impl Stream<Item = impl Serialize> + Send + 'static) -> Body {
+    Body::from_stream(items.map(|item| {
+        let mut line = serde_json::to_vec(&item)?;
+        line.push(b'\n');
+        Ok::<_, serde_json::Error>(Bytes::from(line))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The results of the transactions of `chunks`, with lines of at most
+    /// `max_line_bytes`.
+    async fn parse_with_max(
+        chunks: &[&'static str],
+        max_line_bytes: usize,
+    ) -> Vec<Result<Transaction>> {
+        let chunks = chunks
+            .iter()
+            .map(|chunk| Ok::<_, std::convert::Infallible>(Bytes::from_static(chunk.as_bytes())))
+            .collect::<Vec<_>>();
+        transactions(stream::iter(chunks), max_line_bytes)
+            .collect()
+            .await
+    }
+
+    /// The results of the transactions of `chunks`.
+    async fn parse(chunks: &[&'static str]) -> Vec<Result<Transaction>> {
+        parse_with_max(chunks, usize::MAX).await
+    }
+
+    #[tokio::test]
+    async fn lines_split_across_chunks() {
+        let txs = parse(&[
+            r#"{"to": "0x000000000000000000000000000000000000000a"}"#,
+            "\n\n{\"to\": \"0x0000000000",
+            r#"00000000000000000000000000000b"}"#,
+        ])
+        .await;
+
+        let to = txs
+            .into_iter()
+            .map(|tx| tx.unwrap().to().to().copied())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            to,
+            vec![
+                Some(Address::with_last_byte(0x0a)),
+                Some(Address::with_last_byte(0x0b))
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_line_does_not_end_stream() {
+        let txs = parse(&[
+            "not json\n",
+            r#"{"to": "0x000000000000000000000000000000000000000a"}"#,
+        ])
+        .await;
+
+        assert_eq!(txs.len(), 2);
+        assert!(matches!(
+            txs[0],
+            Err(Error::InvalidNdjsonLine { line: 1, .. })
+        ));
+        assert!(txs[1].is_ok());
+    }
+
+    #[tokio::test]
+    async fn too_long_line_is_invalid_without_ending_stream() {
+        let txs = parse_with_max(
+            &[
+                r#"{"to": "0x000000000000000000000000000000000000000a", "#,
+                r#""value": "0x1"}"#,
+                "\n",
+                r#"{"to": "0x000000000000000000000000000000000000000b"}"#,
+            ],
+            60,
+        )
+        .await;
+
+        assert_eq!(txs.len(), 2);
+        assert!(matches!(
+            txs[0],
+            Err(Error::InvalidNdjsonLine { line: 1, .. })
+        ));
+        assert_eq!(
+            txs[1].as_ref().unwrap().to().to().copied(),
+            Some(Address::with_last_byte(0x0b))
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use std::net::IpAddr;
+use std::sync::Mutex;
+
+use dashmap::DashMap;
+
+use crate::prelude::*;
+
+/// Maximum number of clients tracked, new clients beyond it are rejected
+/// until idle ones, whose bucket is full again, are forgotten, bounding the
+/// memory used by many distinct clients.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Minimum interval between scans forgetting idle clients, so that a flood
+/// of new clients at the limit does not scan all clients on every request.
+const EVICTION_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The tokens left of a client and when they were last refilled.
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// Limits the rate of requests per client IP with a token bucket per
+/// client, see [`RateLimit`].
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    limit: RateLimit,
+    buckets: DashMap<IpAddr, Bucket>,
+
+    /// When idle clients were last forgotten, if ever.
+    evicted_at: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    /// A limiter of requests of each client to `limit`.
+    pub(crate) fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            buckets: DashMap::new(),
+            evicted_at: Mutex::new(None),
+        }
+    }
+
+    /// Takes a token of the bucket of `client` at `now`, returning `false`
+    /// if none is left, i.e. the request is to be rejected, or if `client`
+    /// is new and [`MAX_TRACKED_CLIENTS`] are tracked, none of them idle.
+    pub(crate) fn try_acquire(&self, client: IpAddr, now: Instant) -> bool {
+        if !self.buckets.contains_key(&client) && self.buckets.len() >= MAX_TRACKED_CLIENTS {
+            self.evict_idle(now);
+            if self.buckets.len() >= MAX_TRACKED_CLIENTS {
+                warn!("Too many clients tracked, rejecting new client {client}");
+                return false;
+            }
+        }
+        let mut bucket = self.buckets.entry(client).or_insert(Bucket {
+            tokens: self.burst(),
+            refilled_at: now,
+        });
+        bucket.tokens = self.refilled(&bucket, now);
+        bucket.refilled_at = now;
+        if bucket.tokens < 1.0 {
+            return false;
+        }
+        bucket.tokens -= 1.0;
+        true
+    }
+}
+
+// ========================================
+// Private
+// ========================================
+impl RateLimiter {
+    fn burst(&self) -> f64 {
+        self.limit.burst() as f64
+    }
+
+    /// The tokens of `bucket` refilled until `now`, at most the burst.
+    fn refilled(&self, bucket: &Bucket, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(bucket.refilled_at);
+        (bucket.tokens + elapsed.as_secs_f64() * self.limit.per_second()).min(self.burst())
+    }
+
+    /// Forgets idle clients, whose bucket is full again, at most once per
+    /// [`EVICTION_INTERVAL`] and by one caller at a time.
+    fn evict_idle(&self, now: Instant) {
+        let Ok(mut evicted_at) = self.evicted_at.try_lock() else {
+            return;
+        };
+        if evicted_at.is_some_and(|at| now.saturating_duration_since(at) < EVICTION_INTERVAL) {
+            return;
+        }
+        *evicted_at = Some(now);
+        self.buckets
+            .retain(|_, bucket| self.refilled(bucket, now) < self.burst());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Sut = RateLimiter;
+
+    const CLIENT: IpAddr = IpAddr::V4(std::net::Ipv4Addr::LOCALHOST);
+
+    #[test]
+    fn burst_then_refill() {
+        let sut = Sut::new(RateLimit::new(2.0, 3));
+        let now = Instant::now();
+
+        assert!((0..3).all(|_| sut.try_acquire(CLIENT, now)));
+        assert!(!sut.try_acquire(CLIENT, now));
+        assert!(sut.try_acquire(CLIENT, now + Duration::from_millis(500)));
+        assert!(!sut.try_acquire(CLIENT, now + Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn clients_are_limited_independently() {
+        let sut = Sut::new(RateLimit::new(1.0, 1));
+        let now = Instant::now();
+        let other = IpAddr::V4(std::net::Ipv4Addr::new(10, 0, 0, 1));
+
+        assert!(sut.try_acquire(CLIENT, now));
+        assert!(!sut.try_acquire(CLIENT, now));
+        assert!(sut.try_acquire(other, now));
+    }
+
+    #[test]
+    fn new_clients_rejected_at_max_until_idle_ones_are_forgotten() {
+        let sut = Sut::new(RateLimit::new(1.0, 1));
+        let now = Instant::now();
+        let client = |i: usize| IpAddr::V6(std::net::Ipv6Addr::from(i as u128 + 1));
+
+        assert!((0..MAX_TRACKED_CLIENTS).all(|i| sut.try_acquire(client(i), now)));
+        assert!(!sut.try_acquire(CLIENT, now));
+        // tracked clients are still served
+        assert!(sut.try_acquire(client(0), now + Duration::from_secs(1)));
+
+        let later = now + EVICTION_INTERVAL + Duration::from_secs(1);
+        assert!(sut.try_acquire(CLIENT, later));
+        assert!(sut.buckets.len() < MAX_TRACKED_CLIENTS);
+    }
+}
@@ -0,0 +1,48 @@
+use crate::prelude::*;
+
+tokio::task_local! {
+    /// The id of the request being handled by the current task.
+    static REQUEST_ID: RequestId;
+}
+
+/// The header of the id of a request, read from requests and echoed in
+/// responses.
+pub(crate) const X_REQUEST_ID: &str = "x-request-id";
+
+/// The id of the request being handled by the current task, if any. Work
+/// spawned onto other tasks, e.g. local simulations, has none.
+pub(crate) fn current_request_id() -> Option<RequestId> {
+    REQUEST_ID.try_with(RequestId::clone).ok()
+}
+
+/// Runs `future` as the handling of the request with `request_id`, see
+/// [`current_request_id`].
+pub(crate) async fn with_request_id<F: Future>(request_id: RequestId, future: F) -> F::Output {
+    REQUEST_ID.scope(request_id, future).await
+}
+
+/// A [`log::Log`] prefixing the messages of `L` with the id of the current
+/// request, if any, e.g. `[9b2e6c1a-...] Cache hit`.
+pub(crate) struct RequestIdPrefixLogger<L>(pub(crate) L);
+
+impl<L: log::Log> log::Log for RequestIdPrefixLogger<L> {
+    fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+        self.0.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        match current_request_id() {
+            Some(request_id) => self.0.log(
+                &record
+                    .to_builder()
+                    .args(format_args!("[{request_id}] {}", record.args()))
+                    .build(),
+            ),
+            None => self.0.log(record),
+        }
+    }
+
+    fn flush(&self) {
+        self.0.flush()
+    }
+}
@@ -1,47 +1,553 @@
 use crate::prelude::*;
 
+use futures_util::StreamExt;
+use tower_http::compression::CompressionLayer;
+use tower_http::compression::predicate::{DefaultPredicate, NotForContentType, Predicate};
+use tower_http::cors::{AllowHeaders, AllowOrigin, CorsLayer};
+use tower_http::decompression::RequestDecompressionLayer;
+use tower_http::limit::RequestBodyLimitLayer;
+use tower_http::timeout::TimeoutLayer;
+use tracing::Instrument;
+
+/// The header of a request whose response is stored by its value, so that
+/// retries with the same value are answered with the stored response, see
+/// [`GastimatorConfig::idempotency_ttl`].
+const IDEMPOTENCY_KEY: &str = "idempotency-key";
+
 // ========================================
 // Private
 // ========================================
 
+/// Responds with the response stored under the `Idempotency-Key` header of
+/// `headers` for `route`, if any, so that retried requests are not estimated
+/// again, else with the response of `estimate`, which is stored under the
+/// key if successful. Errors are not stored, so retries of failed requests
+/// are estimated again. A key reused for another request, i.e. one of
+/// another `request_hash`, to the same route fails with
+/// [`Error::IdempotencyKeyReused`].
+async fn idempotent<T>(
+    route: &str,
+    headers: &HeaderMap,
+    request_hash: B256,
+    gastimator: &Gastimator,
+    estimate: impl Future<Output = Result<T>>,
+) -> Result<Json<T>>
+where
+    T: Clone + Into<IdempotentResponse> + TryFrom<IdempotentResponse>,
+{
+    let key = headers
+        .get(IDEMPOTENCY_KEY)
+        .and_then(|value| value.to_str().ok())
+        .map(|key| IdempotencyKey::new(route, key));
+    let Some(key) = key else {
+        return estimate.await.map(Json);
+    };
+    // Keys are scoped by route, so the stored response is of type `T`
+    let stored = gastimator
+        .idempotent_response(&key, request_hash)?
+        .and_then(|response| T::try_from(response).ok());
+    if let Some(response) = stored {
+        debug!("Replaying response of idempotency key `{}`", key.key());
+        return Ok(Json(response));
+    }
+    let response = estimate.await?;
+    gastimator.store_idempotent_response(key, request_hash, response.clone());
+    Ok(Json(response))
+}
+
 // Uh... axum needs this. I can probably impl Handler for Gastimator instead.
 // but seems not worth it for now.
 async fn estimate_gas(
+    Extension(request_id): Extension<RequestId>,
+    headers: HeaderMap,
+    Query(options): Query<EstimateOptions>,
     Json(tx): Json<Transaction>,
     gastimator: Arc<Gastimator>,
 ) -> Result<Json<GasEstimateResponse>> {
-    gastimator.estimate_gas(tx).await.map(Json)
+    let request_hash = IdempotencyKey::request_hash(&(&tx, &options));
+    idempotent(
+        "/tx",
+        &headers,
+        request_hash,
+        &gastimator,
+        gastimator.estimate_gas_with_options(tx, options),
+    )
+    .await
+    .map(|Json(response)| Json(response.with_request_id(request_id)))
 }
 
 // Uh... axum needs this. I can probably impl Handler for Gastimator instead.
 // but seems not worth it for now.
 async fn estimate_gas_rlp(
+    Extension(request_id): Extension<RequestId>,
+    headers: HeaderMap,
+    Query(options): Query<EstimateOptions>,
     Json(tx): Json<RawTransaction>,
     gastimator: Arc<Gastimator>,
+    max_rlp_bytes: usize,
+) -> Result<Json<GasEstimateResponse>> {
+    let request_hash = IdempotencyKey::request_hash(&(&tx, &options));
+    let tx = tx.decode(max_rlp_bytes)?;
+    let decoded = options.decoded().then(|| tx.clone());
+    idempotent("/rlp", &headers, request_hash, &gastimator, async {
+        let response = gastimator.estimate_gas_with_options(tx, options).await?;
+        Ok(match decoded {
+            Some(tx) => response.with_decoded_transaction(tx),
+            None => response,
+        })
+    })
+    .await
+    .map(|Json(response)| Json(response.with_request_id(request_id)))
+}
+
+/// Estimates the raw transaction of `body`, a plain hex string of its RLP,
+/// with or without `0x` prefix, e.g. piped from a CLI, instead of JSON.
+async fn estimate_gas_rlp_raw(
+    request_id: Extension<RequestId>,
+    headers: HeaderMap,
+    query: Query<EstimateOptions>,
+    body: String,
+    gastimator: Arc<Gastimator>,
+    max_rlp_bytes: usize,
+) -> Result<Json<GasEstimateResponse>> {
+    let tx = body.trim().parse::<RawTransaction>()?;
+    estimate_gas_rlp(
+        request_id,
+        headers,
+        query,
+        Json(tx),
+        gastimator,
+        max_rlp_bytes,
+    )
+    .await
+}
+
+/// Estimates the mined transaction with the hash of `input`, fetched from
+/// the remote.
+async fn estimate_gas_by_hash(
+    Extension(request_id): Extension<RequestId>,
+    headers: HeaderMap,
+    Query(options): Query<EstimateOptions>,
+    Json(input): Json<TransactionHashInput>,
+    gastimator: Arc<Gastimator>,
+) -> Result<Json<GasEstimateResponse>> {
+    let request_hash = IdempotencyKey::request_hash(&(&input, &options));
+    idempotent("/tx/by-hash", &headers, request_hash, &gastimator, async {
+        let tx = gastimator.transaction_by_hash(input.hash()).await?;
+        gastimator.estimate_gas_with_options(tx, options).await
+    })
+    .await
+    .map(|Json(response)| Json(response.with_request_id(request_id)))
+}
+
+/// Estimates the deployment of the contract bytecode of `request`.
+async fn estimate_gas_deploy(
+    Extension(request_id): Extension<RequestId>,
+    headers: HeaderMap,
+    Query(options): Query<EstimateOptions>,
+    Json(request): Json<DeployRequest>,
+    gastimator: Arc<Gastimator>,
 ) -> Result<Json<GasEstimateResponse>> {
-    estimate_gas(Json(Transaction::try_from(tx)?), gastimator).await
+    let request_hash = IdempotencyKey::request_hash(&(&request, &options));
+    let tx = Transaction::try_from(request)?;
+    idempotent(
+        "/deploy",
+        &headers,
+        request_hash,
+        &gastimator,
+        gastimator.estimate_gas_with_options(tx, options),
+    )
+    .await
+    .map(|Json(response)| Json(response.with_request_id(request_id)))
+}
+
+/// Estimates the mined transaction with the hash of `input` and compares
+/// the estimate against the gas it actually used.
+async fn accuracy(
+    Query(options): Query<EstimateOptions>,
+    Json(input): Json<TransactionHashInput>,
+    gastimator: Arc<Gastimator>,
+) -> Result<Json<AccuracyResponse>> {
+    Ok(Json(gastimator.accuracy(input.hash(), options).await?))
+}
+
+async fn health() -> Json<ServiceStatusResponse> {
+    Json(ServiceStatus::Ok.into())
+}
+
+// Uh... axum needs this. I can probably impl Handler for Gastimator instead.
+// but seems not worth it for now.
+async fn estimate_gas_batch(
+    headers: HeaderMap,
+    Query(options): Query<EstimateOptions>,
+    Json(txs): Json<Vec<Transaction>>,
+    gastimator: Arc<Gastimator>,
+) -> Result<Json<Vec<BatchGasEstimateItem>>> {
+    let request_hash = IdempotencyKey::request_hash(&(&txs, &options));
+    idempotent("/tx/batch", &headers, request_hash, &gastimator, async {
+        let results = gastimator.estimate_gas_batch(txs, options).await;
+        Ok(results
+            .into_iter()
+            .map(BatchGasEstimateItem::from)
+            .collect())
+    })
+    .await
+}
+
+/// Estimates the newline-delimited JSON transactions of `body`, streaming
+/// back one [`BatchGasEstimateItem`] per line as they are estimated.
+async fn estimate_gas_stream(
+    Query(options): Query<EstimateOptions>,
+    body: axum::body::Body,
+    gastimator: Arc<Gastimator>,
+    max_line_bytes: usize,
+) -> impl IntoResponse {
+    let items = gastimator
+        .estimate_gas_stream(ndjson_transactions(body, max_line_bytes), options)
+        .map(BatchGasEstimateItem::from);
+    (
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        ndjson_body(items),
+    )
+}
+
+/// Upgrades the connection to a WebSocket on which transactions are
+/// estimated, see [`estimate_gas_web_socket`].
+async fn web_socket(
+    Query(options): Query<EstimateOptions>,
+    upgrade: axum::extract::WebSocketUpgrade,
+    gastimator: Arc<Gastimator>,
+) -> Response {
+    upgrade.on_upgrade(move |socket| estimate_gas_web_socket(socket, options, gastimator))
+}
+
+// Uh... axum needs this. I can probably impl Handler for Gastimator instead.
+// but seems not worth it for now.
+async fn simulate(
+    Query(options): Query<SimulateOptions>,
+    Json(tx): Json<Transaction>,
+    gastimator: Arc<Gastimator>,
+) -> Result<Json<SimulationResponse>> {
+    let response = if options.state_diff() {
+        let (estimate, state_diff) = gastimator.estimate_with_state_diff(tx).await?;
+        SimulationResponse::new(estimate, state_diff)
+    } else {
+        SimulationResponse::new(gastimator.estimate_gas(tx).await?, None)
+    };
+    Ok(Json(response))
+}
+
+/// Locally simulates the transaction as a read-only call, returning the
+/// data returned by it.
+async fn call(
+    Json(tx): Json<Transaction>,
+    gastimator: Arc<Gastimator>,
+) -> Result<Json<CallOutput>> {
+    gastimator.simulate_call(tx).await.map(Json)
+}
+
+/// Simulates the transactions in order against shared state, returning the
+/// gas used by each and in total.
+async fn simulate_bundle(
+    Json(txs): Json<Vec<Transaction>>,
+    gastimator: Arc<Gastimator>,
+) -> Result<Json<BundleSimulationResponse>> {
+    gastimator.simulate_bundle(txs).await.map(Json)
+}
+
+// Uh... axum needs this. I can probably impl Handler for Gastimator instead.
+// but seems not worth it for now.
+async fn replace(
+    Query(options): Query<EstimateOptions>,
+    Json(request): Json<ReplacementRequest>,
+    gastimator: Arc<Gastimator>,
+) -> Result<Json<ReplacementResponse>> {
+    let (original, replacement) = request.into_parts();
+    gastimator
+        .estimate_replacement(original, replacement, options)
+        .await
+        .map(Json)
+}
+
+/// The metrics of the gastimator in the Prometheus text exposition format.
+async fn metrics(gastimator: Arc<Gastimator>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        gastimator.render_metrics(),
+    )
+}
+
+/// Classifies the transaction, without estimating it.
+async fn classify(
+    Json(tx): Json<TransactionOrRaw>,
+    max_rlp_bytes: usize,
+) -> Result<Json<ClassificationResponse>> {
+    let tx = tx.decode(max_rlp_bytes)?;
+    Ok(Json(ClassificationResponse::from(&tx)))
+}
+
+/// Decodes the RLP and recovers its signer, without estimating it.
+async fn verify_rlp(
+    Json(raw): Json<RawTransaction>,
+    max_rlp_bytes: usize,
+) -> Result<Json<RlpVerifyResponse>> {
+    RlpVerifyResponse::verify(raw, max_rlp_bytes).map(Json)
+}
+
+async fn estimator_stats(gastimator: Arc<Gastimator>) -> Json<EstimatorStats> {
+    Json(gastimator.metrics().estimator_stats())
+}
+
+async fn cache_stats(gastimator: Arc<Gastimator>) -> Json<CacheStats> {
+    Json(gastimator.cache_stats())
+}
+
+/// Clears the cache, if the bearer token of the `Authorization` header is
+/// the admin token of `server_config`, if any.
+async fn cache_clear(
+    headers: HeaderMap,
+    gastimator: Arc<Gastimator>,
+    server_config: ServerConfig,
+) -> Result<Json<CacheClearResponse>> {
+    let bearer_token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    server_config.authorize_admin(bearer_token)?;
+    Ok(Json(gastimator.clear_cache().into()))
+}
+
+/// Returns `200` if the gastimator is ready, i.e. warmed up, else `503`,
+/// optionally pinging the remote gas estimator if not yet ready.
+async fn ready(
+    Query(options): Query<ReadinessOptions>,
+    gastimator: Arc<Gastimator>,
+) -> (StatusCode, Json<ServiceStatusResponse>) {
+    if options.ping() && !gastimator.is_ready() {
+        if let Err(e) = gastimator.ping_remote().await {
+            warn!("Readiness ping failed: {e}");
+        }
+    }
+    if gastimator.is_ready() {
+        (StatusCode::OK, Json(ServiceStatus::Ready.into()))
+    } else {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ServiceStatus::NotReady.into()),
+        )
+    }
+}
+
+/// Answers CORS preflight requests from origins allowed by
+/// `allowed_origins`, and adds `Access-Control-Allow-Origin` to responses to
+/// their other requests. Requests from other origins are answered without
+/// CORS headers, so that browsers block them.
+fn cors_layer(allowed_origins: &AllowedOrigins) -> CorsLayer {
+    let allow_origin = match allowed_origins {
+        AllowedOrigins::Any => AllowOrigin::any(),
+        AllowedOrigins::List(origins) => AllowOrigin::list(
+            origins
+                .iter()
+                .filter_map(|origin| HeaderValue::from_str(origin.trim_end_matches('/')).ok()),
+        ),
+    };
+    CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods([Method::GET, Method::POST])
+        .allow_headers(AllowHeaders::mirror_request())
+        .max_age(Duration::from_secs(3600))
+}
+
+/// Responds with `504 Gateway Timeout` if handling the request takes longer
+/// than `timeout`, dropping the handler, since the server, not the client,
+/// was too slow.
+fn timeout_layer(timeout: Duration) -> TimeoutLayer {
+    TimeoutLayer::with_status_code(StatusCode::GATEWAY_TIMEOUT, timeout)
+}
+
+/// Responds with [`Error::RateLimited`] if the client, identified by its IP,
+/// exceeded the rate limit of `limiter`. Requests of unknown clients, i.e.
+/// served without connect info, are not limited.
+async fn rate_limit(
+    State(limiter): State<Arc<RateLimiter>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let client = request
+        .extensions()
+        .get::<axum::extract::ConnectInfo<SocketAddr>>()
+        .map(|info| info.0.ip());
+    match client {
+        Some(client) if !limiter.try_acquire(client, Instant::now()) => {
+            Error::RateLimited.into_response()
+        }
+        _ => next.run(request).await,
+    }
+}
+
+/// Handles `request` with the id of its `X-Request-Id` header, or a
+/// generated one if missing or invalid, see [`RequestId`]. The id is
+/// available to handlers as extension, prefixes the log lines of the
+/// request, is a field of its `request` span and is echoed in the
+/// `X-Request-Id` header of the response.
+async fn request_id(mut request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(X_REQUEST_ID)
+        .and_then(|value| value.to_str().ok())
+        .and_then(RequestId::new)
+        .unwrap_or_else(RequestId::generate);
+    request.extensions_mut().insert(request_id.clone());
+    let span = tracing::info_span!("request", request_id = %request_id);
+    let mut response =
+        with_request_id(request_id.clone(), next.run(request).instrument(span)).await;
+    if let Ok(value) = HeaderValue::from_str(request_id.as_str()) {
+        response.headers_mut().insert(X_REQUEST_ID, value);
+    }
+    response
 }
 
 use std::sync::Once;
 
 static INIT: Once = Once::new();
 
-fn init_logging() {
+/// Initializes logging in `format`, once, later calls have no effect.
+fn init_logging(format: LogFormat) {
     INIT.call_once(|| {
-        pretty_env_logger::init();
+        let span_logger = match format {
+            LogFormat::Text => {
+                let mut builder = pretty_env_logger::formatted_builder();
+                if let Ok(filters) = std::env::var("RUST_LOG") {
+                    builder.parse_filters(&filters);
+                }
+                let logger = builder.build();
+                log::set_max_level(logger.filter());
+                if let Err(e) = log::set_boxed_logger(Box::new(RequestIdPrefixLogger(logger))) {
+                    eprintln!("Failed to set logger: {e}");
+                }
+                SpanLogger::default()
+            }
+            LogFormat::Json => {
+                if let Err(e) = JsonLogger::from_env().init() {
+                    eprintln!("Failed to set JSON logger: {e}");
+                }
+                SpanLogger::structured()
+            }
+        };
+        // Spans of `gastimator` are logged with their duration when closed.
+        if let Err(e) = tracing::subscriber::set_global_default(span_logger) {
+            warn!("Failed to set tracing subscriber: {e}");
+        }
     });
 }
 
-fn build_app(gastimator: Arc<Gastimator>) -> Router {
-    Router::new()
+/// Builds the router, the core routes `/tx` and `/rlp` are always mounted,
+/// optional routes only if enabled in `server_config`, all answering CORS
+/// requests from the allowed origins of `server_config` and timing out after
+/// its request timeout. Requests exceeding the rate limit of `server_config`,
+/// if any, are rejected before anything else.
+fn build_app(gastimator: Arc<Gastimator>, server_config: &ServerConfig) -> Router {
+    let router = Router::new()
         .route("/tx", {
             let gastimator = gastimator.clone();
-            post(move |body| estimate_gas(body, gastimator))
+            post(move |request_id, headers, query, body| {
+                estimate_gas(request_id, headers, query, body, gastimator)
+            })
         })
         .route("/rlp", {
             let gastimator = gastimator.clone();
-            post(move |body| estimate_gas_rlp(body, gastimator))
+            let max_rlp_bytes = server_config.max_rlp_bytes();
+            post(move |request_id, headers, query, body| {
+                estimate_gas_rlp(request_id, headers, query, body, gastimator, max_rlp_bytes)
+            })
+        });
+    let app = OptionalRoute::ALL
+        .into_iter()
+        .filter(|route| server_config.is_route_enabled(*route))
+        .fold(router, |router, route| {
+            let gastimator = gastimator.clone();
+            let method_router = match route {
+                OptionalRoute::Batch => post(move |headers, query, body| {
+                    estimate_gas_batch(headers, query, body, gastimator)
+                }),
+                OptionalRoute::Health => get(health),
+                OptionalRoute::Ready => get(move |query| ready(query, gastimator)),
+                OptionalRoute::CacheStats => get(move || cache_stats(gastimator)),
+                OptionalRoute::Simulate => {
+                    post(move |query, body| simulate(query, body, gastimator))
+                }
+                OptionalRoute::Metrics => get(move || metrics(gastimator)),
+                OptionalRoute::Stats => get(move || estimator_stats(gastimator)),
+                OptionalRoute::Classify => {
+                    let max_rlp_bytes = server_config.max_rlp_bytes();
+                    post(move |body| classify(body, max_rlp_bytes))
+                }
+                OptionalRoute::RlpVerify => {
+                    let max_rlp_bytes = server_config.max_rlp_bytes();
+                    post(move |body| verify_rlp(body, max_rlp_bytes))
+                }
+                OptionalRoute::Call => post(move |body| call(body, gastimator)),
+                OptionalRoute::Bundle => post(move |body| simulate_bundle(body, gastimator)),
+                OptionalRoute::ByHash => post(move |request_id, headers, query, body| {
+                    estimate_gas_by_hash(request_id, headers, query, body, gastimator)
+                }),
+                OptionalRoute::Deploy => post(move |request_id, headers, query, body| {
+                    estimate_gas_deploy(request_id, headers, query, body, gastimator)
+                }),
+                OptionalRoute::Accuracy => {
+                    post(move |query, body| accuracy(query, body, gastimator))
+                }
+                OptionalRoute::RlpRaw => {
+                    let max_rlp_bytes = server_config.max_rlp_bytes();
+                    post(move |request_id, headers, query, body| {
+                        estimate_gas_rlp_raw(
+                            request_id,
+                            headers,
+                            query,
+                            body,
+                            gastimator,
+                            max_rlp_bytes,
+                        )
+                    })
+                }
+                OptionalRoute::Stream => {
+                    let max_line_bytes = server_config.max_body_bytes();
+                    post(move |query, body| {
+                        estimate_gas_stream(query, body, gastimator, max_line_bytes)
+                    })
+                }
+                OptionalRoute::WebSocket => {
+                    get(move |query, upgrade| web_socket(query, upgrade, gastimator))
+                }
+                OptionalRoute::Replace => post(move |query, body| replace(query, body, gastimator)),
+                OptionalRoute::CacheClear => {
+                    let server_config = server_config.clone();
+                    post(move |headers| cache_clear(headers, gastimator, server_config))
+                }
+            };
+            router.route(route.path(), method_router)
         })
+        // The single limit of bodies, also of those not read by an extractor
+        .layer(axum::extract::DefaultBodyLimit::disable())
+        .layer(RequestBodyLimitLayer::new(server_config.max_body_bytes()))
+        // Bodies are limited after decompression, so a small compressed body
+        // cannot inflate beyond `max_body_bytes`.
+        .layer(RequestDecompressionLayer::new())
+        // Streamed NDJSON is not compressed, it would be buffered by the
+        // encoder instead of answered line by line.
+        .layer(CompressionLayer::new().compress_when(
+            DefaultPredicate::new().and(NotForContentType::const_new("application/x-ndjson")),
+        ))
+        .layer(timeout_layer(server_config.request_timeout()))
+        .layer(cors_layer(server_config.allowed_origins()));
+    let app = match server_config.rate_limit() {
+        Some(limit) => app.layer(middleware::from_fn_with_state(
+            Arc::new(RateLimiter::new(limit)),
+            rate_limit,
+        )),
+        None => app,
+    };
+    app.layer(middleware::from_fn(request_id))
 }
 
 async fn bind_and_signal(
@@ -62,19 +568,91 @@ async fn bind_and_signal(
 // Public
 // ========================================
 
-/// Starts the server and signals readiness when the endpoints are live.
+/// Completes when the process receives Ctrl-C (`SIGINT`) or, on unix,
+/// `SIGTERM`, e.g. sent by an orchestrator on deploy.
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            warn!("Failed to listen for Ctrl-C: {e}");
+            std::future::pending::<()>().await
+        }
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                warn!("Failed to listen for SIGTERM: {e}");
+                std::future::pending::<()>().await
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
+/// Starts the server and signals readiness when the endpoints are live,
+/// shutting down gracefully on [`shutdown_signal`].
 pub async fn run_signaling_readiness(
     config: &Config,
     ready_tx: oneshot::Sender<SocketAddr>,
 ) -> Result<()> {
-    init_logging();
+    run_signaling_readiness_until(config, ready_tx, shutdown_signal()).await
+}
+
+/// Starts the server and signals readiness when the endpoints are live,
+/// when `shutdown` completes, stops accepting connections and returns once
+/// in-flight requests have been responded to.
+pub async fn run_signaling_readiness_until(
+    config: &Config,
+    ready_tx: oneshot::Sender<SocketAddr>,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Result<()> {
+    init_logging(config.server().log_format());
     debug!("Starting gastimate server... args: {:?}", config.server());
-    let gastimator = Arc::new(Gastimator::new(config.alchemy_api_key().clone()));
-    let app = build_app(gastimator);
+    config.validate()?;
+    let gastimator = Arc::new(Gastimator::from_config(config)?);
+    #[cfg(feature = "otel")]
+    let meter_provider = match config.server().otlp_endpoint() {
+        Some(endpoint) => Some(
+            OtlpMetricsExporter::new(endpoint, DEFAULT_OTLP_EXPORT_INTERVAL)?
+                .start(gastimator.clone())?,
+        ),
+        None => None,
+    };
+    // Warm up in the background, `/ready` returns `503` until completed.
+    tokio::spawn({
+        let gastimator = gastimator.clone();
+        async move { gastimator.warm_up().await }
+    });
+    let app = build_app(gastimator, config.server());
     let (listener, address) =
         bind_and_signal(config.server().address_with_port(), ready_tx).await?;
     info!("Listening on: {}", address);
-    axum::serve(listener, app).await.map_err(Error::start)
+    let served = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(async move {
+        shutdown.await;
+        info!("Shutting down, draining in-flight requests...");
+    })
+    .await
+    .map_err(Error::start);
+    #[cfg(feature = "otel")]
+    if let Some(meter_provider) = meter_provider {
+        // Exports the metrics since the last export, blocking on the request.
+        if let Ok(Err(e)) = tokio::task::spawn_blocking(move || meter_provider.shutdown()).await {
+            warn!("Failed to export metrics on shutdown: {e}");
+        }
+    }
+    served
 }
 
 pub async fn run(config: &Config) {
@@ -83,12 +661,903 @@ pub async fn run(config: &Config) {
     let server_handle =
         tokio::spawn(async move { run_signaling_readiness(&config, ready_tx).await });
     // Wait for the server to signal readiness and get the bound address
-    ready_rx
+    if ready_rx.await.is_err() {
+        // The server failed before binding, e.g. due to an invalid config,
+        // surface why instead of a generic readiness failure.
+        let result = server_handle.await.expect("Server task should not panic");
+        result
+            .and(Err::<(), _>(Error::FailedToSignalReadiness))
+            .unwrap_display();
+        return;
+    }
+    server_handle
         .await
-        .map_err(|_| Error::FailedToSignalReadiness)
+        .expect("Server task should not panic")
         .unwrap_display();
-    let _ = server_handle
-        .into_future()
+    info!("Server shut down");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serves the app built with `enabled_routes`, returning its URL.
+    async fn serve(enabled_routes: impl IntoIterator<Item = OptionalRoute>) -> String {
+        serve_with_admin_token(enabled_routes, None).await
+    }
+
+    /// Serves the app built with `enabled_routes` and `admin_token`,
+    /// returning its URL.
+    async fn serve_with_admin_token(
+        enabled_routes: impl IntoIterator<Item = OptionalRoute>,
+        admin_token: Option<&str>,
+    ) -> String {
+        serve_with_config(
+            ServerConfigBuilder::default()
+                .enabled_routes(enabled_routes.into_iter().collect::<HashSet<_>>())
+                .admin_token(admin_token.map(str::to_owned))
+                .clone(),
+        )
         .await
-        .expect("Should never finish");
+    }
+
+    /// Serves the app built with `server_config` on a free local port,
+    /// returning its URL.
+    async fn serve_with_config(mut server_config: ServerConfigBuilder) -> String {
+        let server_config = server_config
+            .address("127.0.0.1")
+            .port(0u16)
+            .build()
+            .unwrap();
+        let config = ConfigBuilder::default()
+            .server(server_config.clone())
+            .gastimator(
+                GastimatorConfigBuilder::default()
+                    .mode(GastimatorMode::LocalOnly)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        let gastimator = Arc::new(Gastimator::from_config(&config).unwrap());
+        let app = build_app(gastimator, &server_config);
+        let listener = tokio::net::TcpListener::bind(server_config.address_with_port())
+            .await
+            .unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .await
+            .unwrap()
+        });
+        url
+    }
+
+    async fn status_of_get(url: &str) -> u16 {
+        Client::new()
+            .get(url)
+            .send()
+            .await
+            .unwrap()
+            .status()
+            .as_u16()
+    }
+
+    #[tokio::test]
+    async fn disabled_route_is_not_found() {
+        let url = serve([OptionalRoute::Health]).await;
+
+        assert_eq!(status_of_get(&format!("{url}/health")).await, 200);
+        assert_eq!(status_of_get(&format!("{url}/ready")).await, 404);
+        let batch = Client::new()
+            .post(format!("{url}/tx/batch"))
+            .json(&Vec::<Transaction>::new())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(batch.status().as_u16(), 404);
+    }
+
+    #[tokio::test]
+    async fn core_routes_always_mounted() {
+        let url = serve([]).await;
+
+        let response = Client::new()
+            .post(format!("{url}/tx"))
+            .json(&Transaction::sample_native_token_transfer())
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status().as_u16(), 200);
+    }
+
+    #[tokio::test]
+    async fn gas_exceeds_limit_is_unprocessable_with_typed_payload() {
+        let url = serve([]).await;
+
+        let response = Client::new()
+            .post(format!("{url}/tx"))
+            .json(&Transaction::sample_native_token_transfer_gas_limit(
+                Gas::from(1),
+            ))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status().as_u16(), 422);
+        let error: ErrorResponse = response.json().await.unwrap();
+        assert_eq!(error.error(), "GasExceedsLimit");
+        assert_eq!(error.details()["estimated_cost"], 21_000);
+        assert_eq!(error.details()["gas_limit"], 1);
+    }
+
+    #[tokio::test]
+    async fn metrics_in_prometheus_text_format() {
+        let url = serve(OptionalRoute::ALL).await;
+
+        let response = Client::new()
+            .get(format!("{url}/metrics"))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status().as_u16(), 200);
+        let body = response.text().await.unwrap();
+        assert!(body.contains("# TYPE gastimator_estimates_total counter"));
+        assert!(body.contains("gastimator_cache_hits_total 0"));
+    }
+
+    #[tokio::test]
+    async fn estimator_stats_as_json() {
+        let url = serve([OptionalRoute::Stats]).await;
+
+        let stats: EstimatorStats = Client::new()
+            .get(format!("{url}/stats"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        assert_eq!(stats.remote(), OutcomeCounts::default());
+        assert_eq!(stats.estimate_sources().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn cache_stats() {
+        let url = serve(OptionalRoute::ALL).await;
+
+        let stats: CacheStats = Client::new()
+            .get(format!("{url}/cache/stats"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        assert_eq!(stats.hits(), 0);
+        assert_eq!(stats.size(), 0);
+    }
+
+    #[tokio::test]
+    async fn cache_clear_requires_admin_token() {
+        let url = serve_with_admin_token(OptionalRoute::ALL, Some("secret")).await;
+        let clear = |token: Option<&'static str>| {
+            let request = Client::new().post(format!("{url}/cache/clear"));
+            let request = match token {
+                Some(token) => request.bearer_auth(token),
+                None => request,
+            };
+            async move { request.send().await.unwrap() }
+        };
+
+        assert_eq!(clear(None).await.status().as_u16(), 401);
+        assert_eq!(clear(Some("wrong")).await.status().as_u16(), 401);
+        let response = clear(Some("secret")).await;
+        assert_eq!(response.status().as_u16(), 200);
+        let response: CacheClearResponse = response.json().await.unwrap();
+        assert_eq!(response.removed(), 0);
+    }
+
+    #[tokio::test]
+    async fn cache_clear_not_mounted_without_admin_token() {
+        let url = serve(OptionalRoute::ALL).await;
+        let response = Client::new()
+            .post(format!("{url}/cache/clear"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 404);
+    }
+
+    #[tokio::test]
+    async fn cors_preflight_from_allowed_origin() {
+        let url = serve_with_config(
+            ServerConfigBuilder::default()
+                .allowed_origins(AllowedOrigins::new(["https://app.example.com"]))
+                .clone(),
+        )
+        .await;
+        let preflight = |origin: &'static str| {
+            Client::new()
+                .request("OPTIONS".parse().unwrap(), format!("{url}/tx"))
+                .header("origin", origin)
+                .header("access-control-request-method", "POST")
+                .header("access-control-request-headers", "content-type")
+                .send()
+        };
+
+        let allowed = preflight("https://app.example.com").await.unwrap();
+        assert_eq!(allowed.status().as_u16(), 200);
+        assert_eq!(
+            allowed.headers()["access-control-allow-origin"],
+            "https://app.example.com"
+        );
+        assert_eq!(
+            allowed.headers()["access-control-allow-headers"],
+            "content-type"
+        );
+        assert_eq!(
+            allowed.headers()["access-control-allow-methods"],
+            "GET,POST"
+        );
+
+        let disallowed = preflight("https://evil.example.com").await.unwrap();
+        assert!(
+            !disallowed
+                .headers()
+                .contains_key("access-control-allow-origin")
+        );
+    }
+
+    #[tokio::test]
+    async fn cors_any_origin() {
+        let url = serve_with_config(
+            ServerConfigBuilder::default()
+                .allowed_origins(AllowedOrigins::Any)
+                .clone(),
+        )
+        .await;
+
+        let response = Client::new()
+            .post(format!("{url}/tx"))
+            .header("origin", "https://app.example.com")
+            .json(&Transaction::sample_native_token_transfer())
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status().as_u16(), 200);
+        assert_eq!(response.headers()["access-control-allow-origin"], "*");
+    }
+
+    #[tokio::test]
+    async fn slow_request_times_out() {
+        let app = Router::new()
+            .route(
+                "/slow",
+                get(|| async { tokio::time::sleep(Duration::from_secs(10)).await }),
+            )
+            .layer(timeout_layer(Duration::from_millis(10)));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let response = Client::new()
+            .get(format!("{url}/slow"))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status().as_u16(), 504);
+    }
+
+    #[tokio::test]
+    async fn shuts_down_gracefully() {
+        let config = ConfigBuilder::default()
+            .server(
+                ServerConfigBuilder::default()
+                    .address("127.0.0.1")
+                    .port(0u16)
+                    .build()
+                    .unwrap(),
+            )
+            .gastimator(
+                GastimatorConfigBuilder::default()
+                    .mode(GastimatorMode::LocalOnly)
+                    .build()
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        let (ready_tx, ready_rx) = oneshot::channel();
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let server = tokio::spawn(async move {
+            run_signaling_readiness_until(&config, ready_tx, async {
+                let _ = shutdown_rx.await;
+            })
+            .await
+        });
+        let address = ready_rx.await.unwrap();
+        assert_eq!(
+            status_of_get(&format!("http://{address}/health")).await,
+            200
+        );
+
+        shutdown_tx.send(()).unwrap();
+
+        assert!(server.await.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn classify_without_estimating() {
+        let url = serve(OptionalRoute::ALL).await;
+
+        let classification: ClassificationResponse = Client::new()
+            .post(format!("{url}/classify"))
+            .json(&Transaction::sample_native_token_transfer_cachable())
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        assert_eq!(classification.kind(), &TransactionKind::NativeTokenTransfer);
+        assert!(classification.cacheable());
+        assert_eq!(classification.intrinsic_gas(), Gas::from(21_000));
+    }
+
+    #[tokio::test]
+    async fn rlp_with_decoded_transaction() {
+        let url = serve([]).await;
+        let tx = Transaction::sample_native_token_transfer_cachable();
+        let raw = RawTransaction { rlp: tx.to_rlp() };
+        let estimate = async |query: &str| -> GasEstimateResponse {
+            Client::new()
+                .post(format!("{url}/rlp{query}"))
+                .json(&raw)
+                .send()
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap()
+        };
+
+        let decoded = estimate("?decoded=true").await;
+        let decoded_tx = decoded.decoded_transaction().as_ref().unwrap();
+        assert_eq!(decoded_tx.to(), tx.to());
+        assert_eq!(decoded_tx.nonce(), tx.nonce());
+        assert_eq!(decoded_tx.value(), tx.value());
+        assert!(estimate("").await.decoded_transaction().is_none());
+    }
+
+    #[tokio::test]
+    async fn rlp_raw_accepts_plain_hex() {
+        let url = serve([OptionalRoute::RlpRaw]).await;
+        let hex = RawTransaction {
+            rlp: Transaction::sample_contract_creation_cachable().to_rlp(),
+        }
+        .to_string();
+        let estimate = async |body: String| {
+            Client::new()
+                .post(format!("{url}/rlp/raw"))
+                .header("content-type", "text/plain")
+                .body(body)
+                .send()
+                .await
+                .unwrap()
+        };
+
+        for body in [hex.clone(), format!("0x{hex}\n")] {
+            let response = estimate(body).await;
+            assert_eq!(response.status().as_u16(), 200);
+            let response: GasEstimateResponse = response.json().await.unwrap();
+            assert_eq!(response.kind(), &TransactionKind::ContractCreation);
+        }
+        assert_eq!(estimate("not hex".to_owned()).await.status().as_u16(), 400);
+    }
+
+    #[tokio::test]
+    async fn rlp_verify_recovers_signer() {
+        let url = serve([OptionalRoute::RlpVerify]).await;
+        let verify = async |rlp: &str| {
+            Client::new()
+                .post(format!("{url}/rlp/verify"))
+                .json(&serde_json::json!({ "rlp": rlp }))
+                .send()
+                .await
+                .unwrap()
+        };
+
+        // https://etherscan.io/tx/0xb1869db00d08d706059ae6a167b9d89b01884606ee4dec42c19c9c6466471542
+        let response = verify("0x02f87201824f4c83142ebf842d441366825208942e575fe17124f7ef2d22bbfb33cf3dbfc3f002d68711c37937e0800080c001a0152c51f0aa71d7698b486a34f8ffc9b61cc7a000c34d48e1cf9361d8973ba518a024216a87cb193b7e502ad9ddbcfc9674c40fe98bd4a7bda575ba03185621cd13").await;
+        assert_eq!(response.status().as_u16(), 200);
+        let response: RlpVerifyResponse = response.json().await.unwrap();
+        assert_eq!(
+            response.signer(),
+            Some(alloy::primitives::address!(
+                "0xd5b1d4e6626748968daee303133d2be5e2353f44"
+            ))
+        );
+        assert_eq!(response.transaction().nonce(), &Some(20300));
+
+        assert_eq!(verify("0xdeadbeef").await.status().as_u16(), 400);
+    }
+
+    #[tokio::test]
+    async fn deploy_estimates_contract_creation() {
+        let url = serve([OptionalRoute::Deploy]).await;
+        let deploy = async |request: DeployRequest| {
+            Client::new()
+                .post(format!("{url}/deploy"))
+                .json(&request)
+                .send()
+                .await
+                .unwrap()
+        };
+
+        // PUSH1 0 PUSH1 0 RETURN, deploying empty code
+        let bytecode = [0x60, 0x00, 0x60, 0x00, 0xf3];
+        let response = deploy(DeployRequest::new(bytecode, [0x2a; 32], U256::ZERO)).await;
+        assert_eq!(response.status().as_u16(), 200);
+        let response: GasEstimateResponse = response.json().await.unwrap();
+        assert_eq!(response.kind(), &TransactionKind::ContractCreation);
+
+        let empty = DeployRequest::new(Bytes::new(), Bytes::new(), U256::ZERO);
+        assert_eq!(deploy(empty).await.status().as_u16(), 400);
+    }
+
+    #[tokio::test]
+    async fn by_hash_without_remote_is_not_implemented() {
+        let url = serve([OptionalRoute::ByHash]).await;
+
+        let response = Client::new()
+            .post(format!("{url}/tx/by-hash"))
+            .json(&TransactionHashInput::from(B256::repeat_byte(0xab)))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status().as_u16(), 501);
+    }
+
+    #[tokio::test]
+    async fn call_returns_output() {
+        let url = serve(OptionalRoute::ALL).await;
+        let contract = Address::new([0xcc; 20]);
+        let tx = TransactionBuilder::default()
+            .to(contract)
+            .state_override(StateOverride::new([(
+                contract,
+                AccountOverrideBuilder::default()
+                    // PUSH1 1 PUSH1 0 MSTORE8 PUSH1 1 PUSH1 0 RETURN
+                    .code(Bytes::from([
+                        0x60, 0x01, 0x60, 0x00, 0x53, 0x60, 0x01, 0x60, 0x00, 0xf3,
+                    ]))
+                    .build()
+                    .unwrap(),
+            )]))
+            .build()
+            .unwrap();
+
+        let response: serde_json::Value = Client::new()
+            .post(format!("{url}/call"))
+            .json(&tx)
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        assert_eq!(response["output"], "0x01");
+        assert!(response["gas_used"].as_u64().unwrap() > 21_000);
+    }
+
+    #[tokio::test]
+    async fn bundle_returns_gas_of_each_tx() {
+        let url = serve([OptionalRoute::Bundle]).await;
+        let bundle = async |txs: Vec<Transaction>| {
+            Client::new()
+                .post(format!("{url}/bundle"))
+                .json(&txs)
+                .send()
+                .await
+                .unwrap()
+        };
+        let tx = Transaction::sample_native_token_transfer();
+
+        let response = bundle(vec![tx.clone(), tx]).await;
+        assert_eq!(response.status().as_u16(), 200);
+        let response: BundleSimulationResponse = response.json().await.unwrap();
+        assert_eq!(response.gas_used().len(), 2);
+        assert_eq!(response.total_gas(), Gas::from(42_000));
+
+        assert_eq!(bundle(Vec::new()).await.status().as_u16(), 400);
+    }
+
+    #[tokio::test]
+    async fn stream_estimates_ndjson() {
+        let url = serve(OptionalRoute::ALL).await;
+        let tx =
+            serde_json::to_string(&Transaction::sample_native_token_transfer_cachable()).unwrap();
+        let body = format!("{tx}\nnot json\n\n{tx}\n");
+
+        let response = Client::new()
+            .post(format!("{url}/tx/stream"))
+            .body(body)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE.as_str()],
+            "application/x-ndjson"
+        );
+        let items = response
+            .text()
+            .await
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect::<Vec<serde_json::Value>>();
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0]["kind"], "native_token_transfer");
+        assert!(items[1]["error"].as_str().unwrap().contains("line 2"));
+        assert_eq!(items[2]["kind"], "native_token_transfer");
+    }
+
+    #[tokio::test]
+    async fn web_socket_estimates_until_closed() {
+        use futures_util::SinkExt;
+        use tokio_tungstenite::tungstenite::Message;
+
+        let url = serve(OptionalRoute::ALL).await;
+        let (mut socket, _) = tokio_tungstenite::connect_async(url.replace("http", "ws") + "/ws")
+            .await
+            .unwrap();
+        let tx =
+            serde_json::to_string(&Transaction::sample_native_token_transfer_cachable()).unwrap();
+
+        socket.send(Message::text(tx)).await.unwrap();
+        socket.send(Message::text("not json")).await.unwrap();
+        let mut answer = async || -> serde_json::Value {
+            let message = socket.next().await.unwrap().unwrap();
+            serde_json::from_str(message.to_text().unwrap()).unwrap()
+        };
+
+        assert_eq!(answer().await["kind"], "native_token_transfer");
+        assert!(
+            answer().await["error"]
+                .as_str()
+                .unwrap()
+                .contains("Invalid WebSocket message")
+        );
+        socket.close(None).await.unwrap();
+        assert!(matches!(
+            socket.next().await,
+            Some(Ok(Message::Close(_))) | None
+        ));
+    }
+
+    #[tokio::test]
+    async fn oversized_body_and_rlp_are_rejected() {
+        let url = serve_with_config(
+            ServerConfigBuilder::default()
+                .max_body_bytes(1024usize)
+                .max_rlp_bytes(64usize)
+                .clone(),
+        )
+        .await;
+        let status_of_rlp = async |len: usize| {
+            Client::new()
+                .post(format!("{url}/rlp"))
+                .json(&RawTransaction {
+                    rlp: Bytes::from(vec![0xab; len]),
+                })
+                .send()
+                .await
+                .unwrap()
+                .status()
+                .as_u16()
+        };
+
+        assert_eq!(status_of_rlp(1024).await, 413);
+        let response = Client::new()
+            .post(format!("{url}/rlp"))
+            .json(&RawTransaction {
+                rlp: Bytes::from(vec![0xab; 65]),
+            })
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 400);
+        assert!(response.text().await.unwrap().contains("exceeds the max"));
+    }
+
+    #[tokio::test]
+    async fn oversized_body_is_rejected_without_extractor() {
+        let url = serve_with_config(
+            ServerConfigBuilder::default()
+                .max_body_bytes(1024usize)
+                .clone(),
+        )
+        .await;
+        let status_of_post = async |path: &str| {
+            Client::new()
+                .post(format!("{url}{path}"))
+                .header(header::CONTENT_TYPE.as_str(), "text/plain")
+                .body("a".repeat(2048))
+                .send()
+                .await
+                .unwrap()
+                .status()
+                .as_u16()
+        };
+
+        assert_eq!(status_of_post("/rlp/raw").await, 413);
+        assert_eq!(status_of_post("/tx/stream").await, 413);
+    }
+
+    fn gzip(bytes: &[u8]) -> Vec<u8> {
+        use std::io::Write;
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), Default::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn gunzip(bytes: &[u8]) -> Vec<u8> {
+        use std::io::Read;
+        let mut decoded = Vec::new();
+        flate2::read::GzDecoder::new(bytes)
+            .read_to_end(&mut decoded)
+            .unwrap();
+        decoded
+    }
+
+    #[tokio::test]
+    async fn gzip_request_and_response() {
+        let url = serve([]).await;
+        let tx = serde_json::to_vec(&Transaction::sample_native_token_transfer()).unwrap();
+
+        let response = Client::new()
+            .post(format!("{url}/tx"))
+            .header(header::CONTENT_TYPE.as_str(), "application/json")
+            .header(header::CONTENT_ENCODING.as_str(), "gzip")
+            .header(header::ACCEPT_ENCODING.as_str(), "gzip")
+            .body(gzip(&tx))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status().as_u16(), 200);
+        assert_eq!(
+            response.headers()[header::CONTENT_ENCODING.as_str()],
+            "gzip"
+        );
+        let body = gunzip(&response.bytes().await.unwrap());
+        let response: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(response["kind"], "native_token_transfer");
+    }
+
+    #[tokio::test]
+    async fn uncompressed_response_unless_accepted() {
+        let url = serve([]).await;
+
+        let response = Client::new()
+            .post(format!("{url}/tx"))
+            .json(&Transaction::sample_native_token_transfer())
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status().as_u16(), 200);
+        assert!(
+            response
+                .headers()
+                .get(header::CONTENT_ENCODING.as_str())
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn decompressed_body_is_limited() {
+        let url = serve_with_config(
+            ServerConfigBuilder::default()
+                .max_body_bytes(1024usize)
+                .clone(),
+        )
+        .await;
+        let body = serde_json::to_vec(&RawTransaction {
+            rlp: Bytes::from(vec![0; 4096]),
+        })
+        .unwrap();
+        let compressed = gzip(&body);
+        assert!(compressed.len() < 1024);
+
+        let response = Client::new()
+            .post(format!("{url}/rlp"))
+            .header(header::CONTENT_TYPE.as_str(), "application/json")
+            .header(header::CONTENT_ENCODING.as_str(), "gzip")
+            .body(compressed)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status().as_u16(), 413);
+    }
+
+    #[tokio::test]
+    async fn unsupported_content_encoding_is_rejected() {
+        let url = serve([]).await;
+
+        let response = Client::new()
+            .post(format!("{url}/tx"))
+            .header(header::CONTENT_TYPE.as_str(), "application/json")
+            .header(header::CONTENT_ENCODING.as_str(), "br")
+            .body("{}")
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status().as_u16(), 415);
+    }
+
+    #[tokio::test]
+    async fn stream_is_not_compressed() {
+        let url = serve(OptionalRoute::ALL).await;
+        let tx =
+            serde_json::to_string(&Transaction::sample_native_token_transfer_cachable()).unwrap();
+
+        let response = Client::new()
+            .post(format!("{url}/tx/stream"))
+            .header(header::ACCEPT_ENCODING.as_str(), "gzip")
+            .body(format!("{tx}\n"))
+            .send()
+            .await
+            .unwrap();
+
+        assert!(
+            response
+                .headers()
+                .get(header::CONTENT_ENCODING.as_str())
+                .is_none()
+        );
+        assert!(
+            response
+                .text()
+                .await
+                .unwrap()
+                .contains("native_token_transfer")
+        );
+    }
+
+    #[tokio::test]
+    async fn idempotency_key_replays_stored_response() {
+        let url = serve([]).await;
+        let estimate = |key: Option<&str>, tx: Transaction| {
+            let url = url.clone();
+            let key = key.map(str::to_owned);
+            async move {
+                let request = Client::new().post(format!("{url}/tx")).json(&tx);
+                let request = match key {
+                    Some(key) => request.header("idempotency-key", key),
+                    None => request,
+                };
+                let response = request.send().await.unwrap();
+                let status = response.status().as_u16();
+                let gas_usage = response
+                    .json::<GasEstimateResponse>()
+                    .await
+                    .map(|response| response.gas_usage().clone());
+                (status, gas_usage.ok())
+            }
+        };
+        let transfer = Transaction::sample_native_token_transfer();
+        let creation = Transaction::sample_contract_creation();
+
+        let first = estimate(Some("retry-1"), transfer.clone()).await;
+        let replayed = estimate(Some("retry-1"), transfer).await;
+        let reused = estimate(Some("retry-1"), creation.clone()).await;
+        let other_key = estimate(Some("retry-2"), creation.clone()).await;
+        let without_key = estimate(None, creation).await;
+
+        assert_eq!(first.0, 200);
+        assert_eq!(replayed, first);
+        assert_eq!(reused, (422, None));
+        assert_eq!(other_key.0, 200);
+        assert_ne!(other_key, first);
+        assert_eq!(without_key, other_key);
+    }
+
+    #[tokio::test]
+    async fn idempotency_key_replays_stored_batch() {
+        let url = serve([OptionalRoute::Batch, OptionalRoute::Stats]).await;
+        let batch = |txs: Vec<Transaction>| {
+            let url = url.clone();
+            async move {
+                let response = Client::new()
+                    .post(format!("{url}/tx/batch"))
+                    .header("idempotency-key", "retry-1")
+                    .json(&txs)
+                    .send()
+                    .await
+                    .unwrap();
+                let status = response.status().as_u16();
+                let items = response.json::<Vec<BatchGasEstimateItem>>().await.ok();
+                (status, items)
+            }
+        };
+        let creation = Transaction::sample_contract_creation();
+
+        let first = batch(vec![creation.clone()]).await;
+        let replayed = batch(vec![creation.clone()]).await;
+        let reused = batch(vec![creation.clone(), creation]).await;
+
+        assert_eq!(first.0, 200);
+        assert_eq!(replayed, first);
+        assert_eq!(reused, (422, None));
+        let stats: EstimatorStats = Client::new()
+            .get(format!("{url}/stats"))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(stats.local().ok() + stats.local().error(), 1);
+    }
+
+    #[tokio::test]
+    async fn request_id_is_echoed() {
+        let url = serve([]).await;
+        let client = Client::new();
+
+        let response = client
+            .post(format!("{url}/tx"))
+            .header("x-request-id", "req-42")
+            .json(&Transaction::sample_native_token_transfer())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.headers()["x-request-id"], "req-42");
+        let body = response.json::<GasEstimateResponse>().await.unwrap();
+        assert_eq!(body.request_id().as_ref().unwrap().as_str(), "req-42");
+
+        let response = client
+            .post(format!("{url}/tx"))
+            .json(&Transaction::sample_native_token_transfer())
+            .send()
+            .await
+            .unwrap();
+        let generated = response.headers()["x-request-id"]
+            .to_str()
+            .unwrap()
+            .to_owned();
+        assert_eq!(generated.len(), 36);
+        let body = response.json::<GasEstimateResponse>().await.unwrap();
+        assert_eq!(body.request_id().as_ref().unwrap().as_str(), generated);
+    }
+
+    #[tokio::test]
+    async fn requests_exceeding_rate_limit_are_rejected() {
+        let url = serve_with_config(
+            ServerConfigBuilder::default()
+                .rate_limit(RateLimit::new(0.01, 2))
+                .clone(),
+        )
+        .await;
+
+        assert_eq!(status_of_get(&format!("{url}/health")).await, 200);
+        assert_eq!(status_of_get(&format!("{url}/health")).await, 200);
+        assert_eq!(status_of_get(&format!("{url}/health")).await, 429);
+    }
 }
@@ -0,0 +1,397 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt::{Debug, Write},
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Instant,
+};
+
+use tracing::{
+    Event, Metadata, Subscriber,
+    field::{Field, Visit},
+    level_filters::LevelFilter,
+    span::{Attributes, Id, Record},
+};
+use tracing_core::span::Current;
+
+thread_local! {
+    /// The spans entered on this thread, innermost last.
+    static ENTERED: RefCell<Vec<Id>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A span which has not yet closed.
+struct OpenSpan {
+    metadata: &'static Metadata<'static>,
+    /// Names of the span and its ancestors, e.g. `estimate > compute_estimates`.
+    path: String,
+    fields: String,
+    /// The fields, typed, logged as key-values if structured.
+    values: Vec<(&'static str, FieldValue)>,
+    start: Instant,
+    references: usize,
+}
+
+/// A minimal [`Subscriber`] which logs, using `log`, the duration and fields
+/// of every span of `gastimator` when it closes, e.g.
+/// `estimate > compute_estimates > local_simulation took 1.2 ms`, and
+/// forwards events to `log`, so that latency can be attributed per phase.
+/// Spans are only tracked if `debug` is enabled, see [`log::max_level`].
+///
+/// If structured, see [`Self::structured`], fields, the path of the span and
+/// its `elapsed_ms` are logged as key-values instead of in the message, e.g.
+/// for the [`JsonLogger`].
+#[derive(Default)]
+pub(crate) struct SpanLogger {
+    next_id: AtomicU64,
+    spans: Mutex<HashMap<Id, OpenSpan>>,
+    structured: bool,
+}
+
+/// The value of a field, typed so that numbers and booleans stay such in
+/// structured logs.
+#[derive(Debug, Clone, PartialEq)]
+enum FieldValue {
+    Bool(bool),
+    U64(u64),
+    I64(i64),
+    F64(f64),
+    Str(String),
+}
+
+impl FieldValue {
+    fn to_value(&self) -> log::kv::Value<'_> {
+        match self {
+            Self::Bool(value) => (*value).into(),
+            Self::U64(value) => (*value).into(),
+            Self::I64(value) => (*value).into(),
+            Self::F64(value) => (*value).into(),
+            Self::Str(value) => value.as_str().into(),
+        }
+    }
+}
+
+/// Formats fields as ` key=value`, and the `message` field, if any, as is,
+/// also keeping the typed values of the fields.
+#[derive(Default)]
+struct FieldFormatter {
+    message: String,
+    fields: String,
+    values: Vec<(&'static str, FieldValue)>,
+}
+
+impl FieldFormatter {
+    fn record_value(&mut self, field: &Field, value: FieldValue, debug: &dyn Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{debug:?}");
+        } else {
+            let _ = write!(self.fields, " {}={debug:?}", field.name());
+            self.values.push((field.name(), value));
+        }
+    }
+}
+
+impl Visit for FieldFormatter {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.record_value(field, FieldValue::Str(format!("{value:?}")), value);
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record_value(
+            field,
+            FieldValue::Str(value.to_owned()),
+            &format_args!("{value}"),
+        );
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.record_value(field, FieldValue::Bool(value), &value);
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.record_value(field, FieldValue::U64(value), &value);
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.record_value(field, FieldValue::I64(value), &value);
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.record_value(field, FieldValue::F64(value), &value);
+    }
+}
+
+/// The key-values of a structured log line, `span` with `path`, if any,
+/// `extra` and `values`.
+fn key_values<'a>(
+    path: Option<&'a str>,
+    extra: &[(&'static str, log::kv::Value<'a>)],
+    values: &'a [(&'static str, FieldValue)],
+) -> Vec<(&'static str, log::kv::Value<'a>)> {
+    path.map(|path| ("span", path.into()))
+        .into_iter()
+        .chain(extra.iter().cloned())
+        .chain(values.iter().map(|(key, value)| (*key, value.to_value())))
+        .collect()
+}
+
+fn log_level(level: &tracing::Level) -> log::Level {
+    match *level {
+        tracing::Level::ERROR => log::Level::Error,
+        tracing::Level::WARN => log::Level::Warn,
+        tracing::Level::INFO => log::Level::Info,
+        tracing::Level::DEBUG => log::Level::Debug,
+        tracing::Level::TRACE => log::Level::Trace,
+    }
+}
+
+impl SpanLogger {
+    /// Spans are logged at `debug`, when they close.
+    const SPAN_LEVEL: log::Level = log::Level::Debug;
+
+    /// A logger of fields as key-values instead of in the message.
+    pub(crate) fn structured() -> Self {
+        Self {
+            structured: true,
+            ..Default::default()
+        }
+    }
+
+    fn current(&self) -> Option<Id> {
+        ENTERED.with(|entered| entered.borrow().last().cloned())
+    }
+}
+
+impl Subscriber for SpanLogger {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        if metadata.is_span() {
+            // Unless spans are logged they are not tracked at all, so that
+            // no lock is taken nor fields formatted.
+            return Self::SPAN_LEVEL <= log::max_level()
+                && metadata.target().starts_with("gastimator");
+        }
+        log::logger().enabled(
+            &log::Metadata::builder()
+                .level(log_level(metadata.level()))
+                .target(metadata.target())
+                .build(),
+        )
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        Some(match log::max_level() {
+            log::LevelFilter::Off => LevelFilter::OFF,
+            log::LevelFilter::Error => LevelFilter::ERROR,
+            log::LevelFilter::Warn => LevelFilter::WARN,
+            log::LevelFilter::Info => LevelFilter::INFO,
+            log::LevelFilter::Debug => LevelFilter::DEBUG,
+            log::LevelFilter::Trace => LevelFilter::TRACE,
+        })
+    }
+
+    fn new_span(&self, attributes: &Attributes<'_>) -> Id {
+        let id = Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1);
+        let parent = if attributes.is_contextual() {
+            self.current()
+        } else {
+            attributes.parent().cloned()
+        };
+        let mut formatter = FieldFormatter::default();
+        attributes.record(&mut formatter);
+        let mut spans = self.spans.lock().unwrap();
+        let name = attributes.metadata().name();
+        let path = match parent.and_then(|parent| spans.get(&parent)) {
+            Some(parent) => format!("{} > {name}", parent.path),
+            None => name.to_owned(),
+        };
+        spans.insert(
+            id.clone(),
+            OpenSpan {
+                metadata: attributes.metadata(),
+                path,
+                fields: formatter.fields,
+                values: formatter.values,
+                start: Instant::now(),
+                references: 1,
+            },
+        );
+        id
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        if let Some(open) = self.spans.lock().unwrap().get_mut(span) {
+            let mut formatter = FieldFormatter::default();
+            values.record(&mut formatter);
+            open.fields.push_str(&formatter.fields);
+            open.values.extend(formatter.values);
+        }
+    }
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let metadata = event.metadata();
+        let mut formatter = FieldFormatter::default();
+        event.record(&mut formatter);
+        let path = self
+            .current()
+            .and_then(|id| self.spans.lock().unwrap().get(&id).map(|s| s.path.clone()));
+        let mut record = log::Record::builder();
+        record
+            .level(log_level(metadata.level()))
+            .target(metadata.target())
+            .module_path(metadata.module_path())
+            .file(metadata.file())
+            .line(metadata.line());
+        if self.structured {
+            let key_values = key_values(path.as_deref(), &[], &formatter.values);
+            log::logger().log(
+                &record
+                    .args(format_args!("{}", formatter.message))
+                    .key_values(&key_values.as_slice())
+                    .build(),
+            );
+        } else {
+            let prefix = path.map(|path| format!("[{path}] ")).unwrap_or_default();
+            log::logger().log(
+                &record
+                    .args(format_args!(
+                        "{prefix}{}{}",
+                        formatter.message, formatter.fields
+                    ))
+                    .build(),
+            );
+        }
+    }
+
+    fn enter(&self, span: &Id) {
+        ENTERED.with(|entered| entered.borrow_mut().push(span.clone()));
+    }
+
+    fn exit(&self, span: &Id) {
+        ENTERED.with(|entered| {
+            let mut entered = entered.borrow_mut();
+            if let Some(index) = entered.iter().rposition(|id| id == span) {
+                entered.remove(index);
+            }
+        });
+    }
+
+    fn clone_span(&self, span: &Id) -> Id {
+        if let Some(open) = self.spans.lock().unwrap().get_mut(span) {
+            open.references += 1;
+        }
+        span.clone()
+    }
+
+    fn try_close(&self, span: Id) -> bool {
+        let mut spans = self.spans.lock().unwrap();
+        let Some(open) = spans.get_mut(&span) else {
+            return false;
+        };
+        open.references -= 1;
+        if open.references > 0 {
+            return false;
+        }
+        let open = spans.remove(&span).expect("checked above");
+        drop(spans);
+        let elapsed_ms = open.start.elapsed().as_secs_f64() * 1000.0;
+        if self.structured {
+            let key_values = key_values(
+                Some(&open.path),
+                &[("elapsed_ms", elapsed_ms.into())],
+                &open.values,
+            );
+            log::logger().log(
+                &log::Record::builder()
+                    .level(Self::SPAN_LEVEL)
+                    .target(open.metadata.target())
+                    .args(format_args!("{} took {elapsed_ms:.3} ms", open.path))
+                    .key_values(&key_values.as_slice())
+                    .build(),
+            );
+        } else {
+            log::log!(
+                target: open.metadata.target(),
+                Self::SPAN_LEVEL,
+                "{} took {elapsed_ms:.3} ms{}",
+                open.path,
+                open.fields
+            );
+        }
+        true
+    }
+
+    fn current_span(&self) -> Current {
+        match self.current().and_then(|id| {
+            self.spans
+                .lock()
+                .unwrap()
+                .get(&id)
+                .map(|s| (id, s.metadata))
+        }) {
+            Some((id, metadata)) => Current::new(id, metadata),
+            None => Current::none(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing::info_span;
+
+    use super::*;
+
+    type Sut = SpanLogger;
+
+    #[test]
+    fn nested_spans_have_path_and_recorded_fields() {
+        log::set_max_level(log::LevelFilter::Debug);
+        let sut = Sut::default();
+        tracing::subscriber::with_default(sut, || {
+            let outer =
+                info_span!(target: "gastimator", "estimate", branch = tracing::field::Empty);
+            let _outer = outer.enter();
+            tracing::Span::current().record("branch", "local");
+            let inner = info_span!(target: "gastimator", "compute_estimates");
+            let _inner = inner.enter();
+            tracing::dispatcher::get_default(|dispatch| {
+                let sut = dispatch.downcast_ref::<Sut>().unwrap();
+                let spans = sut.spans.lock().unwrap();
+                let paths = spans.values().map(|s| s.path.as_str()).collect::<Vec<_>>();
+                assert!(paths.contains(&"estimate > compute_estimates"));
+                let outer = spans.values().find(|s| s.path == "estimate").unwrap();
+                assert_eq!(outer.fields, " branch=local");
+                assert_eq!(
+                    outer.values,
+                    vec![("branch", FieldValue::Str("local".to_owned()))]
+                );
+            });
+        });
+    }
+
+    #[test]
+    fn field_values_are_typed() {
+        log::set_max_level(log::LevelFilter::Debug);
+        let sut = Sut::structured();
+        tracing::subscriber::with_default(sut, || {
+            let span =
+                info_span!(target: "gastimator", "estimate", cacheable = true, entries = 3u64);
+            let _span = span.enter();
+            tracing::dispatcher::get_default(|dispatch| {
+                let sut = dispatch.downcast_ref::<Sut>().unwrap();
+                let spans = sut.spans.lock().unwrap();
+                let span = spans.values().find(|s| s.path == "estimate").unwrap();
+                assert_eq!(
+                    span.values,
+                    vec![
+                        ("cacheable", FieldValue::Bool(true)),
+                        ("entries", FieldValue::U64(3))
+                    ]
+                );
+            });
+        });
+    }
+}
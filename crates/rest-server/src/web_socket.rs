@@ -0,0 +1,50 @@
+use crate::prelude::*;
+
+use axum::extract::ws::{Message, WebSocket};
+use futures_util::{SinkExt, StreamExt};
+
+/// The transaction of the WebSocket `message`, `None` for control messages,
+/// i.e. ping and pong, which are answered by the WebSocket itself.
+fn transaction(message: Message) -> Option<Result<Transaction>> {
+    let invalid = |e: serde_json::Error| Error::InvalidWebSocketMessage {
+        underlying: e.to_string(),
+    };
+    match message {
+        Message::Text(text) => Some(serde_json::from_str(&text).map_err(invalid)),
+        Message::Binary(bytes) => Some(serde_json::from_slice(&bytes).map_err(invalid)),
+        Message::Ping(_) | Message::Pong(_) | Message::Close(_) => None,
+    }
+}
+
+/// Estimates each transaction received on `socket`, answering each with a
+/// [`BatchGasEstimateItem`] in the same order, until the client closes the
+/// socket.
+///
+/// Estimates are computed as [`Gastimator::estimate_gas_stream`], which
+/// reads at most `worker_pool_size` transactions ahead, and no further
+/// messages are read while an answer is being sent, so that a client not
+/// reading its answers is slowed down rather than buffered for.
+pub(crate) async fn estimate_gas_web_socket(
+    socket: WebSocket,
+    options: EstimateOptions,
+    gastimator: Arc<Gastimator>,
+) {
+    let (mut sender, receiver) = socket.split();
+    let txs = receiver
+        .take_while(|message| {
+            let open = matches!(message, Ok(message) if !matches!(message, Message::Close(_)));
+            async move { open }
+        })
+        .filter_map(|message| async move { message.ok().and_then(transaction) });
+    let mut items = gastimator.estimate_gas_stream(txs, options).boxed();
+    while let Some(item) = items.next().await {
+        let json = serde_json::to_string(&BatchGasEstimateItem::from(item))
+            .expect("estimate items are always serializable");
+        if let Err(e) = sender.send(Message::Text(json.into())).await {
+            debug!("WebSocket closed while sending: {e}");
+            return;
+        }
+    }
+    // Answers the close of the client, if any, else closes the socket.
+    let _ = sender.close().await;
+}
@@ -82,10 +82,18 @@ impl Tester {
     async fn estimate(
         &self,
         input: &Transaction,
+    ) -> std::result::Result<GasEstimateResponse, String> {
+        self.estimate_with_query(input, "").await
+    }
+
+    async fn estimate_with_query(
+        &self,
+        input: &Transaction,
+        query: &str,
     ) -> std::result::Result<GasEstimateResponse, String> {
         let response = self
             .client
-            .post(format!("{}/tx", self.url))
+            .post(format!("{}/tx{}", self.url, query))
             .json(&input)
             .send()
             .await
@@ -108,6 +116,29 @@ impl Tester {
         Ok(model)
     }
 
+    async fn estimate_batch(&self, input: &[Transaction]) -> Vec<BatchGasEstimateItem> {
+        self.client
+            .post(format!("{}/tx/batch", self.url))
+            .json(&input)
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap()
+    }
+
+    async fn get_status(&self, path: &str) -> (u16, ServiceStatusResponse) {
+        let response = self
+            .client
+            .get(format!("{}{}", self.url, path))
+            .send()
+            .await
+            .unwrap();
+        let status = response.status().as_u16();
+        (status, response.json().await.unwrap())
+    }
+
     fn _cleanup(self) {
         // Cleanup: Abort the server task
         self.server_handle.abort();
@@ -205,12 +236,39 @@ async fn erc20_token_transfer_dai() {
                 },
                 low: 22640.into(),
                 high: 35090.into(), // off - actual gas is: 29,930
+                recommended: 31978.into(),
             }
         );
     })
     .await;
 }
 
+#[tokio::test]
+async fn raw_remote_response() {
+    // ARRANGE
+    // https://etherscan.io/tx/0xd8cb17599010751c7b3a2fb8db54f18c084e47b75ce3ea6a0bf2f067e730bc75
+    let input = &Transaction::from_rlp(
+        "0x02f8af011e84054e0840845003d67a82953f946b175474e89094c44da98b954eedeac495271d0f80b844a9059cbb00000000000000000000000080974f1fa51d9aad25ae0b857dd58ee0569a9b2b0000000000000000000000000000000000000000000000000304a509418a0225c001a0abb53cd56cc7162acbed7bf8193e7e31d5751b16e4bf5bd45b951906ec7206b9a0543ead33208796e51d2a68c81a4c78565047f3300c969677655d478f78d6c5d1",
+    );
+    Tester::test(|tester| async move {
+        // ACT
+        let response = tester
+            .estimate_with_query(input, "?raw_remote=true")
+            .await
+            .unwrap();
+
+        // ASSERT
+        let raw = response.raw_remote_response().as_ref().unwrap();
+        let parsed: RpcResponse = serde_json::from_str(raw).unwrap();
+        let remote_gas = parsed.result.to_u64().unwrap();
+        let GasUsage::EstimateWithRange { high, .. } = response.gas_usage() else {
+            panic!("Expected a range estimate");
+        };
+        pretty_assertions::assert_eq!(Gas::from(remote_gas), *high);
+    })
+    .await;
+}
+
 #[tokio::test]
 async fn call_contract_1inch() {
     // ARRANGE
@@ -253,10 +311,12 @@ async fn call_data_limit_too_low() {
         // ACT
         let result = tester.estimate(input).await;
         // ASSERT
-        assert!(result.err().unwrap().contains(&format!(
-            "GasExceedsLimit {{ estimated_cost: Some(Gas(24648)), gas_limit: Gas({}) }}",
-            gas_limit
-        )));
+        let error: ErrorResponse = serde_json::from_str(&result.unwrap_err()).unwrap();
+        assert_eq!(error.error(), "GasExceedsLimit");
+        assert_eq!(
+            error.details(),
+            &serde_json::json!({ "estimated_cost": 24648, "gas_limit": *gas_limit })
+        );
     })
     .await;
 }
@@ -270,29 +330,93 @@ async fn transfer_limit_too_low() {
         // ACT
         let result = tester.estimate(input).await;
         // ASSERT
-        assert!(result.err().unwrap().contains(&format!(
-            "GasExceedsLimit {{ estimated_cost: Some(Gas(21000)), gas_limit: Gas({}) }}",
-            gas_limit
-        )));
+        let error: ErrorResponse = serde_json::from_str(&result.unwrap_err()).unwrap();
+        assert_eq!(error.error(), "GasExceedsLimit");
+        assert_eq!(
+            error.details(),
+            &serde_json::json!({ "estimated_cost": 21000, "gas_limit": *gas_limit })
+        );
     })
     .await;
 }
 
 #[tokio::test]
-async fn GIVEN__cached_response__WHEN__cache_hit__THEN__response_time_is_fast() {
+async fn GIVEN__cached_response__WHEN__cache_hit__THEN__response_is_from_cache() {
     // ARRANGE
     Tester::test(|tester| async move {
         // ACT
-        let input = &Transaction::sample_native_token_transfer_cachable();
-        let first = tester.estimate(input).await;
-        let second = tester.estimate(input).await;
+        // native token transfers are exact, thus never cached
+        let input = &Transaction::sample_contract_creation_cachable();
+        let first = tester.estimate(input).await.unwrap();
+        let second = tester.estimate(input).await.unwrap();
+
+        // ASSERT
+        assert!(!first.from_cache());
+        assert!(second.from_cache());
+        assert_eq!(second.source(), EstimateSource::Cached);
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn health() {
+    Tester::test(|tester| async move {
+        let (status, response) = tester.get_status("/health").await;
+        assert_eq!(status, 200);
+        assert_eq!(*response.status(), ServiceStatus::Ok);
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn ready_after_warm_up() {
+    Tester::test(|tester| async move {
+        let mut ready = false;
+        for _ in 0..50 {
+            let (status, response) = tester.get_status("/ready?ping=true").await;
+            if status == 200 {
+                assert_eq!(*response.status(), ServiceStatus::Ready);
+                ready = true;
+                break;
+            }
+            assert_eq!(status, 503);
+            assert_eq!(*response.status(), ServiceStatus::NotReady);
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        assert!(ready, "Should become ready after warm-up");
+    })
+    .await;
+}
 
-        let time_first = first.as_ref().unwrap().time_elapsed_in_millis();
-        let time_second = second.as_ref().unwrap().time_elapsed_in_millis();
+#[tokio::test]
+async fn batch_with_partial_failure() {
+    // ARRANGE
+    let input = &[
+        Transaction::sample_native_token_transfer(),
+        Transaction::sample_native_token_transfer_gas_limit(Gas::from(10)),
+    ];
+    Tester::test(|tester| async move {
+        // ACT
+        let response = tester.estimate_batch(input).await;
 
         // ASSERT
-        // actually Rust is so fast that both times are 0
-        assert!(time_second <= time_first);
+        assert_eq!(response.len(), 2);
+        let BatchGasEstimateItem::Estimate(first) = &response[0] else {
+            panic!("Expected estimate, got: {:?}", response[0]);
+        };
+        pretty_assertions::assert_eq!(
+            *first.gas_usage(),
+            GasUsage::Exact {
+                kind: TransactionKind::NativeTokenTransfer,
+                gas: 21_000.into()
+            }
+        );
+        assert_eq!(
+            response[1],
+            BatchGasEstimateItem::Error {
+                error: "Gas exceeds limit".to_owned()
+            }
+        );
     })
     .await;
 }